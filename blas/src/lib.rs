@@ -0,0 +1,173 @@
+//CBLAS and Fortran BLAS symbols for gemm, built on momms::gemm.
+//The library can be linked in place of a BLAS, or preloaded under a program that already uses one:
+//    LD_PRELOAD=target/release/libmomms_blas.so ./program
+
+extern crate libc;
+extern crate momms;
+
+use std::{slice, process};
+use std::panic::{self, AssertUnwindSafe};
+use libc::{c_char, c_int};
+use momms::matrix::{MatrixRef, MatrixMut};
+use momms::{gemm, Transpose, GemmScalar};
+
+#[cfg(not(feature="ilp64"))]
+pub type BlasInt = i32;
+#[cfg(feature="ilp64")]
+pub type BlasInt = i64;
+
+//Values of the CBLAS_LAYOUT and CBLAS_TRANSPOSE enums
+const CBLAS_ROW_MAJOR: c_int = 101;
+const CBLAS_COL_MAJOR: c_int = 102;
+const CBLAS_NO_TRANS: c_int = 111;
+const CBLAS_TRANS: c_int = 112;
+const CBLAS_CONJ_TRANS: c_int = 113;
+
+//Reports an illegal argument the way the reference xerbla does.
+//pos counts the arguments of the Fortran interface, or of the CBLAS one for cblas_ routines.
+fn xerbla(name: &str, pos: usize) {
+    eprintln!(" ** On entry to {} parameter number {} had an illegal value", name, pos);
+}
+
+fn fortran_trans(trans: c_char) -> Option<Transpose> {
+    match trans as u8 {
+        b'N' | b'n' => Some(Transpose::NoTrans),
+        b'T' | b't' => Some(Transpose::Trans),
+        b'C' | b'c' => Some(Transpose::ConjTrans),
+        _ => None,
+    }
+}
+
+fn cblas_trans(trans: c_int) -> Option<Transpose> {
+    match trans {
+        CBLAS_NO_TRANS => Some(Transpose::NoTrans),
+        CBLAS_TRANS => Some(Transpose::Trans),
+        CBLAS_CONJ_TRANS => Some(Transpose::ConjTrans),
+        _ => None,
+    }
+}
+
+//Shape (height, width) of X as stored, when op(X) is h x w
+fn stored_shape(trans: Transpose, h: usize, w: usize) -> (usize, usize) {
+    if trans == Transpose::NoTrans { (h, w) } else { (w, h) }
+}
+
+//Number of elements spanned by an h x w matrix with the given strides
+fn span(h: usize, w: usize, row_stride: usize, column_stride: usize) -> usize {
+    if h == 0 || w == 0 { 0 } else { (h-1) * row_stride + (w-1) * column_stride + 1 }
+}
+
+unsafe fn as_slice<'a, T>(p: *const T, len: usize) -> &'a [T] {
+    if len == 0 { &[] } else { slice::from_raw_parts(p, len) }
+}
+
+unsafe fn as_mut_slice<'a, T>(p: *mut T, len: usize) -> &'a mut [T] {
+    if len == 0 { &mut [] } else { slice::from_raw_parts_mut(p, len) }
+}
+
+//C = alpha op(A) op(B) + beta C where every matrix is column major (row_major = false) or row major.
+//Arguments are assumed to be checked.
+//A panic can't unwind into the C or Fortran caller, so it aborts the process once the panic is reported.
+unsafe fn gemm_ld<T: GemmScalar>(row_major: bool, transa: Transpose, transb: Transpose,
+                                 m: usize, n: usize, k: usize, alpha: T,
+                                 a: *const T, lda: usize, b: *const T, ldb: usize,
+                                 beta: T, c: *mut T, ldc: usize) {
+    //(row stride, column stride) of a matrix with leading dimension ld
+    let strides = |ld: usize| if row_major { (ld, 1) } else { (1, ld) };
+
+    let (a_h, a_w) = stored_shape(transa, m, k);
+    let (b_h, b_w) = stored_shape(transb, k, n);
+    let (a_rs, a_cs) = strides(lda);
+    let (b_rs, b_cs) = strides(ldb);
+    let (c_rs, c_cs) = strides(ldc);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let a = MatrixRef::new(as_slice(a, span(a_h, a_w, a_rs, a_cs)), a_h, a_w, a_rs, a_cs);
+        let b = MatrixRef::new(as_slice(b, span(b_h, b_w, b_rs, b_cs)), b_h, b_w, b_rs, b_cs);
+        let mut c = MatrixMut::new(as_mut_slice(c, span(m, n, c_rs, c_cs)), m, n, c_rs, c_cs);
+        gemm(transa, transb, alpha, &a, &b, beta, &mut c).expect("gemm shapes are derived from m, n and k");
+    }));
+    if result.is_err() {
+        process::abort();
+    }
+}
+
+//Arguments of xGEMM that can be illegal
+#[derive(Copy, Clone)]
+enum Arg { TransA, TransB, M, N, K, Lda, Ldb, Ldc }
+impl Arg {
+    fn fortran_position(self) -> usize {
+        match self { Arg::TransA => 1, Arg::TransB => 2, Arg::M => 3, Arg::N => 4, Arg::K => 5,
+                     Arg::Lda => 8, Arg::Ldb => 10, Arg::Ldc => 13 }
+    }
+    //CBLAS has the layout as its first argument
+    fn cblas_position(self) -> usize { self.fortran_position() + 1 }
+}
+
+//Argument checks of the reference xGEMM. The leading dimensions must cover a column
+//of a column major matrix, or a row of a row major one.
+fn check_args(row_major: bool, transa: Option<Transpose>, transb: Option<Transpose>,
+              m: BlasInt, n: BlasInt, k: BlasInt, lda: BlasInt, ldb: BlasInt, ldc: BlasInt) -> Option<Arg> {
+    let (transa, transb) = match (transa, transb) {
+        (None, _) => return Some(Arg::TransA),
+        (_, None) => return Some(Arg::TransB),
+        (Some(ta), Some(tb)) => (ta, tb),
+    };
+    if m < 0 { return Some(Arg::M); }
+    if n < 0 { return Some(Arg::N); }
+    if k < 0 { return Some(Arg::K); }
+    let leading = |(h, w): (BlasInt, BlasInt)| if row_major { w.max(1) } else { h.max(1) };
+    let a_shape = if transa == Transpose::NoTrans { (m, k) } else { (k, m) };
+    let b_shape = if transb == Transpose::NoTrans { (k, n) } else { (n, k) };
+    if lda < leading(a_shape) { return Some(Arg::Lda); }
+    if ldb < leading(b_shape) { return Some(Arg::Ldb); }
+    if ldc < leading((m, n)) { return Some(Arg::Ldc); }
+    None
+}
+
+macro_rules! fortran_gemm {
+    ($name: ident, $t: ty, $routine: expr) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(transa: *const c_char, transb: *const c_char,
+                                       m: *const BlasInt, n: *const BlasInt, k: *const BlasInt,
+                                       alpha: *const $t, a: *const $t, lda: *const BlasInt,
+                                       b: *const $t, ldb: *const BlasInt,
+                                       beta: *const $t, c: *mut $t, ldc: *const BlasInt) {
+            let (ta, tb) = (fortran_trans(*transa), fortran_trans(*transb));
+            if let Some(arg) = check_args(false, ta, tb, *m, *n, *k, *lda, *ldb, *ldc) {
+                xerbla($routine, arg.fortran_position());
+                return;
+            }
+            gemm_ld(false, ta.unwrap(), tb.unwrap(), *m as usize, *n as usize, *k as usize,
+                    *alpha, a, *lda as usize, b, *ldb as usize, *beta, c, *ldc as usize);
+        }
+    }
+}
+fortran_gemm!(dgemm_, f64, "DGEMM ");
+fortran_gemm!(sgemm_, f32, "SGEMM ");
+
+macro_rules! cblas_gemm {
+    ($name: ident, $t: ty, $routine: expr) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(layout: c_int, transa: c_int, transb: c_int,
+                                       m: BlasInt, n: BlasInt, k: BlasInt,
+                                       alpha: $t, a: *const $t, lda: BlasInt,
+                                       b: *const $t, ldb: BlasInt,
+                                       beta: $t, c: *mut $t, ldc: BlasInt) {
+            let (ta, tb) = (cblas_trans(transa), cblas_trans(transb));
+            let pos = match layout {
+                CBLAS_ROW_MAJOR | CBLAS_COL_MAJOR =>
+                    check_args(layout == CBLAS_ROW_MAJOR, ta, tb, m, n, k, lda, ldb, ldc).map(Arg::cblas_position),
+                _ => Some(1),
+            };
+            if let Some(pos) = pos {
+                xerbla($routine, pos);
+                return;
+            }
+            gemm_ld(layout == CBLAS_ROW_MAJOR, ta.unwrap(), tb.unwrap(), m as usize, n as usize, k as usize,
+                    alpha, a, lda as usize, b, ldb as usize, beta, c, ldc as usize);
+        }
+    }
+}
+cblas_gemm!(cblas_dgemm, f64, "cblas_dgemm");
+cblas_gemm!(cblas_sgemm, f32, "cblas_sgemm");
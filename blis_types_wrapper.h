@@ -1,2 +0,0 @@
-// #include <bli_type_defs.h>
-#include <blis.h>
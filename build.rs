@@ -1,20 +1,6 @@
-extern crate bindgen;
 extern crate dirs;
 
 use std::{env, process::Command, path::Path};
-use bindgen::callbacks::{MacroParsingBehavior, ParseCallbacks};
-
-
- #[derive(Debug)]
- struct MacroCallback { }
- impl ParseCallbacks for MacroCallback {
-    fn will_parse_macro(&self, name: &str) -> MacroParsingBehavior {
-        if ["FP_SUBNORMAL", "FP_NORMAL", "FP_ZERO", "FP_INFINITE", "FP_NAN"].contains(&name) {
-            return MacroParsingBehavior::Ignore
-        }
-        MacroParsingBehavior::Default
-     }
- }
 
 fn main() -> () {
     let out_dir = env::var("OUT_DIR").unwrap();
@@ -29,25 +15,6 @@ fn main() -> () {
 //        println!("cargo:rustc-link-search=native=/usr/lib/gcc/x86_64-linux-gnu/5");
 //        println!("cargo:rustc-link-lib=dylib=gomp");
 
-        //Use bindgen to create bindings to BLIS's typedefs
-        //This allows us to interface with the BLIS micro-kernel
-		let bindings = bindgen::Builder::default()
-			.header("blis_types_wrapper.h")
-            .clang_arg("-include")
-            .clang_arg("stddef.h")
-            .clang_arg(format!("-I/{}/blis/include/blis", dirs::home_dir().unwrap().to_str().unwrap()))
-            .parse_callbacks(Box::new(MacroCallback{ }))
-            .whitelist_type("pack_t*")
-            .whitelist_type("auxinfo_t")
-			.generate()
-			.expect("Unable to generate bindings");
-
-        // Write the bindings to the $OUT_DIR/bindings.rs file.
-    	let out_path = std::path::PathBuf::from(&out_dir);
-    	bindings
-			.write_to_file(out_path.join("bindings.rs"))
-			.expect("Couldn't write bindings!");
-
         //
         // Compile knm "micro-kernel" standalone
         // via: icc -I${HOME}/blis/include/blis -march=knm -O3 -std=c11 -c sgemm_knm_int_24x16.c -o sgemm_knm_int_24x16.o
@@ -70,34 +37,6 @@ fn main() -> () {
             println!("cargo:rustc-link-lib=static=ukernel");
         }
 
-        if cfg!(feature="hsw"){
-            Command::new("icc").arg(&format!("-I{}/blis/include/blis", dirs::home_dir().unwrap().to_str().unwrap()))
-                               .args(&["-march=hsw", "-O3", "-std=c11", "-fPIC", "-c", "ukernels/bli_gemm_haswell_asm_d12x4.c", "-o"])
-                               .arg(&format!("{}/bli_gemm_haswell_asm_d12x4.o", &out_dir))
-                               .status().unwrap();
-
-            Command::new("icc").arg(&format!("-I{}/blis/include/blis", dirs::home_dir().unwrap().to_str().unwrap()))
-                               .args(&["-march=hsw", "-O3", "-std=c11", "-fPIC", "-c", "ukernels/bli_gemm_haswell_asm_d4x12.c", "-o"])
-                               .arg(&format!("{}/bli_gemm_haswell_asm_d4x12.o", &out_dir))
-                               .status().unwrap();
-
-            Command::new("icc").arg(&format!("-I{}/blis/include/blis", dirs::home_dir().unwrap().to_str().unwrap()))
-                               .args(&["-march=hsw", "-O3", "-std=c11", "-fPIC", "-c", "ukernels/bli_gemm_haswell_asm_d8x6.c", "-o"])
-                               .arg(&format!("{}/bli_gemm_haswell_asm_d8x6.o", &out_dir))
-                               .status().unwrap();
-
-            Command::new("icc").arg(&format!("-I{}/blis/include/blis", dirs::home_dir().unwrap().to_str().unwrap()))
-                               .args(&["-march=hsw", "-O3", "-std=c11", "-fPIC", "-c", "ukernels/bli_gemm_haswell_asm_d6x8.c", "-o"])
-                               .arg(&format!("{}/bli_gemm_haswell_asm_d6x8.o", &out_dir))
-                               .status().unwrap();
-
-            Command::new("ar").args(&["crus", "libukernel.a", "bli_gemm_haswell_asm_d12x4.o", "bli_gemm_haswell_asm_d4x12.o", "bli_gemm_haswell_asm_d6x8.o", "bli_gemm_haswell_asm_d8x6.o"])
-                              .current_dir(&Path::new(&out_dir))
-                              .status().unwrap();
-            println!("cargo:rustc-link-search=native={}", &out_dir);
-            println!("cargo:rustc-link-lib=static=ukernel");
-        }
-
         //Needed for linking with BLIS when it was compiled with icc
 //        println!("cargo:rustc-link-lib=static=irc");
         
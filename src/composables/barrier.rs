@@ -1,6 +1,6 @@
 use matrix::{Scalar,Mat};
 use thread_comm::ThreadInfo;
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,NodeDescription};
 use core::marker::PhantomData;
 
 pub struct Barrier<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S: GemmNode<T, At, Bt, Ct>> {
@@ -24,4 +24,7 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S: GemmNode<T, At, Bt, Ct>>
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     }
+    fn describe() -> NodeDescription {
+        NodeDescription::new("Barrier", "barrier".to_string()).then(S::describe())
+    }
 }
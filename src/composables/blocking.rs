@@ -0,0 +1,156 @@
+use core::mem;
+use std::fs;
+use composables::{TreeBuilder,Nway,CacheSizes};
+use composables::planner::round_down;
+use MommsError;
+
+//One level of data cache, as the analytical model sees it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CacheLevel {
+    //In bytes
+    pub size: usize,
+    //Ways of associativity; a fully associative cache has size / line_size ways
+    pub ways: usize,
+    pub line_size: usize,
+}
+impl CacheLevel {
+    pub fn sets(&self) -> usize {
+        match self.try_sets() {
+            Ok(sets) => sets,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    //Fails for a level without ways or with empty lines, which can't hold anything
+    pub fn try_sets(&self) -> Result<usize, MommsError> {
+        match self.ways.checked_mul(self.line_size) {
+            Some(way_line) if way_line > 0 => Ok((self.size / way_line).max(1)),
+            _ => Err(MommsError::UnknownCacheTopology{ reason: "a cache level needs at least one way and a nonzero line size" }),
+        }
+    }
+}
+
+//The data and unified caches seen by a core, from L1 outwards
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheTopology {
+    pub levels: Vec<CacheLevel>,
+}
+
+//Block sizes of Goto's algorithm for one Mr x Nr kernel
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BlockingPlan {
+    pub mr: usize,
+    pub nr: usize,
+    pub kc: usize,
+    pub mc: usize,
+    pub nc: usize,
+}
+impl BlockingPlan {
+    //Goto's algorithm with these block sizes, parallelized in the jr loop
+    pub fn goto(&self, n_threads: usize) -> TreeBuilder {
+        TreeBuilder::new().spawn(n_threads).part_n(self.nc).part_k(self.kc).pack_b(self.nr)
+            .part_m(self.mc).pack_a(self.mr).parallel_n(self.nr, Nway::TheRest).kernel_nm(self.nr, self.mr)
+    }
+}
+
+//Sizes in sysfs are in bytes, or suffixed with K or M
+fn parse_size(text: &str) -> Option<usize> {
+    let text = text.trim();
+    let (digits, scale) = match text.chars().last() {
+        Some('K') => (&text[..text.len()-1], 1024),
+        Some('M') => (&text[..text.len()-1], 1024 * 1024),
+        _ => (text, 1),
+    };
+    digits.parse::<usize>().ok().map(|n| n * scale)
+}
+
+fn read_sysfs(dir: &str, name: &str) -> Option<String> {
+    fs::read_to_string(format!("{}/{}", dir, name)).ok()
+}
+
+//Ways of the cache a block of bytes occupies, when it is contiguous
+fn ways_for(bytes: usize, level: &CacheLevel) -> usize {
+    let way_size = level.sets() * level.line_size;
+    (bytes + way_size - 1) / way_size
+}
+
+impl Default for CacheTopology {
+    //The caches of CacheSizes::default(), with the associativities of a Haswell core
+    fn default() -> CacheTopology {
+        CacheTopology{ levels: vec![
+            CacheLevel{ size: 32 * 1024, ways: 8, line_size: 64 },
+            CacheLevel{ size: 256 * 1024, ways: 8, line_size: 64 },
+            CacheLevel{ size: 8 * 1024 * 1024, ways: 16, line_size: 64 }] }
+    }
+}
+
+impl CacheTopology {
+    //The caches of cpu 0, from /sys/devices/system/cpu
+    pub fn detect() -> Result<CacheTopology, MommsError> {
+        let mut levels: Vec<(usize, CacheLevel)> = Vec::new();
+        for index in 0.. {
+            let dir = format!("/sys/devices/system/cpu/cpu0/cache/index{}", index);
+            let kind = match read_sysfs(&dir, "type") {
+                Some(kind) => kind,
+                None => break,
+            };
+            if kind.trim() == "Instruction" { continue; }
+            let field = |name: &str| read_sysfs(&dir, name).and_then(|s| s.trim().parse::<usize>().ok());
+            let size = read_sysfs(&dir, "size").and_then(|s| parse_size(&s));
+            match (field("level"), size, field("coherency_line_size")) {
+                (Some(level), Some(size), Some(line_size)) if size > 0 && line_size > 0 => {
+                    //Zero ways means the cache is fully associative
+                    let ways = match field("ways_of_associativity") {
+                        Some(0) | None => size / line_size,
+                        Some(ways) => ways,
+                    };
+                    levels.push((level, CacheLevel{ size: size, ways: ways, line_size: line_size }));
+                },
+                _ => return Err(MommsError::UnknownCacheTopology{ reason: "a cache in sysfs is missing its level, size or line size" }),
+            }
+        }
+        if levels.is_empty() {
+            return Err(MommsError::UnknownCacheTopology{ reason: "no caches listed in /sys/devices/system/cpu/cpu0/cache" });
+        }
+        levels.sort_by_key(|&(level, _)| level);
+        Ok(CacheTopology{ levels: levels.into_iter().map(|(_, cache)| cache).collect() })
+    }
+
+    //The sizes the Planner derives its block sizes from. Missing levels keep the defaults.
+    pub fn cache_sizes(&self) -> CacheSizes {
+        let default = CacheSizes::default();
+        let size = |i: usize, default: usize| self.levels.get(i).map_or(default, |level| level.size);
+        CacheSizes{ l1: size(0, default.l1), l2: size(1, default.l2), l3: size(2, default.l3) }
+    }
+
+    //Block sizes for an mr x nr kernel, following Low et al., "Analytical Modeling Is Enough for High-Performance BLIS".
+    //Each level keeps one block resident in some of its ways, leaves one way for the streamed operand,
+    //and gives the rest to the blocks of the level inside it:
+    //    L1: an mr x kc micro-panel of A, with a kc x nr micro-panel of B streaming through
+    //    L2: the mc x kc block of A, with the micro-panel of B in its own ways
+    //    L3: the kc x nc panel of B, with the block of A in its own ways
+    pub fn blocking_plan<T>(&self, mr: usize, nr: usize) -> Result<BlockingPlan, MommsError> {
+        if self.levels.len() < 3 {
+            return Err(MommsError::UnknownCacheTopology{ reason: "the analytical model needs three levels of data cache" });
+        }
+        if mr == 0 || nr == 0 {
+            return Err(MommsError::InvalidKernelShape{ mr: mr, nr: nr });
+        }
+        for level in &self.levels[..3] {
+            level.try_sets()?;
+        }
+        let elem = mem::size_of::<T>();
+        let (l1, l2, l3) = (&self.levels[0], &self.levels[1], &self.levels[2]);
+
+        //The micro-panels of A and B take ways of L1 in the ratio mr : nr
+        let a_ways_l1 = ((l1.ways.saturating_sub(1)) as f64 / (1.0 + nr as f64 / mr as f64)).floor().max(1.0) as usize;
+        let kc = (a_ways_l1 * l1.sets() * l1.line_size / (mr * elem)).max(1);
+
+        let a_ways_l2 = l2.ways.saturating_sub(1 + ways_for(kc * nr * elem, l2)).max(1);
+        let mc = round_down(a_ways_l2 * l2.sets() * l2.line_size / (kc * elem), mr);
+
+        let b_ways_l3 = l3.ways.saturating_sub(1 + ways_for(mc * kc * elem, l3)).max(1);
+        let nc = round_down(b_ways_l3 * l3.sets() * l3.line_size / (kc * elem), nr);
+
+        Ok(BlockingPlan{ mr: mr, nr: nr, kc: kc, mc: mc, nc: nc })
+    }
+}
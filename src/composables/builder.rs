@@ -0,0 +1,284 @@
+use matrix::{Scalar,Mat,ResizableBuffer,Matrix,RowPanelMatrix,ColumnPanelMatrix,Traced};
+use composables::{GemmNode,DynNode,Static,TripleLoop,
+    DynPartM,DynPartN,DynPartK,DynPackA,DynPackB,DynParallelM,DynParallelN,Nway,DynSpawnThreads};
+use kern::{KernelNM,KernelMN};
+use typenum::{Unsigned,U4,U6,U8,U12,U14,U16,U24,U32};
+use MommsError;
+
+//One node of a runtime-built control tree
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NodeSpec {
+    //Number of threads
+    SpawnThreads(usize),
+    //Block size
+    PartM(usize),
+    PartN(usize),
+    PartK(usize),
+    //Iota and how many ways to split
+    ParallelM(usize, Nway),
+    ParallelN(usize, Nway),
+    //Height of the row panels A is packed into
+    PackA(usize),
+    //Width of the column panels B is packed into
+    PackB(usize),
+    KernelNM{ nr: usize, mr: usize },
+    //Like KernelNM, but loops over m outside of n
+    KernelMN{ mr: usize, nr: usize },
+    TripleLoop,
+}
+impl NodeSpec {
+    fn is_leaf(&self) -> bool {
+        match *self {
+            NodeSpec::KernelNM{..} | NodeSpec::KernelMN{..} | NodeSpec::TripleLoop => true,
+            _ => false,
+        }
+    }
+}
+
+//Describes a control tree from the root down and builds it.
+//The Goto algorithm with 4x12 kernels is
+//    TreeBuilder::new().spawn(4).part_n(3000).part_k(192).pack_b(12).part_m(120).pack_a(4)
+//        .parallel_n(12, Nway::TheRest).kernel_nm(12, 4).build::<f64>()
+#[derive(Clone, Debug, Default)]
+pub struct TreeBuilder {
+    specs: Vec<NodeSpec>,
+}
+impl TreeBuilder {
+    pub fn new() -> TreeBuilder { TreeBuilder{ specs: Vec::new() } }
+    pub fn push(mut self, spec: NodeSpec) -> TreeBuilder {
+        self.specs.push(spec);
+        self
+    }
+    pub fn spawn(self, n_threads: usize) -> TreeBuilder { self.push(NodeSpec::SpawnThreads(n_threads)) }
+    pub fn part_m(self, bsz: usize) -> TreeBuilder { self.push(NodeSpec::PartM(bsz)) }
+    pub fn part_n(self, bsz: usize) -> TreeBuilder { self.push(NodeSpec::PartN(bsz)) }
+    pub fn part_k(self, bsz: usize) -> TreeBuilder { self.push(NodeSpec::PartK(bsz)) }
+    pub fn parallel_m(self, iota: usize, nway: Nway) -> TreeBuilder { self.push(NodeSpec::ParallelM(iota, nway)) }
+    pub fn parallel_n(self, iota: usize, nway: Nway) -> TreeBuilder { self.push(NodeSpec::ParallelN(iota, nway)) }
+    pub fn pack_a(self, mr: usize) -> TreeBuilder { self.push(NodeSpec::PackA(mr)) }
+    pub fn pack_b(self, nr: usize) -> TreeBuilder { self.push(NodeSpec::PackB(nr)) }
+    pub fn kernel_nm(self, nr: usize, mr: usize) -> TreeBuilder { self.push(NodeSpec::KernelNM{ nr: nr, mr: mr }) }
+    pub fn kernel_mn(self, mr: usize, nr: usize) -> TreeBuilder { self.push(NodeSpec::KernelMN{ mr: mr, nr: nr }) }
+    pub fn triple_loop(self) -> TreeBuilder { self.push(NodeSpec::TripleLoop) }
+
+    pub fn specs(&self) -> &[NodeSpec] { &self.specs }
+
+    //Checks the description and builds the tree
+    pub fn build<T: DynScalar>(&self) -> Result<DynNode<T, Matrix<T>, Matrix<T>, Matrix<T>>, MommsError> {
+        let (mr, nr) = self.validate(&T::kernel_shapes())?;
+        Ok(T::build_shape(&self.specs, mr, nr).expect("validated shapes have a kernel"))
+    }
+
+    //Like build, but for Traced matrices, so the tree can run through a CacheSim
+    pub fn build_traced<T: DynScalar>(&self) -> Result<DynNode<T, Traced<Matrix<T>>, Traced<Matrix<T>>, Traced<Matrix<T>>>, MommsError> {
+        let (mr, nr) = self.validate(&T::kernel_shapes())?;
+        Ok(T::build_traced_shape(&self.specs, mr, nr).expect("validated shapes have a kernel"))
+    }
+
+    //Returns the (Mr, Nr) the tree is instantiated with.
+    //Without any shapes, the tree only has to be consistent, and unfixed sizes are returned as 0.
+    pub(crate) fn validate(&self, shapes: &[(usize, usize)]) -> Result<(usize, usize), MommsError> {
+        let err = |node, reason| Err(MommsError::InvalidControlTree{ node: node, reason: reason });
+        let leaf = match self.specs.iter().position(NodeSpec::is_leaf) {
+            Some(leaf) => leaf,
+            None => return err(self.specs.len(), "the tree must end in KernelNM, KernelMN or TripleLoop"),
+        };
+        if leaf + 1 < self.specs.len() {
+            return err(leaf + 1, "no node may follow the leaf");
+        }
+
+        //Each of Mr and Nr is fixed by the kernel or by the packed formats, and all must agree
+        let (mut mr, mut nr) = (None, None);
+        let (mut a_packed, mut b_packed) = (false, false);
+        //Threads the parallelizers below this node have yet to split among them.
+        //Without SpawnThreads the tree may run on any number of threads, so only a tree that spawns is checked.
+        let checks_threads = self.specs.iter().any(|spec| match *spec { NodeSpec::SpawnThreads(_) => true, _ => false });
+        let (mut spawned, mut threads) = (false, 1);
+        for (i, spec) in self.specs.iter().enumerate() {
+            let positive = match *spec {
+                NodeSpec::SpawnThreads(x) | NodeSpec::PartM(x) | NodeSpec::PartN(x) | NodeSpec::PartK(x) |
+                NodeSpec::PackA(x) | NodeSpec::PackB(x) => x > 0,
+                NodeSpec::ParallelM(iota, nway) | NodeSpec::ParallelN(iota, nway) =>
+                    iota > 0 && nway != Nway::Target(0),
+                NodeSpec::KernelNM{ nr, mr } | NodeSpec::KernelMN{ mr, nr } => nr > 0 && mr > 0,
+                NodeSpec::TripleLoop => true,
+            };
+            if !positive {
+                return err(i, "block sizes, iotas, thread counts and kernel sizes must be positive");
+            }
+            let (spec_mr, spec_nr) = match *spec {
+                NodeSpec::PackA(x) => { a_packed = true; (Some(x), None) },
+                NodeSpec::PackB(x) => { b_packed = true; (None, Some(x)) },
+                NodeSpec::KernelNM{ nr, mr } | NodeSpec::KernelMN{ mr, nr } => {
+                    if !a_packed || !b_packed {
+                        return err(i, "the kernel needs A packed by PackA and B packed by PackB");
+                    }
+                    (Some(mr), Some(nr))
+                },
+                _ => (None, None),
+            };
+            if spec_mr.is_some() && mr.is_some() && spec_mr != mr {
+                return err(i, "the height of the row panels of A differs from Mr");
+            }
+            if spec_nr.is_some() && nr.is_some() && spec_nr != nr {
+                return err(i, "the width of the column panels of B differs from Nr");
+            }
+            mr = mr.or(spec_mr);
+            nr = nr.or(spec_nr);
+
+            //Below the packing nodes, partitions must start on panel boundaries
+            let misaligned = match *spec {
+                NodeSpec::PartM(x) | NodeSpec::ParallelM(x, _) => a_packed && x % mr.unwrap() != 0,
+                NodeSpec::PartN(x) | NodeSpec::ParallelN(x, _) => b_packed && x % nr.unwrap() != 0,
+                _ => false,
+            };
+            if misaligned {
+                return err(i, "block sizes and iotas below PackA or PackB must be multiples of the panel size");
+            }
+
+            match *spec {
+                NodeSpec::SpawnThreads(n_threads) => {
+                    if spawned {
+                        return err(i, "SpawnThreads can't be nested");
+                    }
+                    spawned = true;
+                    threads = n_threads;
+                },
+                NodeSpec::ParallelM(_, nway) | NodeSpec::ParallelN(_, nway) if checks_threads => {
+                    if let Nway::Target(n_way) = nway {
+                        if threads % n_way != 0 {
+                            return err(i, "the ways of a parallelizer must divide the threads left to it");
+                        }
+                    }
+                    threads /= nway.get_n_way(threads);
+                },
+                _ => {},
+            }
+        }
+        if threads != 1 {
+            return err(leaf, "the parallelizers below SpawnThreads must split all of its threads");
+        }
+
+        if shapes.is_empty() {
+            return Ok((mr.unwrap_or(0), nr.unwrap_or(0)));
+        }
+        //Without a kernel, any shape matching the packed formats will do
+        match shapes.iter().find(|&&(m, n)| mr.map_or(true, |mr| mr == m) && nr.map_or(true, |nr| nr == n)) {
+            Some(&shape) => Ok(shape),
+            None => err(leaf, "there is no kernel of this Mr and Nr for this datatype"),
+        }
+    }
+}
+
+//Datatypes runtime-built trees can be instantiated for.
+//Block sizes are runtime values, but Mr and Nr pick the kernel and packed formats,
+//so each supported pair is instantiated ahead of time.
+pub trait DynScalar: Scalar + 'static {
+    //The supported (Mr, Nr) pairs
+    fn kernel_shapes() -> Vec<(usize, usize)>;
+    fn build_shape(specs: &[NodeSpec], mr: usize, nr: usize) -> Option<DynNode<Self, Matrix<Self>, Matrix<Self>, Matrix<Self>>>;
+    fn build_traced_shape(specs: &[NodeSpec], mr: usize, nr: usize)
+        -> Option<DynNode<Self, Traced<Matrix<Self>>, Traced<Matrix<Self>>, Traced<Matrix<Self>>>>;
+}
+macro_rules! dyn_scalar {
+    ($t: ty, $(($mr: ty, $nr: ty)),*) => {
+        impl DynScalar for $t {
+            fn kernel_shapes() -> Vec<(usize, usize)> {
+                vec![$((<$mr>::to_usize(), <$nr>::to_usize())),*]
+            }
+            fn build_shape(specs: &[NodeSpec], mr: usize, nr: usize) -> Option<DynNode<$t, Matrix<$t>, Matrix<$t>, Matrix<$t>>> {
+                $(if (mr, nr) == (<$mr>::to_usize(), <$nr>::to_usize()) {
+                    return Some(build_node::<$t, Leaf<$mr, $nr>, Matrix<$t>, Matrix<$t>>(specs));
+                })*
+                None
+            }
+            fn build_traced_shape(specs: &[NodeSpec], mr: usize, nr: usize)
+                -> Option<DynNode<$t, Traced<Matrix<$t>>, Traced<Matrix<$t>>, Traced<Matrix<$t>>>> {
+                $(if (mr, nr) == (<$mr>::to_usize(), <$nr>::to_usize()) {
+                    return Some(build_node::<$t, TracedLeaf<$mr, $nr>, Traced<Matrix<$t>>, Traced<Matrix<$t>>>(specs));
+                })*
+                None
+            }
+        }
+    }
+}
+dyn_scalar!(f64, (U4, U12), (U6, U8), (U12, U4), (U8, U6), (U16, U14), (U14, U16), (U8, U4), (U24, U8));
+dyn_scalar!(f32, (U4, U24), (U6, U16), (U24, U4), (U16, U6), (U32, U14), (U14, U32), (U8, U8));
+
+//The formats a runtime tree runs on: C, and the buffers PackA and PackB pack into
+trait Formats<T: DynScalar> {
+    type C: Mat<T> + 'static;
+    type PackedA: Mat<T> + ResizableBuffer<T> + 'static;
+    type PackedB: Mat<T> + ResizableBuffer<T> + 'static;
+}
+struct Leaf<Mr: Unsigned, Nr: Unsigned> { _mr: Mr, _nr: Nr }
+impl<T: DynScalar, Mr: Unsigned + Send + 'static, Nr: Unsigned + Send + 'static> Formats<T> for Leaf<Mr, Nr> {
+    type C = Matrix<T>;
+    type PackedA = RowPanelMatrix<T, Mr>;
+    type PackedB = ColumnPanelMatrix<T, Nr>;
+}
+//The same formats, with every access run through a CacheSim
+struct TracedLeaf<Mr: Unsigned, Nr: Unsigned> { _mr: Mr, _nr: Nr }
+impl<T: DynScalar, Mr: Unsigned + Send + 'static, Nr: Unsigned + Send + 'static> Formats<T> for TracedLeaf<Mr, Nr> {
+    type C = Traced<Matrix<T>>;
+    type PackedA = Traced<RowPanelMatrix<T, Mr>>;
+    type PackedB = Traced<ColumnPanelMatrix<T, Nr>>;
+}
+
+//This trait exists so that the kernel can be specialized on the packed formats.
+//Only packed A and B reach it after validation.
+trait KernelLeaf<T: DynScalar, At: Mat<T>, Bt: Mat<T>>: Formats<T> {
+    fn kernel(spec: NodeSpec) -> Option<DynNode<T, At, Bt, Self::C>>;
+}
+impl<T: DynScalar, At: Mat<T> + 'static, Bt: Mat<T> + 'static, F: Formats<T>> KernelLeaf<T, At, Bt> for F {
+    default fn kernel(_spec: NodeSpec) -> Option<DynNode<T, At, Bt, F::C>> { None }
+}
+impl<T: DynScalar, Mr: Unsigned + Send + 'static, Nr: Unsigned + Send + 'static>
+    KernelLeaf<T, RowPanelMatrix<T, Mr>, ColumnPanelMatrix<T, Nr>> for Leaf<Mr, Nr> {
+    fn kernel(spec: NodeSpec) -> Option<DynNode<T, RowPanelMatrix<T, Mr>, ColumnPanelMatrix<T, Nr>, Matrix<T>>> {
+        match spec {
+            NodeSpec::KernelMN{..} => Some(Box::new(Static::new(
+                KernelMN::<T, RowPanelMatrix<T, Mr>, ColumnPanelMatrix<T, Nr>, Matrix<T>, Mr, Nr>::new()))),
+            _ => Some(Box::new(Static::new(
+                KernelNM::<T, RowPanelMatrix<T, Mr>, ColumnPanelMatrix<T, Nr>, Matrix<T>, Nr, Mr>::new()))),
+        }
+    }
+}
+impl<T: DynScalar, Mr: Unsigned + Send + 'static, Nr: Unsigned + Send + 'static>
+    KernelLeaf<T, Traced<RowPanelMatrix<T, Mr>>, Traced<ColumnPanelMatrix<T, Nr>>> for TracedLeaf<Mr, Nr> {
+    fn kernel(spec: NodeSpec) -> Option<DynNode<T, Traced<RowPanelMatrix<T, Mr>>, Traced<ColumnPanelMatrix<T, Nr>>, Traced<Matrix<T>>>> {
+        match spec {
+            NodeSpec::KernelMN{..} => Some(Box::new(Static::new(
+                KernelMN::<T, Traced<RowPanelMatrix<T, Mr>>, Traced<ColumnPanelMatrix<T, Nr>>, Traced<Matrix<T>>, Mr, Nr>::new()))),
+            _ => Some(Box::new(Static::new(
+                KernelNM::<T, Traced<RowPanelMatrix<T, Mr>>, Traced<ColumnPanelMatrix<T, Nr>>, Traced<Matrix<T>>, Nr, Mr>::new()))),
+        }
+    }
+}
+
+//Builds the subtree described by specs, which must have been validated.
+//PackA and PackB switch At and Bt to the packed formats of F, so only four pairs of them are instantiated.
+fn build_node<T: DynScalar, F: Formats<T>, At: Mat<T> + 'static, Bt: Mat<T> + 'static>
+    (specs: &[NodeSpec]) -> DynNode<T, At, Bt, F::C> {
+    let rest = &specs[1..];
+    match specs[0] {
+        NodeSpec::SpawnThreads(n_threads) =>
+            Box::new(DynSpawnThreads::new((0..n_threads).map(|_| build_node::<T, F, At, Bt>(rest)).collect())),
+        NodeSpec::PartM(bsz) => Box::new(DynPartM::new(bsz, build_node::<T, F, At, Bt>(rest))),
+        NodeSpec::PartN(bsz) => Box::new(DynPartN::new(bsz, build_node::<T, F, At, Bt>(rest))),
+        NodeSpec::PartK(bsz) => Box::new(DynPartK::new(bsz, build_node::<T, F, At, Bt>(rest))),
+        NodeSpec::ParallelM(iota, nway) =>
+            Box::new(DynParallelM::new(iota, nway, build_node::<T, F, At, Bt>(rest))),
+        NodeSpec::ParallelN(iota, nway) =>
+            Box::new(DynParallelN::new(iota, nway, build_node::<T, F, At, Bt>(rest))),
+        NodeSpec::PackA(_) =>
+            Box::new(DynPackA::<T, At, Bt, F::C, F::PackedA>::new(
+                build_node::<T, F, F::PackedA, Bt>(rest))),
+        NodeSpec::PackB(_) =>
+            Box::new(DynPackB::<T, At, Bt, F::C, F::PackedB>::new(
+                build_node::<T, F, At, F::PackedB>(rest))),
+        NodeSpec::KernelNM{..} | NodeSpec::KernelMN{..} =>
+            <F as KernelLeaf<T, At, Bt>>::kernel(specs[0]).expect("the kernel needs packed A and B"),
+        NodeSpec::TripleLoop => Box::new(Static::new(TripleLoop{})),
+    }
+}
@@ -0,0 +1,38 @@
+use matrix::{Scalar,Mat};
+use composables::AlgorithmStep;
+use MommsError;
+
+//Checks of the block sizes of a control tree against the packed formats it partitions.
+//Nodes run them in new(), so inconsistent trees are rejected before computing wrong answers.
+
+fn check_multiple(node: &'static str, bsz: usize, multiple: usize) -> Result<(), MommsError> {
+    if bsz % multiple == 0 { Ok(()) } else {
+        Err(MommsError::MisalignedBlockSize{ node: node, bsz: bsz, multiple: multiple })
+    }
+}
+//Partitions in m split the rows of A and C
+pub fn check_part_m<T: Scalar, At: Mat<T>, Ct: Mat<T>>(node: &'static str, bsz: usize) -> Result<(), MommsError> {
+    check_multiple(node, bsz, At::block_multiples().0)?;
+    check_multiple(node, bsz, Ct::block_multiples().0)
+}
+//Partitions in n split the columns of B and C
+pub fn check_part_n<T: Scalar, Bt: Mat<T>, Ct: Mat<T>>(node: &'static str, bsz: usize) -> Result<(), MommsError> {
+    check_multiple(node, bsz, Bt::block_multiples().1)?;
+    check_multiple(node, bsz, Ct::block_multiples().1)
+}
+//Partitions in k split the columns of A and the rows of B
+pub fn check_part_k<T: Scalar, At: Mat<T>, Bt: Mat<T>>(node: &'static str, bsz: usize) -> Result<(), MommsError> {
+    check_multiple(node, bsz, At::block_multiples().1)?;
+    check_multiple(node, bsz, Bt::block_multiples().0)
+}
+//Packed buffers are laid out by the first child's hierarchy description, so the other must match it
+pub fn check_same_hierarchy(node: &'static str, first: &[AlgorithmStep], rest: &[AlgorithmStep]) -> Result<(), MommsError> {
+    if first == rest { Ok(()) } else { Err(MommsError::MismatchedHierarchies{ node: node }) }
+}
+
+//new() can't fail, so it panics with the error
+pub fn expect_valid(result: Result<(), MommsError>) {
+    if let Err(e) = result {
+        panic!("{}", e);
+    }
+}
@@ -0,0 +1,137 @@
+use core::fmt::{Display,Write};
+use composables::{Nway,AlgorithmStep};
+
+//What a node does to the problem, for analyses of a tree that shouldn't depend on how it is displayed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NodeRole {
+    //Loops over blocks of one dimension
+    Part(AlgorithmStep),
+    //Copies A or B into a packed buffer
+    PackA,
+    PackB,
+    //Loops over the mr x nr micro-tiles of its problem, over m outside of n if m_outer
+    Kernel{ mr: usize, nr: usize, m_outer: bool },
+}
+
+//Description of a node of a control tree and of its subtree, for documenting and reviewing algorithms.
+//Unlike hierarchy_description, every node is described, including packing, parallelism and barriers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeDescription {
+    //Name of the node's type, e.g. PartN
+    pub name: &'static str,
+    //Parameters of the node, e.g. ("bsz", "3000") or ("layout", "RowPanelMatrix(panel height 4)")
+    pub params: Vec<(&'static str, String)>,
+    //What the node does to the problem, if it partitions, packs or runs a kernel
+    pub role: Option<NodeRole>,
+    //What the node does, as a line of the loop nest
+    pub statement: String,
+    //Whether the children run inside a loop the node introduces, rather than after it
+    pub nests: bool,
+    pub children: Vec<NodeDescription>,
+}
+impl NodeDescription {
+    pub fn new(name: &'static str, statement: String) -> NodeDescription {
+        NodeDescription{ name: name, params: Vec::new(), role: None, statement: statement, nests: false, children: Vec::new() }
+    }
+    pub fn param<V: Display>(mut self, key: &'static str, value: V) -> NodeDescription {
+        self.params.push((key, value.to_string()));
+        self
+    }
+    pub fn role(mut self, role: NodeRole) -> NodeDescription {
+        self.role = Some(role);
+        self
+    }
+    //Adds a child that runs after this node's own work
+    pub fn then(mut self, child: NodeDescription) -> NodeDescription {
+        self.children.push(child);
+        self
+    }
+    //Adds a child that runs inside this node's loop
+    pub fn nest(mut self, child: NodeDescription) -> NodeDescription {
+        self.nests = true;
+        self.children.push(child);
+        self
+    }
+
+    //Renders the tree as an indented loop nest, e.g.
+    //    for n in blocks of 3000
+    //        for k in blocks of 192
+    //            pack B into ColumnPanelMatrix(panel width 12)
+    //            for m in blocks of 120
+    //                ...
+    pub fn loop_nest(&self) -> String {
+        let mut out = String::new();
+        self.write_loop_nest(&mut out, 0);
+        out
+    }
+    fn write_loop_nest(&self, out: &mut String, depth: usize) {
+        for _ in 0..depth { out.push_str("    "); }
+        out.push_str(&self.statement);
+        out.push('\n');
+        let child_depth = if self.nests { depth + 1 } else { depth };
+        for child in &self.children {
+            child.write_loop_nest(out, child_depth);
+        }
+    }
+
+    //Renders the tree as a Graphviz digraph, one box per node labeled with its type and parameters
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        out.push_str("digraph control_tree {\n    node [shape=box, fontname=\"monospace\"];\n");
+        self.write_dot(&mut out, &mut 0);
+        out.push_str("}\n");
+        out
+    }
+    //Writes this subtree with ids from *next_id on, and returns the id of this node
+    fn write_dot(&self, out: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let mut label = escape_dot(self.name);
+        for &(key, ref value) in &self.params {
+            label.push_str(&format!("\\n{} = {}", escape_dot(key), escape_dot(value)));
+        }
+        let _ = writeln!(out, "    n{} [label=\"{}\"];", id, label);
+        for child in &self.children {
+            let child_id = child.write_dot(out, next_id);
+            let style = if self.nests { "" } else { " [style=dashed]" };
+            let _ = writeln!(out, "    n{} -> n{}{};", id, child_id, style);
+        }
+        id
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+//Descriptions shared by the static and runtime-built nodes
+fn part_step(dim: char, bsz: usize) -> AlgorithmStep {
+    match dim {
+        'm' => AlgorithmStep::M{ bsz: bsz },
+        'n' => AlgorithmStep::N{ bsz: bsz },
+        _ => AlgorithmStep::K{ bsz: bsz },
+    }
+}
+pub fn describe_part(name: &'static str, dim: char, bsz: usize, child: NodeDescription) -> NodeDescription {
+    NodeDescription::new(name, format!("for {} in blocks of {}", dim, bsz)).param("bsz", bsz)
+        .role(NodeRole::Part(part_step(dim, bsz))).nest(child)
+}
+pub fn describe_first_diff_part(name: &'static str, dim: char, bsz: usize,
+                                first: NodeDescription, rest: NodeDescription) -> NodeDescription {
+    NodeDescription::new(name, format!("for {} in blocks of {}", dim, bsz)).param("bsz", bsz)
+        .role(NodeRole::Part(part_step(dim, bsz)))
+        .nest(NodeDescription::new("first block", "first block:".to_string()).nest(first))
+        .nest(NodeDescription::new("other blocks", "other blocks:".to_string()).nest(rest))
+}
+pub fn describe_pack(name: &'static str, operand: char, layout: String, child: NodeDescription) -> NodeDescription {
+    let role = if operand == 'A' { NodeRole::PackA } else { NodeRole::PackB };
+    NodeDescription::new(name, format!("pack {} into {}", operand, layout)).param("layout", layout).role(role).then(child)
+}
+pub fn describe_parallel(name: &'static str, dim: char, iota: usize, nway: Nway, child: NodeDescription) -> NodeDescription {
+    let ways = match nway {
+        Nway::Target(n_way) => format!("{} ways", n_way),
+        Nway::TheRest => "among all threads".to_string(),
+    };
+    NodeDescription::new(name, format!("split {} {} in multiples of {}", dim, ways, iota))
+        .param("iota", iota).param("nway", nway).nest(child)
+}
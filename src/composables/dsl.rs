@@ -0,0 +1,198 @@
+use core::fmt;
+use core::str::FromStr;
+use composables::{NodeSpec,TreeBuilder,Nway};
+use MommsError;
+
+//Text form of runtime-built control trees. Nodes are listed from the root down, separated by >:
+//    spawn(4) > partN(3000) > partK(192) > packB(colpanel 12) > partM(120) > packA(rowpanel 4)
+//        > parN(12, rest) > kernelNM(12,4)
+//parM and parN take an iota and either rest or the number of ways to split.
+//kernelNM takes Nr then Mr, like the KernelNM type, and kernelMN takes Mr then Nr.
+
+impl fmt::Display for Nway {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Nway::Target(nthr) => write!(f, "{}", nthr),
+            Nway::TheRest => write!(f, "rest"),
+        }
+    }
+}
+impl fmt::Display for NodeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NodeSpec::SpawnThreads(n_threads) => write!(f, "spawn({})", n_threads),
+            NodeSpec::PartM(bsz) => write!(f, "partM({})", bsz),
+            NodeSpec::PartN(bsz) => write!(f, "partN({})", bsz),
+            NodeSpec::PartK(bsz) => write!(f, "partK({})", bsz),
+            NodeSpec::ParallelM(iota, nway) => write!(f, "parM({}, {})", iota, nway),
+            NodeSpec::ParallelN(iota, nway) => write!(f, "parN({}, {})", iota, nway),
+            NodeSpec::PackA(mr) => write!(f, "packA(rowpanel {})", mr),
+            NodeSpec::PackB(nr) => write!(f, "packB(colpanel {})", nr),
+            NodeSpec::KernelNM{ nr, mr } => write!(f, "kernelNM({},{})", nr, mr),
+            NodeSpec::KernelMN{ mr, nr } => write!(f, "kernelMN({},{})", mr, nr),
+            NodeSpec::TripleLoop => write!(f, "tripleLoop"),
+        }
+    }
+}
+impl fmt::Display for TreeBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, spec) in self.specs().iter().enumerate() {
+            if i > 0 { write!(f, " > ")?; }
+            write!(f, "{}", spec)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TreeBuilder {
+    type Err = MommsError;
+    fn from_str(text: &str) -> Result<TreeBuilder, MommsError> {
+        Parser{ text: text, pos: 0, end: text.len() }.tree()
+    }
+}
+
+//Parses a list of named algorithms, one per line as
+//    name: tree
+//Everything after a # is a comment.
+pub fn parse_algorithms(text: &str) -> Result<Vec<(String, TreeBuilder)>, MommsError> {
+    let mut algorithms = Vec::new();
+    let mut line_start = 0;
+    for line in text.split('\n') {
+        let end = line_start + line.find('#').unwrap_or(line.len());
+        let mut parser = Parser{ text: text, pos: line_start, end: end };
+        line_start += line.len() + 1;
+
+        parser.skip_whitespace();
+        if parser.pos == parser.end { continue; }
+        let name_start = parser.pos;
+        let colon = match text[name_start..end].find(':') {
+            Some(colon) => name_start + colon,
+            None => return Err(parser.error(name_start, "expected name: tree")),
+        };
+        let name = text[name_start..colon].trim();
+        if name.is_empty() {
+            return Err(parser.error(name_start, "the algorithm has no name"));
+        }
+        parser.pos = colon + 1;
+        algorithms.push((name.to_string(), parser.tree()?));
+    }
+    Ok(algorithms)
+}
+
+//Recursive descent over text[pos..end]. Errors point into the whole text.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    end: usize,
+}
+impl<'a> Parser<'a> {
+    fn error(&self, pos: usize, reason: &'static str) -> MommsError {
+        let before = &self.text[..pos];
+        let line_start = before.rfind('\n').map_or(0, |nl| nl + 1);
+        MommsError::MalformedControlTree{ line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1, reason: reason }
+    }
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..self.end].chars().next()
+    }
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() { break; }
+            self.pos += c.len_utf8();
+        }
+    }
+    //Consumes c if it is the next non-whitespace character
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) { self.pos += c.len_utf8(); true } else { false }
+    }
+    fn expect(&mut self, c: char, reason: &'static str) -> Result<(), MommsError> {
+        if self.eat(c) { Ok(()) } else { Err(self.error(self.pos, reason)) }
+    }
+    //Returns the next run of characters satisfying pred and where it starts
+    fn take_while<F: Fn(char) -> bool>(&mut self, pred: F) -> (usize, &'a str) {
+        self.skip_whitespace();
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !pred(c) { break; }
+            self.pos += c.len_utf8();
+        }
+        (start, &self.text[start..self.pos])
+    }
+    fn word(&mut self) -> (usize, &'a str) {
+        self.take_while(|c| c.is_alphanumeric() || c == '_')
+    }
+    fn number(&mut self) -> Result<usize, MommsError> {
+        let (start, digits) = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() { return Err(self.error(start, "expected a number")); }
+        digits.parse().map_err(|_| self.error(start, "the number is too large"))
+    }
+    fn keyword(&mut self, keyword: &str, reason: &'static str) -> Result<(), MommsError> {
+        let (start, word) = self.word();
+        if word == keyword { Ok(()) } else { Err(self.error(start, reason)) }
+    }
+
+    fn tree(&mut self) -> Result<TreeBuilder, MommsError> {
+        let mut builder = TreeBuilder::new().push(self.node()?);
+        while self.eat('>') {
+            builder = builder.push(self.node()?);
+        }
+        self.skip_whitespace();
+        if self.pos != self.end {
+            return Err(self.error(self.pos, "expected > between nodes"));
+        }
+        Ok(builder)
+    }
+    fn node(&mut self) -> Result<NodeSpec, MommsError> {
+        let (start, name) = self.word();
+        if name == "tripleLoop" {
+            if self.eat('(') { self.expect(')', "tripleLoop takes no arguments")?; }
+            return Ok(NodeSpec::TripleLoop);
+        }
+        match name {
+            "" => return Err(self.error(start, "expected a node")),
+            "spawn" | "partM" | "partN" | "partK" | "parM" | "parN" | "packA" | "packB" | "kernelNM" | "kernelMN" => (),
+            _ => return Err(self.error(start, "unknown node")),
+        }
+        self.expect('(', "expected (")?;
+        let spec = match name {
+            "spawn" => NodeSpec::SpawnThreads(self.number()?),
+            "partM" => NodeSpec::PartM(self.number()?),
+            "partN" => NodeSpec::PartN(self.number()?),
+            "partK" => NodeSpec::PartK(self.number()?),
+            "parM" | "parN" => {
+                let iota = self.number()?;
+                self.expect(',', "expected , between the iota and the number of ways")?;
+                self.skip_whitespace();
+                let nway = if self.peek().map_or(false, |c| c.is_ascii_digit()) {
+                    Nway::Target(self.number()?)
+                } else {
+                    self.keyword("rest", "expected rest or a number of ways")?;
+                    Nway::TheRest
+                };
+                if name == "parM" { NodeSpec::ParallelM(iota, nway) } else { NodeSpec::ParallelN(iota, nway) }
+            },
+            "packA" => {
+                self.keyword("rowpanel", "packA packs into rowpanel")?;
+                NodeSpec::PackA(self.number()?)
+            },
+            "packB" => {
+                self.keyword("colpanel", "packB packs into colpanel")?;
+                NodeSpec::PackB(self.number()?)
+            },
+            "kernelNM" => {
+                let nr = self.number()?;
+                self.expect(',', "expected , between Nr and Mr")?;
+                NodeSpec::KernelNM{ nr: nr, mr: self.number()? }
+            },
+            "kernelMN" => {
+                let mr = self.number()?;
+                self.expect(',', "expected , between Mr and Nr")?;
+                NodeSpec::KernelMN{ mr: mr, nr: self.number()? }
+            },
+            _ => unreachable!(),
+        };
+        self.expect(')', "expected )")?;
+        Ok(spec)
+    }
+}
@@ -2,7 +2,7 @@ use core::marker::PhantomData;
 use matrix::{Scalar,Mat,PackPair,ResizableBuffer};
 //use typenum::Unsigned;
 use thread_comm::ThreadInfo;
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,NodeDescription,NodeRole};
 
 pub struct DelayedPackA<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Apt: Mat<T>, 
     S: GemmNode<T, PackPair<T,At,Apt>, Bt, Ct>> {
@@ -58,6 +58,11 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Apt: Mat<T>, S>
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     } 
+    fn describe() -> NodeDescription {
+        let layout = Apt::describe_layout();
+        NodeDescription::new("DelayedPackA", format!("pair A with a buffer packed as {}", layout))
+            .param("layout", layout).role(NodeRole::PackA).then(S::describe())
+    }
 }
 
 pub struct DelayedPackB<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bpt: Mat<T>, 
@@ -114,6 +119,11 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bpt: Mat<T>, S>
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     } 
+    fn describe() -> NodeDescription {
+        let layout = Bpt::describe_layout();
+        NodeDescription::new("DelayedPackB", format!("pair B with a buffer packed as {}", layout))
+            .param("layout", layout).role(NodeRole::PackB).then(S::describe())
+    }
 }
 
 pub struct UnpairA<T: Scalar, At: Mat<T>, Apt: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, 
@@ -139,6 +149,9 @@ impl<T: Scalar, At: Mat<T>, Apt: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S>
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     } 
+    fn describe() -> NodeDescription {
+        NodeDescription::new("UnpairA", "continue with the packed A".to_string()).then(S::describe())
+    }
 }
 
 pub struct UnpairB<T: Scalar, At: Mat<T>, Bt: Mat<T>, Bpt: Mat<T>, Ct: Mat<T>, 
@@ -164,6 +177,9 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Bpt: Mat<T>, Ct: Mat<T>, S>
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     } 
+    fn describe() -> NodeDescription {
+        NodeDescription::new("UnpairB", "continue with the packed B".to_string()).then(S::describe())
+    }
 }
 
 pub struct UnpairC<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Cpt: Mat<T>,
@@ -189,4 +205,7 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Cpt: Mat<T>, S>
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     } 
+    fn describe() -> NodeDescription {
+        NodeDescription::new("UnpairC", "continue with the packed C".to_string()).then(S::describe())
+    }
 }
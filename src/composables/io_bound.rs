@@ -0,0 +1,125 @@
+use core::mem;
+use composables::{NodeDescription,NodeRole,AlgorithmStep,CacheLevel};
+
+//Words moved between one level of cache and the level beyond it, predicted for a control tree
+//and bounded from below for any algorithm
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DataMovement {
+    //Capacity of the level in elements
+    pub capacity: usize,
+    pub words: f64,
+    pub lower_bound: f64,
+}
+
+//The steps of a control tree that decide its data movement
+#[derive(Copy, Clone, Debug)]
+enum Step {
+    Part{ dim: char, bsz: usize },
+    Pack{ operand: char },
+}
+
+//The partitions and packing nodes along the path to the kernel, and the loops of the kernel over its micro-tiles.
+//FirstDiffPart nodes are read as their other blocks, and threads are assumed to share the caches,
+//so parallel nodes don't change what each level holds.
+fn steps(desc: &NodeDescription, out: &mut Vec<Step>) {
+    match desc.role {
+        Some(NodeRole::Part(AlgorithmStep::M{ bsz })) => out.push(Step::Part{ dim: 'm', bsz: bsz }),
+        Some(NodeRole::Part(AlgorithmStep::N{ bsz })) => out.push(Step::Part{ dim: 'n', bsz: bsz }),
+        Some(NodeRole::Part(AlgorithmStep::K{ bsz })) => out.push(Step::Part{ dim: 'k', bsz: bsz }),
+        Some(NodeRole::PackA) => out.push(Step::Pack{ operand: 'a' }),
+        Some(NodeRole::PackB) => out.push(Step::Pack{ operand: 'b' }),
+        Some(NodeRole::Kernel{ mr, nr, m_outer }) => {
+            let (m_loop, n_loop) = (Step::Part{ dim: 'm', bsz: mr }, Step::Part{ dim: 'n', bsz: nr });
+            if m_outer { out.extend(&[m_loop, n_loop]); } else { out.extend(&[n_loop, m_loop]); }
+        },
+        None => {},
+    }
+    if let Some(child) = desc.children.last() {
+        steps(child, out);
+    }
+}
+
+//A loop of a partition, with the number of blocks and the problem each iteration solves
+struct Loop {
+    step: usize,
+    dim: char,
+    iters: f64,
+    //Elements touched by one iteration, including packed copies
+    footprint: f64,
+}
+
+//The dimensions each operand spans
+fn spans(operand: char, dim: char) -> bool {
+    match operand {
+        'a' => dim == 'm' || dim == 'k',
+        'b' => dim == 'k' || dim == 'n',
+        _ => dim == 'm' || dim == 'n',
+    }
+}
+
+impl NodeDescription {
+    //The data movement of an m x n x k gemm with this tree through each level of a cache hierarchy.
+    //An operand that doesn't span the dimension of a loop is reused across its iterations if one iteration
+    //touches no more than the level holds (its LRU reuse distance), and is read again each iteration otherwise.
+    //PackA and PackB read their block once per iteration of the loops above them and write a packed copy,
+    //which stays in the level if it fits there. C is written back as often as it is read.
+    //The lower bound is the larger of 2mnk/sqrt(S) - 2S (Smith et al., "A Tight I/O Lower Bound for
+    //Matrix Multiplication") and reading A, B and C and writing C once.
+    pub fn data_movement<T>(&self, m: usize, n: usize, k: usize, levels: &[CacheLevel]) -> Vec<DataMovement> {
+        let mut tree_steps = Vec::new();
+        steps(self, &mut tree_steps);
+
+        //Blocks of each operand after each step, averaged over the blocks of the partitions so ragged edges count for less
+        let (mut bm, mut bn, mut bk) = (m as f64, n as f64, k as f64);
+        let mut packs: Vec<(usize, char, f64)> = Vec::new();
+        let mut loops: Vec<Loop> = Vec::new();
+        for (i, &step) in tree_steps.iter().enumerate() {
+            match step {
+                Step::Part{ dim, bsz } => {
+                    let extent = match dim { 'm' => &mut bm, 'n' => &mut bn, _ => &mut bk };
+                    let iters = (*extent / bsz as f64).ceil();
+                    *extent /= iters;
+                    loops.push(Loop{ step: i, dim: dim, iters: iters, footprint: bm * bk + bk * bn + bm * bn });
+                },
+                Step::Pack{ operand } => {
+                    let size = if operand == 'a' { bm * bk } else { bk * bn };
+                    packs.push((i, operand, size));
+                },
+            }
+        }
+        for l in loops.iter_mut() {
+            l.footprint += packs.iter().filter(|p| p.0 > l.step).map(|p| p.2).sum::<f64>();
+        }
+
+        let (m, n, k) = (m as f64, n as f64, k as f64);
+        let compulsory = m * k + k * n + 2.0 * m * n;
+        levels.iter().map(|level| {
+            let capacity = level.size / mem::size_of::<T>();
+            let s = capacity as f64;
+            let mut words = 0.0;
+            for &(operand, size) in [('a', m * k), ('b', k * n), ('c', m * n)].iter() {
+                //Each operand is read from its source until it is packed, then from each packed copy in turn
+                let mut starts: Vec<Option<(usize, f64)>> = vec![None];
+                starts.extend(packs.iter().filter(|p| p.1 == operand).map(|p| Some((p.0, p.2))));
+                for (j, &start) in starts.iter().enumerate() {
+                    let first = start.map_or(0, |p| p.0);
+                    let end = starts.get(j + 1).map_or(tree_steps.len(), |next| next.unwrap().0);
+                    let reads: f64 = loops.iter().filter(|l| l.step >= first && l.step < end && !spans(operand, l.dim)
+                        && l.footprint > s).map(|l| l.iters).product();
+                    words += match start {
+                        None if operand == 'c' => 2.0 * size * reads,
+                        None => size * reads,
+                        Some((_, packed)) => {
+                            let copies: f64 = loops.iter().filter(|l| l.step < first && !spans(operand, l.dim))
+                                .map(|l| l.iters).product();
+                            let traffic = if packed > s { 1.0 + reads } else { reads - 1.0 };
+                            size * copies * traffic
+                        },
+                    };
+                }
+            }
+            let lower_bound = (2.0 * m * n * k / s.sqrt() - 2.0 * s).max(compulsory);
+            DataMovement{ capacity: capacity, words: words, lower_bound: lower_bound }
+        }).collect()
+    }
+}
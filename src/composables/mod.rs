@@ -7,21 +7,43 @@ mod barrier;
 mod triple_loop;
 mod unpack;
 mod fused_pack;
+mod requantize;
+mod builder;
+mod dsl;
+mod describe;
+mod check;
+mod planner;
+mod blocking;
+mod simulate;
+mod io_bound;
+#[cfg(target_os="linux")]
+mod perf;
 
 //pub use self::gemm::{GemmNode,AlgorithmStep};
-pub use self::part::{PartM,PartN,PartK,FirstDiffPartM,FirstDiffPartN,FirstDiffPartK};
-pub use self::pack::{PackA,PackB};
-pub use self::parallel_range::{ParallelM,ParallelN,Nwayer,TheRest,Target};
-pub use self::spawn::{SpawnThreads};
+pub use self::part::{PartM,PartN,PartK,FirstDiffPartM,FirstDiffPartN,FirstDiffPartK,DynPartM,DynPartN,DynPartK};
+pub use self::pack::{PackA,PackB,DynPackA,DynPackB};
+pub use self::parallel_range::{ParallelM,ParallelN,Nwayer,TheRest,Target,DynParallelM,DynParallelN,Nway};
+pub use self::spawn::{SpawnThreads,DynSpawnThreads};
 pub use self::barrier::{Barrier};
 pub use self::triple_loop::{TripleLoop};
 pub use self::unpack::{UnpackC};
 pub use self::fused_pack::{DelayedPackA,DelayedPackB,UnpairA,UnpairB,UnpairC};
+pub use self::requantize::{Requantize};
+pub use self::builder::{NodeSpec,TreeBuilder,DynScalar};
+pub use self::dsl::{parse_algorithms};
+pub use self::describe::{NodeDescription,NodeRole};
+pub use self::planner::{Planner,Family,CacheSizes};
+pub use self::blocking::{CacheTopology,CacheLevel,BlockingPlan};
+pub use self::simulate::{CacheSim,LevelTraffic};
+pub(crate) use self::simulate::{trace_kernel,trace_pack};
+pub use self::io_bound::{DataMovement};
+#[cfg(target_os="linux")]
+pub use self::perf::{PerfCounters,DynPerfCounters,PerfSample,take_perf_samples};
 
 use matrix::{Scalar,Mat};
 use thread_comm::ThreadInfo;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum AlgorithmStep {
     M { bsz: usize },
     N { bsz: usize },
@@ -33,4 +55,37 @@ pub trait GemmNode<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> {
     unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, thr: &ThreadInfo<T>) -> ();
     fn new() -> Self;
     fn hierarchy_description() -> Vec<AlgorithmStep>;
+    fn describe() -> NodeDescription;
+}
+
+//Object safe counterpart of GemmNode, for control trees assembled at runtime.
+//Children are boxed and block sizes are plain usizes, so sweeping them needs no recompilation.
+pub trait DynGemmNode<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>>: Send {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, thr: &ThreadInfo<T>) -> ();
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep>;
+    fn describe(&self) -> NodeDescription;
+}
+pub type DynNode<T, At, Bt, Ct> = Box<DynGemmNode<T, At, Bt, Ct>>;
+
+//Runs a compile time subtree, usually a kernel, as a node of a runtime tree
+pub struct Static<S> {
+    node: S,
+}
+impl<S> Static<S> {
+    pub fn new(node: S) -> Static<S> {
+        Static{ node: node }
+    }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S: GemmNode<T, At, Bt, Ct> + Send>
+    DynGemmNode<T, At, Bt, Ct> for Static<S> {
+    #[inline(always)]
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, thr: &ThreadInfo<T>) -> () {
+        self.node.run(a, b, c, thr);
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        S::hierarchy_description()
+    }
+    fn describe(&self) -> NodeDescription {
+        S::describe()
+    }
 }
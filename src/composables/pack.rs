@@ -1,10 +1,13 @@
 use core::{ptr,cmp};
 use core::marker::PhantomData;
 
-use matrix::{Scalar,Mat,ColumnPanelMatrix,RowPanelMatrix,Hierarch,Matrix,ResizableBuffer,HierarchyNode,RoCM};
+use matrix::{Scalar,Mat,ColumnPanelMatrix,RowPanelMatrix,Hierarch,Matrix,MatrixRef,Transposed,ResizableBuffer,HierarchyNode,RoCM,RoCMMut,Addressed,Traced};
+use matrix::{QuantElem,QMatrix,QRowPanelMatrix,QColumnPanelMatrix,K_GROUP};
+use matrix::{HalfElem,HalfMatrix};
 use typenum::Unsigned;
 use thread_comm::ThreadInfo;
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,DynGemmNode,DynNode,NodeDescription,trace_pack};
+use composables::describe::{describe_pack};
 
 //returns (a,b)
 //a*b = nt such that a >= b
@@ -46,88 +49,192 @@ impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> Copier<T, At, Apt>
     }
 }
  
-//Specialized implementation of Packer for packing from general strided matrices into column panel
-//matrices
-impl<T: Scalar, PW: Unsigned> Copier<T, Matrix<T>, ColumnPanelMatrix<T, PW>> 
-    for Packer<T, Matrix<T>, ColumnPanelMatrix<T, PW>> {
-    fn pack(a: &mut Matrix<T>, a_pack: &mut ColumnPanelMatrix<T, PW>, thr: &ThreadInfo<T>) {
+//A strided source to pack from, h x w with leaf strides rs and cs
+struct PanelSource<S> {
+    buf: *const S,
+    rs: usize,
+    cs: usize,
+    h: usize,
+    w: usize,
+}
+impl<S> PanelSource<S> {
+    fn transposed(self) -> PanelSource<S> {
+        PanelSource{ buf: self.buf, rs: self.cs, cs: self.rs, h: self.w, w: self.h }
+    }
+}
+
+//Packs this thread's share of src into panels of width pw, cut from src's columns.
+//Each group of `group` consecutive rows of a panel is stored column by column, so with a group of 1
+//every row of a panel is contiguous. Elements past the end of src are zero padded.
+//n_panels panels are split among panel_thr = (threads, id) and n_groups groups along them among along_thr.
+//Row panels are packed as the column panels of the transposed source.
+unsafe fn pack_panels<S: Copy, P: Copy, F: Fn(S) -> P, G: FnMut(usize) -> *mut P>
+    (src: PanelSource<S>, pw: usize, group: usize, n_panels: usize, n_groups: usize,
+     panel_thr: (usize, usize), along_thr: (usize, usize), mut get_panel: G, zero: P, convert: F) {
+    let thread_range = |n: usize, (nt, tid): (usize, usize)| {
+        let per_thread = (n-1) / nt + 1;
+        let start = per_thread * tid;
+        start..cmp::min(n, start+per_thread)
+    };
+    let groups = thread_range(n_groups, along_thr);
+
+    for panel in thread_range(n_panels, panel_thr) {
+        let p = get_panel(panel);
+        let src1 = src.buf.offset((panel * pw * src.cs) as isize);
+        //Don't read past the last column of src; it may end exactly where src's buffer does
+        let n_cols = cmp::min(pw, src.w - panel * pw);
+
+        for g in groups.clone() {
+            let pg = p.offset((g * group * pw) as isize);
+            for i in 0..pw {
+                for q in 0..group {
+                    let y = g * group + q;
+                    let alpha = if i < n_cols && y < src.h {
+                        convert(ptr::read(src1.offset((y*src.rs + i*src.cs) as isize)))
+                    } else {
+                        zero
+                    };
+                    ptr::write(pg.offset((i*group + q) as isize), alpha);
+                }
+            }
+        }
+    }
+}
+
+//Column panels are split among the x threads of decompose, and the rows along them among the y threads.
+//Returns (panel threads, along threads) as (number of threads, id) pairs.
+fn column_panel_split(nt: usize, tid: usize) -> ((usize, usize), (usize, usize)) {
+    let (y_nt, x_nt) = decompose(nt);
+    ((x_nt, tid / y_nt), (y_nt, tid % y_nt))
+}
+//Row panels are split among the y threads, and the columns along them among the x threads.
+fn row_panel_split(nt: usize, tid: usize) -> ((usize, usize), (usize, usize)) {
+    let (y_nt, x_nt) = decompose(nt);
+    ((y_nt, tid % y_nt), (x_nt, tid / y_nt))
+}
+
+//Packs a strided source into column panel matrices.
+//The source may be logically transposed; its leaf strides say where each element is.
+unsafe fn pack_column_panels<T: Scalar, At: Mat<T> + RoCM<T>, PW: Unsigned>
+    (a: &At, a_pack: &mut ColumnPanelMatrix<T, PW>, thr: &ThreadInfo<T>) {
+    if a_pack.width() == 0 || a_pack.height() == 0 {
+        return;
+    }
+    let src = PanelSource{ buf: a.get_buffer(), rs: a.get_leaf_rs(), cs: a.get_leaf_cs(), h: a.height(), w: a.width() };
+    let conj = a.is_conjugated();
+    let (panel_thr, along_thr) = column_panel_split(thr.num_threads(), thr.thread_id());
+    let n_panels = (a_pack.width()-1) / PW::to_usize() + 1;
+    pack_panels(src, PW::to_usize(), 1, n_panels, a_pack.height(), panel_thr, along_thr,
+                |panel| a_pack.get_panel(panel), T::zero(), |alpha: T| if conj { alpha.conj() } else { alpha });
+}
+
+//Packs a strided source into row panel matrices.
+unsafe fn pack_row_panels<T: Scalar, At: Mat<T> + RoCM<T>, PH: Unsigned>
+    (a: &At, a_pack: &mut RowPanelMatrix<T, PH>, thr: &ThreadInfo<T>) {
+    if a_pack.width() == 0 || a_pack.height() == 0 {
+        return;
+    }
+    let src = PanelSource{ buf: a.get_buffer(), rs: a.get_leaf_rs(), cs: a.get_leaf_cs(), h: a.height(), w: a.width() };
+    let conj = a.is_conjugated();
+    let (panel_thr, along_thr) = row_panel_split(thr.num_threads(), thr.thread_id());
+    let n_panels = (a_pack.height()-1) / PH::to_usize() + 1;
+    pack_panels(src.transposed(), PH::to_usize(), 1, n_panels, a_pack.width(), panel_thr, along_thr,
+                |panel| a_pack.get_panel(panel), T::zero(), |alpha: T| if conj { alpha.conj() } else { alpha });
+}
+
+//Specialized implementations of Packer for packing from general strided matrices,
+//borrowed views of them, and their transposes into column panel and row panel matrices.
+//The transpose is absorbed by packing with the strides swapped.
+macro_rules! strided_panel_packers {
+    ($($at: ty),*) => {$(
+        impl<'a, T: Scalar, PW: Unsigned> Copier<T, $at, ColumnPanelMatrix<T, PW>>
+            for Packer<T, $at, ColumnPanelMatrix<T, PW>> {
+            fn pack(a: &mut $at, a_pack: &mut ColumnPanelMatrix<T, PW>, thr: &ThreadInfo<T>) {
+                unsafe { pack_column_panels(a, a_pack, thr) }
+            }
+        }
+        impl<'a, T: Scalar, PH: Unsigned> Copier<T, $at, RowPanelMatrix<T, PH>>
+            for Packer<T, $at, RowPanelMatrix<T, PH>> {
+            fn pack(a: &mut $at, a_pack: &mut RowPanelMatrix<T, PH>, thr: &ThreadInfo<T>) {
+                unsafe { pack_row_panels(a, a_pack, thr) }
+            }
+        }
+    )*}
+}
+strided_panel_packers!(Matrix<T>, MatrixRef<'a, T>, Transposed<Matrix<T>>, Transposed<MatrixRef<'a, T>>);
+
+//Specialized implementation of Packer for widening 16-bit float matrices into f32 column panels.
+//Partial panels are zero padded.
+impl<H: HalfElem, PW: Unsigned> Copier<f32, HalfMatrix<H>, ColumnPanelMatrix<f32, PW>>
+    for Packer<f32, HalfMatrix<H>, ColumnPanelMatrix<f32, PW>> {
+    fn pack(a: &mut HalfMatrix<H>, a_pack: &mut ColumnPanelMatrix<f32, PW>, thr: &ThreadInfo<f32>) {
         if a_pack.width() == 0 || a_pack.height() == 0 {
             return;
         }
         unsafe {
-            let ap = a.get_buffer();
-            let cs_a = a.get_column_stride();
-            let rs_a = a.get_row_stride();
-
-            let (y_nt, x_nt) = decompose(thr.num_threads());
-            let x_tid = thr.thread_id() / y_nt;
-            let y_tid = thr.thread_id() % y_nt;
-
-            //Figure out this thread's work in x direction
+            let src = PanelSource{ buf: a.get_buffer(), rs: a.get_row_stride(), cs: a.get_column_stride(), h: a.height(), w: a.width() };
+            let (panel_thr, along_thr) = column_panel_split(thr.num_threads(), thr.thread_id());
             let n_panels = (a_pack.width()-1) / PW::to_usize() + 1;
-            let panels_per_thread = (n_panels-1) / x_nt + 1;
-            let start_panel = panels_per_thread * x_tid;
-            let end_panel = cmp::min(n_panels, start_panel+panels_per_thread);
-
-            //Figure out this thread's work in y direction
-            let rows_per_thread = (a_pack.height()-1) / y_nt + 1;
-            let start_row = rows_per_thread * y_tid;
-            let end_row = cmp::min(a_pack.height(), start_row+rows_per_thread);
-
-            for panel in start_panel..end_panel {
-                let p = a_pack.get_panel(panel);
-                let ap1 = ap.offset((panel * PW::to_usize() * cs_a) as isize);
-
-                for y in start_row..end_row {
-                    for i in 0..PW::to_usize() {
-                        let alpha = ptr::read(ap1.offset((y*rs_a + i*cs_a) as isize));
-                        ptr::write(p.offset((y*PW::to_usize() + i) as isize), alpha);
-                    }
-                }
-            }
+            pack_panels(src, PW::to_usize(), 1, n_panels, a_pack.height(), panel_thr, along_thr,
+                        |panel| a_pack.get_panel(panel), 0.0, |alpha: H| alpha.to_f32());
         }
     }
 }
 
-//Specialized implementation of Packer for packing from general strided matrices into row panel
-//matrices
-impl<T: Scalar, PH: Unsigned> Copier<T, Matrix<T>, RowPanelMatrix<T, PH>> 
-    for Packer<T, Matrix<T>, RowPanelMatrix<T, PH>> {
-    fn pack(a: &mut Matrix<T>, a_pack: &mut RowPanelMatrix<T, PH>, thr: &ThreadInfo<T>) {
+//Specialized implementation of Packer for widening 16-bit float matrices into f32 row panels.
+//Partial panels are zero padded.
+impl<H: HalfElem, PH: Unsigned> Copier<f32, HalfMatrix<H>, RowPanelMatrix<f32, PH>>
+    for Packer<f32, HalfMatrix<H>, RowPanelMatrix<f32, PH>> {
+    fn pack(a: &mut HalfMatrix<H>, a_pack: &mut RowPanelMatrix<f32, PH>, thr: &ThreadInfo<f32>) {
         if a_pack.width() == 0 || a_pack.height() == 0 {
             return;
         }
         unsafe {
-            let ap = a.get_buffer();
-            let cs_a = a.get_column_stride();
-            let rs_a = a.get_row_stride();
+            let src = PanelSource{ buf: a.get_buffer(), rs: a.get_row_stride(), cs: a.get_column_stride(), h: a.height(), w: a.width() };
+            let (panel_thr, along_thr) = row_panel_split(thr.num_threads(), thr.thread_id());
+            let n_panels = (a_pack.height()-1) / PH::to_usize() + 1;
+            pack_panels(src.transposed(), PH::to_usize(), 1, n_panels, a_pack.width(), panel_thr, along_thr,
+                        |panel| a_pack.get_panel(panel), 0.0, |alpha: H| alpha.to_f32());
+        }
+    }
+}
 
-            let (y_nt, x_nt) = decompose(thr.num_threads());
-            let x_tid = thr.thread_id() / y_nt;
-            let y_tid = thr.thread_id() % y_nt;
+//Specialized implementation of Packer for packing 8-bit matrices into interleaved column panels.
+//Each K_GROUP consecutive rows of a panel are stored column by column,
+//and the panel is zero padded to a full PW columns and a multiple of K_GROUP rows.
+impl<S: QuantElem, PW: Unsigned> Copier<i32, QMatrix<S>, QColumnPanelMatrix<S, PW>>
+    for Packer<i32, QMatrix<S>, QColumnPanelMatrix<S, PW>> {
+    fn pack(a: &mut QMatrix<S>, a_pack: &mut QColumnPanelMatrix<S, PW>, thr: &ThreadInfo<i32>) {
+        if a_pack.width() == 0 || a_pack.height() == 0 {
+            return;
+        }
+        unsafe {
+            let src = PanelSource{ buf: a.get_buffer(), rs: a.get_row_stride(), cs: a.get_column_stride(), h: a.height(), w: a.width() };
+            let (panel_thr, along_thr) = column_panel_split(thr.num_threads(), thr.thread_id());
+            let n_panels = (a_pack.width()-1) / PW::to_usize() + 1;
+            let n_groups = (a_pack.height()-1) / K_GROUP + 1;
+            pack_panels(src, PW::to_usize(), K_GROUP, n_panels, n_groups, panel_thr, along_thr,
+                        |panel| a_pack.get_panel(panel), S::narrow(0), |alpha: S| alpha);
+        }
+    }
+}
 
-            //Figure out this thread's work in y direction
+//Specialized implementation of Packer for packing 8-bit matrices into interleaved row panels.
+//Each K_GROUP consecutive columns of a panel are stored row by row,
+//and the panel is zero padded to a full PH rows and a multiple of K_GROUP columns.
+impl<S: QuantElem, PH: Unsigned> Copier<i32, QMatrix<S>, QRowPanelMatrix<S, PH>>
+    for Packer<i32, QMatrix<S>, QRowPanelMatrix<S, PH>> {
+    fn pack(a: &mut QMatrix<S>, a_pack: &mut QRowPanelMatrix<S, PH>, thr: &ThreadInfo<i32>) {
+        if a_pack.width() == 0 || a_pack.height() == 0 {
+            return;
+        }
+        unsafe {
+            let src = PanelSource{ buf: a.get_buffer(), rs: a.get_row_stride(), cs: a.get_column_stride(), h: a.height(), w: a.width() };
+            let (panel_thr, along_thr) = row_panel_split(thr.num_threads(), thr.thread_id());
             let n_panels = (a_pack.height()-1) / PH::to_usize() + 1;
-            let panels_per_thread = (n_panels-1) / y_nt + 1;
-            let start_panel = panels_per_thread * y_tid;
-            let end_panel = cmp::min(n_panels, start_panel+panels_per_thread);
-
-            //Figure out this thread's work in x direction
-            let cols_per_thread = (a_pack.width()-1) / x_nt + 1;
-            let start_col = cols_per_thread * x_tid;
-            let end_col = cmp::min(a_pack.width(), start_col+cols_per_thread);
-
-            for panel in start_panel..end_panel {
-                let p = a_pack.get_panel(panel); 
-                let ap1 = ap.offset((panel * PH::to_usize() * rs_a) as isize); 
-
-                for x in start_col..end_col {
-                    for i in 0..PH::to_usize() {
-                        let alpha = ptr::read(ap1.offset((x*cs_a + i*rs_a) as isize));
-                        ptr::write(p.offset((x*PH::to_usize() + i) as isize), alpha);
-                    }
-                }
-            }
+            let n_groups = (a_pack.width()-1) / K_GROUP + 1;
+            pack_panels(src.transposed(), PH::to_usize(), K_GROUP, n_panels, n_groups, panel_thr, along_thr,
+                        |panel| a_pack.get_panel(panel), S::narrow(0), |alpha: S| alpha);
         }
     }
 }
@@ -151,8 +258,8 @@ fn score_parallelizability(m: usize, y_hier: &[HierarchyNode]) -> (usize, f64)
     (best_depth, best_score)
 }
 
-fn pack_hier_leaf<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> 
-	(a: &mut Matrix<T>, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>,
+fn pack_hier_leaf<T: Scalar, At: Mat<T> + RoCM<T>, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> 
+	(a: &mut At, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>,
 	 x_parallelize_level: isize, x_threads: usize, x_id: usize, 
      y_parallelize_level: isize, y_threads: usize, y_id: usize) {
     //Parallelize Y direction
@@ -174,15 +281,17 @@ fn pack_hier_leaf<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Uns
     };
 
     unsafe{
-        let cs_a = a.get_column_stride();
-        let rs_a = a.get_row_stride();
+        let cs_a = a.get_leaf_cs();
+        let rs_a = a.get_leaf_rs();
         let ap = a.get_buffer();
+        let conj = a.is_conjugated();
 
         let a_pack_p = a_pack.get_mut_buffer();
 
         for y in ystart..yend_a {
             for x in xstart..xend_a {
                 let alpha = ptr::read(ap.offset((y*rs_a + x*cs_a) as isize));
+                let alpha = if conj { alpha.conj() } else { alpha };
                 ptr::write(a_pack_p.offset((y*LRS::to_usize() + x*LCS::to_usize()) as isize), alpha);
             }
         }
@@ -198,8 +307,8 @@ fn pack_hier_leaf<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Uns
         }
     }
 }
-fn pack_hier_y<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> 
-    (a: &mut Matrix<T>, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>, y_hier: &[HierarchyNode], 
+fn pack_hier_y<T: Scalar, At: Mat<T> + RoCM<T>, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> 
+    (a: &mut At, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>, y_hier: &[HierarchyNode], 
     x_parallelize_level: isize, x_threads: usize, x_id: usize,
     y_parallelize_level: isize, y_threads: usize, y_id: usize) {
     if y_hier.len() - 1 == 0 { 
@@ -233,8 +342,8 @@ fn pack_hier_y<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsign
         a_pack.pop_y_view();
     }
 }
-fn pack_hier_x<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> 
-    (a: &mut Matrix<T>, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>, x_hier: &[HierarchyNode], y_hier: &[HierarchyNode],
+fn pack_hier_x<T: Scalar, At: Mat<T> + RoCM<T>, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> 
+    (a: &mut At, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>, x_hier: &[HierarchyNode], y_hier: &[HierarchyNode],
 	 x_parallelize_level: isize, x_threads: usize, x_id: usize, 
      y_parallelize_level: isize, y_threads: usize, y_id: usize)
 {
@@ -268,11 +377,9 @@ fn pack_hier_x<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsign
     }
 }
 
-//Specialized implementation of Packer for packing from Matrix<T> into Hierarch<T>
-impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> 
-    Copier<T, Matrix<T>, Hierarch<T, LH, LW, LRS, LCS>> 
-    for Packer<T, Matrix<T>, Hierarch<T, LH, LW, LRS, LCS>> {
-    default fn pack(a: &mut Matrix<T>, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>, thr: &ThreadInfo<T>) {
+//Packs a strided source, possibly logically transposed, into Hierarch<T>
+fn pack_hier<T: Scalar, At: Mat<T> + RoCM<T>, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned>
+    (a: &mut At, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>, thr: &ThreadInfo<T>) {
         if a_pack.width() == 0 || a_pack.height() == 0 {
             return;
         }
@@ -305,9 +412,72 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned>
         let y_tid = thr.thread_id() % y_nt;
 
         pack_hier_x(a, a_pack, &x_hier, &y_hier, x_depth as isize, x_nt, x_tid, y_depth as isize, y_nt, y_tid);
+}
+
+//Specialized implementation of Packer for packing from Matrix<T> into Hierarch<T>
+impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> 
+    Copier<T, Matrix<T>, Hierarch<T, LH, LW, LRS, LCS>> 
+    for Packer<T, Matrix<T>, Hierarch<T, LH, LW, LRS, LCS>> {
+    default fn pack(a: &mut Matrix<T>, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>, thr: &ThreadInfo<T>) {
+        pack_hier(a, a_pack, thr);
     }
 }
 
+//Borrowed views and transposes pack into Hierarch<T> the same way
+macro_rules! strided_hier_packers {
+    ($($at: ty),*) => {$(
+        impl<'a, T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned>
+            Copier<T, $at, Hierarch<T, LH, LW, LRS, LCS>>
+            for Packer<T, $at, Hierarch<T, LH, LW, LRS, LCS>> {
+            fn pack(a: &mut $at, a_pack: &mut Hierarch<T, LH, LW, LRS, LCS>, thr: &ThreadInfo<T>) {
+                pack_hier(a, a_pack, thr);
+            }
+        }
+    )*}
+}
+strided_hier_packers!(MatrixRef<'a, T>, Transposed<Matrix<T>>, Transposed<MatrixRef<'a, T>>);
+
+//Traced matrices report what the packer reads and writes to the simulator of the matrix packed,
+//which then traces the packed buffer too, and pack the matrices themselves
+impl<T: Scalar, At: Addressed<T>, Apt: Addressed<T>> Copier<T, Traced<At>, Traced<Apt>>
+    for Packer<T, Traced<At>, Traced<Apt>> {
+    fn pack(a: &mut Traced<At>, a_pack: &mut Traced<Apt>, thr: &ThreadInfo<T>) {
+        a_pack.set_sim(a.sim().cloned());
+        if let Some(sim) = a.sim() {
+            if thr.thread_id() == 0 { trace_pack(sim, a.inner(), a_pack.inner()); }
+        }
+        <Packer<T, At, Apt>>::pack(a.inner_mut(), a_pack.inner_mut(), thr);
+    }
+}
+
+//Packs x into x_pack, growing the shared packing buffer if it is too small.
+//All threads of thr must call this together.
+#[inline(always)]
+unsafe fn pack_into<T: Scalar, Xt: Mat<T>, XPt: Mat<T> + ResizableBuffer<T>>
+    (x: &mut Xt, x_pack: &mut XPt, y_marker: AlgorithmStep, x_marker: AlgorithmStep,
+     algo_desc: &[AlgorithmStep], thr: &ThreadInfo<T>) {
+    let capacity_for_xpt = XPt::capacity_for(x, y_marker, x_marker, algo_desc);
+    thr.barrier();
+
+    //Check if we need to resize packing buffer
+    if x_pack.capacity() < capacity_for_xpt {
+        if thr.thread_id() == 0 {
+            x_pack.aquire_buffer_for(capacity_for_xpt);
+        }
+        else {
+            x_pack.set_capacity(capacity_for_xpt);
+        }
+        x_pack.send_alias(thr);
+    }
+
+    //Logically resize the x_pack matrix
+    x_pack.resize_to(x, y_marker, x_marker, algo_desc);
+    <Packer<T, Xt, XPt>>::pack(x, x_pack, thr);
+    //The packed copy carries the scalar of x, which the kernel applies as part of alpha
+    x_pack.set_scalar(x.get_scalar());
+    thr.barrier();
+}
+
 pub struct PackA<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, APt: Mat<T>, 
     S: GemmNode<T, APt, Bt, Ct>> {
     child: S,
@@ -326,27 +496,7 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, APt: Mat<T>, S: GemmNode<T,
     where APt: ResizableBuffer<T> {
     #[inline(always)]
     unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c:&mut Ct, thr: &ThreadInfo<T>) -> () {
-        let y_marker = AlgorithmStep::M{bsz: 0};
-        let x_marker = AlgorithmStep::K{bsz: 0};
-
-        let capacity_for_apt = APt:: capacity_for(a, y_marker, x_marker, &self.algo_desc);
-        thr.barrier();
-
-        //Check if we need to resize packing buffer
-        if self.a_pack.capacity() < capacity_for_apt {
-            if thr.thread_id() == 0 {
-                self.a_pack.aquire_buffer_for(capacity_for_apt);
-            }
-            else {
-                self.a_pack.set_capacity(capacity_for_apt);
-            }
-            self.a_pack.send_alias(thr);
-        }
-
-        //Logically resize the a_pack matrix
-        self.a_pack.resize_to(a, y_marker, x_marker, &self.algo_desc);
-        <Packer<T, At, APt>>::pack(a, &mut self.a_pack, thr);
-        thr.barrier();
+        pack_into(a, &mut self.a_pack, AlgorithmStep::M{bsz: 0}, AlgorithmStep::K{bsz: 0}, &self.algo_desc, thr);
         self.child.run(&mut self.a_pack, b, c, thr);
     }
     fn new() -> Self {
@@ -361,6 +511,9 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, APt: Mat<T>, S: GemmNode<T,
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     } 
+    fn describe() -> NodeDescription {
+        describe_pack("PackA", 'A', APt::describe_layout(), S::describe())
+    }
 }
 
 pub struct PackB<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, BPt: Mat<T>, 
@@ -381,27 +534,7 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, BPt: Mat<T>, S: GemmNode<T,
     where BPt: ResizableBuffer<T> {
     #[inline(always)]
     unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c:&mut Ct, thr: &ThreadInfo<T>) -> () {
-        let y_marker = AlgorithmStep::K{bsz: 0};
-        let x_marker = AlgorithmStep::N{bsz: 0};
-        let capacity_for_bpt = BPt:: capacity_for(b, y_marker, x_marker, &self.algo_desc);
-
-        thr.barrier();
-
-        //Check if we need to resize packing buffer
-        if self.b_pack.capacity() < capacity_for_bpt {
-            if thr.thread_id() == 0 {
-                self.b_pack.aquire_buffer_for(capacity_for_bpt);
-            }
-            else {
-                self.b_pack.set_capacity(capacity_for_bpt);
-            }
-            self.b_pack.send_alias(thr);
-        }
-
-        //Logically resize the c_pack matrix
-        self.b_pack.resize_to(b, y_marker, x_marker, &self.algo_desc);
-        <Packer<T, Bt, BPt>>::pack(b, &mut self.b_pack, thr);
-        thr.barrier();
+        pack_into(b, &mut self.b_pack, AlgorithmStep::K{bsz: 0}, AlgorithmStep::N{bsz: 0}, &self.algo_desc, thr);
         self.child.run(a, &mut self.b_pack, c, thr);
     }
     fn new() -> Self {
@@ -416,4 +549,65 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, BPt: Mat<T>, S: GemmNode<T,
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     } 
+    fn describe() -> NodeDescription {
+        describe_pack("PackB", 'B', BPt::describe_layout(), S::describe())
+    }
+}
+
+//Packers for runtime-built trees. The packed format is still a type;
+//its layout comes from the hierarchy description of the child subtree.
+pub struct DynPackA<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, APt: Mat<T>> {
+    child: DynNode<T, APt, Bt, Ct>,
+    a_pack: APt,
+    algo_desc: Vec<AlgorithmStep>,
+    _at: PhantomData<At>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, APt: Mat<T>> DynPackA<T, At, Bt, Ct, APt>
+    where APt: ResizableBuffer<T> {
+    pub fn new(child: DynNode<T, APt, Bt, Ct>) -> Self {
+        let algo_desc = child.hierarchy_description();
+        let a_pack = APt::empty(AlgorithmStep::M{bsz: 0}, AlgorithmStep::K{bsz: 0}, &algo_desc);
+        DynPackA{ child: child, a_pack: a_pack, algo_desc: algo_desc, _at: PhantomData }
+    }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, APt: Mat<T>> DynGemmNode<T, At, Bt, Ct> for DynPackA<T, At, Bt, Ct, APt>
+    where APt: ResizableBuffer<T> {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c:&mut Ct, thr: &ThreadInfo<T>) -> () {
+        pack_into(a, &mut self.a_pack, AlgorithmStep::M{bsz: 0}, AlgorithmStep::K{bsz: 0}, &self.algo_desc, thr);
+        self.child.run(&mut self.a_pack, b, c, thr);
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        self.child.hierarchy_description()
+    }
+    fn describe(&self) -> NodeDescription {
+        describe_pack("DynPackA", 'A', APt::describe_layout(), self.child.describe())
+    }
+}
+
+pub struct DynPackB<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, BPt: Mat<T>> {
+    child: DynNode<T, At, BPt, Ct>,
+    b_pack: BPt,
+    algo_desc: Vec<AlgorithmStep>,
+    _bt: PhantomData<Bt>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, BPt: Mat<T>> DynPackB<T, At, Bt, Ct, BPt>
+    where BPt: ResizableBuffer<T> {
+    pub fn new(child: DynNode<T, At, BPt, Ct>) -> Self {
+        let algo_desc = child.hierarchy_description();
+        let b_pack = BPt::empty(AlgorithmStep::K{bsz: 0}, AlgorithmStep::N{bsz: 0}, &algo_desc);
+        DynPackB{ child: child, b_pack: b_pack, algo_desc: algo_desc, _bt: PhantomData }
+    }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, BPt: Mat<T>> DynGemmNode<T, At, Bt, Ct> for DynPackB<T, At, Bt, Ct, BPt>
+    where BPt: ResizableBuffer<T> {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c:&mut Ct, thr: &ThreadInfo<T>) -> () {
+        pack_into(b, &mut self.b_pack, AlgorithmStep::K{bsz: 0}, AlgorithmStep::N{bsz: 0}, &self.algo_desc, thr);
+        self.child.run(a, &mut self.b_pack, c, thr);
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        self.child.hierarchy_description()
+    }
+    fn describe(&self) -> NodeDescription {
+        describe_pack("DynPackB", 'B', BPt::describe_layout(), self.child.describe())
+    }
 }
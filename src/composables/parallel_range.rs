@@ -1,24 +1,45 @@
 use core::marker::{PhantomData};
 use matrix::{Scalar,Mat};
 use thread_comm::ThreadInfo;
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,DynGemmNode,DynNode,NodeDescription};
+use composables::describe::{describe_parallel};
+use composables::check::{check_part_m,check_part_n,expect_valid};
 use typenum::Unsigned;
 
 //Some helper types so we can specify how the parallelizers decide how many threads to use
 pub trait Nwayer{
     fn get_n_way(usize) -> usize;
+    fn nway() -> Nway;
 }
 pub struct Target<Nthr: Unsigned> { _nthr: PhantomData<Nthr> }
 impl<Nthr: Unsigned> Nwayer for Target<Nthr> {
     fn get_n_way(n_threads: usize) -> usize {
         if n_threads % Nthr::to_usize() == 0 { Nthr::to_usize() } else { 1 }
     }
+    fn nway() -> Nway { Nway::Target(Nthr::to_usize()) }
 }
 pub struct TheRest { }
 impl Nwayer for TheRest {
     fn get_n_way(n_threads: usize) -> usize {
         n_threads
     }
+    fn nway() -> Nway { Nway::TheRest }
+}
+
+//Runtime counterpart of Nwayer for the Dyn parallelizers, and how Nwayers describe themselves
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Nway {
+    //Split Nthr ways if the threads divide evenly, like Target<Nthr>
+    Target(usize),
+    TheRest,
+}
+impl Nway {
+    pub fn get_n_way(&self, n_threads: usize) -> usize {
+        match *self {
+            Nway::Target(nthr) => if n_threads % nthr == 0 { nthr } else { 1 },
+            Nway::TheRest => n_threads,
+        }
+    }
 }
 
 //Info for one thread to parallelize
@@ -28,6 +49,26 @@ struct ParallelInfo<T: Scalar> {
     work_id: usize,
 }
 
+fn make_subinfo<T: Scalar>(info: &ThreadInfo<T>, n_way: usize) -> ParallelInfo<T> {
+    let subcomm_n_threads = info.num_threads() / n_way;
+
+    //Figure out new thread IDs
+    let subinfo = info.split(n_way);
+    ParallelInfo{ thr: subinfo, n_way: n_way, 
+        work_id: info.thread_id() / subcomm_n_threads }
+}
+
+//Range of this thread's work when a dimension of iter_size is split n_way ways.
+//Ranges start on multiples of iota, so when iota is a multiple of the micro-tile
+//each element of C is computed the same way regardless of the number of threads.
+#[inline(always)]
+fn work_range(iter_size: usize, iota: usize, par_inf: &ParallelInfo<impl Scalar>) -> (usize, usize) {
+    let n_iotas = (iter_size - 1) / iota + 1;
+    let iotas_per_thread = (n_iotas - 1) / par_inf.n_way + 1;
+    let start = iota*iotas_per_thread*par_inf.work_id;
+    (start, start+iota*iotas_per_thread)
+}
+
 pub struct ParallelM<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, 
     Iota: Unsigned, Nthr: Nwayer, S: GemmNode<T, At, Bt, Ct>> {
     //Initialized Stuff
@@ -47,14 +88,7 @@ impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>,
     Iota: Unsigned, Nthr: Nwayer, S: GemmNode<T, At, Bt, Ct>> ParallelM<T,At,Bt,Ct,Iota,Nthr,S> {
     #[inline(always)]
     fn make_subinfo(&mut self, info: &ThreadInfo<T>) -> ParallelInfo<T>{
-        //First figure out how many ways to split into
-        let n_way = Nthr::get_n_way(info.num_threads());
-        let subcomm_n_threads = info.num_threads() / n_way;
-
-        //Figure out new thread IDs
-        let subinfo = info.split(n_way);
-        ParallelInfo{ thr: subinfo, n_way: n_way, 
-            work_id: info.thread_id() / subcomm_n_threads }
+        make_subinfo(info, Nthr::get_n_way(info.num_threads()))
     }
 }
 impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, 
@@ -70,11 +104,8 @@ impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>,
             }, 
         };
 
-        //Determine work range of this thread
-        let n_iotas = (a.iter_height() - 1) / Iota::to_usize() + 1;
-        let iotas_per_thread = (n_iotas - 1) / parallel_info.n_way + 1;
-        let start = Iota::to_usize()*iotas_per_thread*parallel_info.work_id;
-        let end   = start+Iota::to_usize()*iotas_per_thread;
+        //Determine work range of this thread.
+        let (start, end) = work_range(a.iter_height(), Iota::to_usize(), parallel_info);
 
         a.push_y_split(start, end);
         c.push_y_split(start, end);
@@ -86,6 +117,8 @@ impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>,
         c.pop_y_split();
     }
     fn new() -> Self {
+        //Threads' ranges start on multiples of iota
+        expect_valid(check_part_m::<T, At, Ct>("ParallelM", Iota::to_usize()));
         ParallelM{ child: S::new(), par_inf: Option::None,
             _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData,
             _iotat: PhantomData, _nthr: PhantomData }
@@ -93,6 +126,9 @@ impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>,
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     } 
+    fn describe() -> NodeDescription {
+        describe_parallel("ParallelM", 'm', Iota::to_usize(), Nthr::nway(), S::describe())
+    }
 }
 
 pub struct ParallelN<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, 
@@ -114,14 +150,7 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Iota: Unsigned, Nthr: Nwayer
     ParallelN<T,At,Bt,Ct,Iota,Nthr,S> {
     #[inline(always)]
     fn make_subinfo(&mut self, info: &ThreadInfo<T>) -> ParallelInfo<T>{
-        //First figure out how many ways to split into
-        let n_way = Nthr::get_n_way(info.num_threads());
-        let subcomm_n_threads = info.num_threads() / n_way;
-
-        //Figure out new thread IDs
-        let subinfo = info.split(n_way);
-        ParallelInfo{ thr: subinfo, n_way: n_way, 
-            work_id: info.thread_id() / subcomm_n_threads }
+        make_subinfo(info, Nthr::get_n_way(info.num_threads()))
     }
 }
 impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, 
@@ -137,11 +166,8 @@ impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>,
             }, 
         };
 
-        //Determine work range of this thread
-        let n_iotas = (b.iter_width() - 1) / Iota::to_usize() + 1;
-        let iotas_per_thread = (n_iotas - 1) / parallel_info.n_way + 1;
-        let start = Iota::to_usize()*iotas_per_thread*parallel_info.work_id;
-        let end   = start+Iota::to_usize()*iotas_per_thread;
+        //Determine work range of this thread.
+        let (start, end) = work_range(b.iter_width(), Iota::to_usize(), parallel_info);
 
         b.push_x_split(start, end);
         c.push_x_split(start, end);
@@ -153,6 +179,8 @@ impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>,
         c.pop_x_split();
     }
     fn new() -> Self {
+        //Threads' ranges start on multiples of iota
+        expect_valid(check_part_n::<T, Bt, Ct>("ParallelN", Iota::to_usize()));
         ParallelN{ child: S::new(), par_inf: Option::None,
             _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData,
             _iotat: PhantomData, _nthr: PhantomData }
@@ -160,4 +188,76 @@ impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>,
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     }
+    fn describe() -> NodeDescription {
+        describe_parallel("ParallelN", 'n', Iota::to_usize(), Nthr::nway(), S::describe())
+    }
+}
+
+//Parallelizers with iota and the number of ways chosen at runtime
+pub struct DynParallelM<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> {
+    iota: usize,
+    nway: Nway,
+    par_inf: Option<ParallelInfo<T>>,
+    child: DynNode<T, At, Bt, Ct>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynParallelM<T, At, Bt, Ct> {
+    pub fn new(iota: usize, nway: Nway, child: DynNode<T, At, Bt, Ct>) -> Self {
+        expect_valid(check_part_m::<T, At, Ct>("DynParallelM", iota));
+        DynParallelM{ iota: iota, nway: nway, par_inf: None, child: child }
+    }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynGemmNode<T, At, Bt, Ct> for DynParallelM<T, At, Bt, Ct> {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c:&mut Ct, thr: &ThreadInfo<T>) -> () {
+        if self.par_inf.is_none() {
+            self.par_inf = Some(make_subinfo(thr, self.nway.get_n_way(thr.num_threads())));
+        }
+        let parallel_info = self.par_inf.as_ref().unwrap();
+        let (start, end) = work_range(a.iter_height(), self.iota, parallel_info);
+
+        a.push_y_split(start, end);
+        c.push_y_split(start, end);
+        self.child.run(a, b, c, &parallel_info.thr);
+        a.pop_y_split();
+        c.pop_y_split();
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        self.child.hierarchy_description()
+    }
+    fn describe(&self) -> NodeDescription {
+        describe_parallel("DynParallelM", 'm', self.iota, self.nway, self.child.describe())
+    }
+}
+
+pub struct DynParallelN<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> {
+    iota: usize,
+    nway: Nway,
+    par_inf: Option<ParallelInfo<T>>,
+    child: DynNode<T, At, Bt, Ct>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynParallelN<T, At, Bt, Ct> {
+    pub fn new(iota: usize, nway: Nway, child: DynNode<T, At, Bt, Ct>) -> Self {
+        expect_valid(check_part_n::<T, Bt, Ct>("DynParallelN", iota));
+        DynParallelN{ iota: iota, nway: nway, par_inf: None, child: child }
+    }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynGemmNode<T, At, Bt, Ct> for DynParallelN<T, At, Bt, Ct> {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c:&mut Ct, thr: &ThreadInfo<T>) -> () {
+        if self.par_inf.is_none() {
+            self.par_inf = Some(make_subinfo(thr, self.nway.get_n_way(thr.num_threads())));
+        }
+        let parallel_info = self.par_inf.as_ref().unwrap();
+        let (start, end) = work_range(b.iter_width(), self.iota, parallel_info);
+
+        b.push_x_split(start, end);
+        c.push_x_split(start, end);
+        self.child.run(a, b, c, &parallel_info.thr);
+        b.pop_x_split();
+        c.pop_x_split();
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        self.child.hierarchy_description()
+    }
+    fn describe(&self) -> NodeDescription {
+        describe_parallel("DynParallelN", 'n', self.iota, self.nway, self.child.describe())
+    }
 }
@@ -1,6 +1,8 @@
 use matrix::{Scalar,Mat};
 use thread_comm::ThreadInfo;
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,DynGemmNode,DynNode,NodeDescription};
+use composables::describe::{describe_part,describe_first_diff_part};
+use composables::check::{check_part_m,check_part_n,check_part_k,check_same_hierarchy,expect_valid};
 use core::marker::PhantomData;
 use typenum::Unsigned;
 
@@ -32,13 +34,17 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S: GemmNode<T
         c.pop_y_view();
     }
     fn new() -> Self {
-            PartM{ child: S::new(), _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
+        expect_valid(check_part_m::<T, At, Ct>("PartM", Bsz::to_usize()));
+        PartM{ child: S::new(), _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
     }
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         let mut child_desc = S::hierarchy_description();
         child_desc.push(AlgorithmStep::M{ bsz: Bsz::to_usize() });
         child_desc
     }
+    fn describe() -> NodeDescription {
+        describe_part("PartM", 'm', Bsz::to_usize(), S::describe())
+    }
 }
 
 pub struct PartN<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S: GemmNode<T, At, Bt, Ct>> {
@@ -69,13 +75,17 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S: GemmNode<T
         c.pop_x_view();
     }
     fn new() -> Self {
-            PartN{ child: S::new(), _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
+        expect_valid(check_part_n::<T, Bt, Ct>("PartN", Bsz::to_usize()));
+        PartN{ child: S::new(), _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
     }
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         let mut child_desc = S::hierarchy_description();
         child_desc.push(AlgorithmStep::N{ bsz: Bsz::to_usize() });
         child_desc
     }
+    fn describe() -> NodeDescription {
+        describe_part("PartN", 'n', Bsz::to_usize(), S::describe())
+    }
 }
 
 pub struct PartK<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S: GemmNode<T, At, Bt, Ct>> {
@@ -110,6 +120,7 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S: GemmNode<T
         c.set_scalar(beta_save);
     }
     fn new() -> Self {
+        expect_valid(check_part_k::<T, At, Bt>("PartK", Bsz::to_usize()));
         PartK{ child: S::new(), _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
     }
     fn hierarchy_description() -> Vec<AlgorithmStep> {
@@ -117,6 +128,9 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S: GemmNode<T
         child_desc.push(AlgorithmStep::K{ bsz: Bsz::to_usize() });
         child_desc
     }
+    fn describe() -> NodeDescription {
+        describe_part("PartK", 'k', Bsz::to_usize(), S::describe())
+    }
 }
 
 pub struct FirstDiffPartM<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, 
@@ -156,16 +170,19 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S1, S2>
         c.pop_y_view();
     }
     fn new() -> Self {
-            FirstDiffPartM{ child1: S1::new(), child2: S2::new(),  _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
+        expect_valid(check_part_m::<T, At, Ct>("FirstDiffPartM", Bsz::to_usize()));
+        expect_valid(check_same_hierarchy("FirstDiffPartM", &S1::hierarchy_description(), &S2::hierarchy_description()));
+        FirstDiffPartM{ child1: S1::new(), child2: S2::new(),  _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
     }
     fn hierarchy_description() -> Vec<AlgorithmStep> {
-        //There could be two different hierarchy descriptions here!
-        //TODO: We might want a runtime check to make sure the two hierarchy descriptions are
-        //identical. Maybe only in debug mode.
+        //new() checks that the two hierarchy descriptions are identical
         let mut child_desc = S1::hierarchy_description();
         child_desc.push(AlgorithmStep::M{ bsz: Bsz::to_usize() });
         child_desc
     }
+    fn describe() -> NodeDescription {
+        describe_first_diff_part("FirstDiffPartM", 'm', Bsz::to_usize(), S1::describe(), S2::describe())
+    }
 }
 
 pub struct FirstDiffPartN<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, 
@@ -204,13 +221,18 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S1, S2>
         c.pop_x_view();
     }
     fn new() -> Self {
-            FirstDiffPartN{ child1: S1::new(), child2: S2::new(), _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
+        expect_valid(check_part_n::<T, Bt, Ct>("FirstDiffPartN", Bsz::to_usize()));
+        expect_valid(check_same_hierarchy("FirstDiffPartN", &S1::hierarchy_description(), &S2::hierarchy_description()));
+        FirstDiffPartN{ child1: S1::new(), child2: S2::new(), _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
     }
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         let mut child_desc = S1::hierarchy_description();
         child_desc.push(AlgorithmStep::N{ bsz: Bsz::to_usize() });
         child_desc
     }
+    fn describe() -> NodeDescription {
+        describe_first_diff_part("FirstDiffPartN", 'n', Bsz::to_usize(), S1::describe(), S2::describe())
+    }
 }
 
 pub struct FirstDiffPartK<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned,
@@ -254,6 +276,8 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S1, S2>
         c.set_scalar(beta_save);
     }
     fn new() -> Self {
+        expect_valid(check_part_k::<T, At, Bt>("FirstDiffPartK", Bsz::to_usize()));
+        expect_valid(check_same_hierarchy("FirstDiffPartK", &S1::hierarchy_description(), &S2::hierarchy_description()));
         FirstDiffPartK{ child1: S1::new(), child2: S2::new(), _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _bszt: PhantomData }
     }
     fn hierarchy_description() -> Vec<AlgorithmStep> {
@@ -261,4 +285,123 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Bsz: Unsigned, S1, S2>
         child_desc.push(AlgorithmStep::K{ bsz: Bsz::to_usize() });
         child_desc
     }
+    fn describe() -> NodeDescription {
+        describe_first_diff_part("FirstDiffPartK", 'k', Bsz::to_usize(), S1::describe(), S2::describe())
+    }
+}
+
+//Partitioners with the block size chosen at runtime
+pub struct DynPartM<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> {
+    bsz: usize,
+    child: DynNode<T, At, Bt, Ct>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynPartM<T, At, Bt, Ct> {
+    pub fn new(bsz: usize, child: DynNode<T, At, Bt, Ct>) -> Self {
+        expect_valid(check_part_m::<T, At, Ct>("DynPartM", bsz));
+        DynPartM{ bsz: bsz, child: child }
+    }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynGemmNode<T, At, Bt, Ct> for DynPartM<T, At, Bt, Ct> {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, thr: &ThreadInfo<T>) -> () {
+        let m_save = a.push_y_view(self.bsz);
+        c.push_y_view(self.bsz);
+
+        let mut i = 0;
+        while i < m_save  {
+            a.slide_y_view_to(i, self.bsz);
+            c.slide_y_view_to(i, self.bsz);
+
+            self.child.run(a, b, c, thr);
+            i += self.bsz;
+        }
+
+        a.pop_y_view();
+        c.pop_y_view();
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        let mut child_desc = self.child.hierarchy_description();
+        child_desc.push(AlgorithmStep::M{ bsz: self.bsz });
+        child_desc
+    }
+    fn describe(&self) -> NodeDescription {
+        describe_part("DynPartM", 'm', self.bsz, self.child.describe())
+    }
+}
+
+pub struct DynPartN<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> {
+    bsz: usize,
+    child: DynNode<T, At, Bt, Ct>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynPartN<T, At, Bt, Ct> {
+    pub fn new(bsz: usize, child: DynNode<T, At, Bt, Ct>) -> Self {
+        expect_valid(check_part_n::<T, Bt, Ct>("DynPartN", bsz));
+        DynPartN{ bsz: bsz, child: child }
+    }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynGemmNode<T, At, Bt, Ct> for DynPartN<T, At, Bt, Ct> {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, thr: &ThreadInfo<T>) -> () {
+        let n_save = b.push_x_view(self.bsz);
+        c.push_x_view(self.bsz);
+
+        let mut i = 0;
+        while i < n_save {
+            b.slide_x_view_to(i, self.bsz);
+            c.slide_x_view_to(i, self.bsz);
+
+            self.child.run(a, b, c, thr);
+            i += self.bsz;
+        }
+
+        b.pop_x_view();
+        c.pop_x_view();
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        let mut child_desc = self.child.hierarchy_description();
+        child_desc.push(AlgorithmStep::N{ bsz: self.bsz });
+        child_desc
+    }
+    fn describe(&self) -> NodeDescription {
+        describe_part("DynPartN", 'n', self.bsz, self.child.describe())
+    }
+}
+
+pub struct DynPartK<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> {
+    bsz: usize,
+    child: DynNode<T, At, Bt, Ct>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynPartK<T, At, Bt, Ct> {
+    pub fn new(bsz: usize, child: DynNode<T, At, Bt, Ct>) -> Self {
+        expect_valid(check_part_k::<T, At, Bt>("DynPartK", bsz));
+        DynPartK{ bsz: bsz, child: child }
+    }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynGemmNode<T, At, Bt, Ct> for DynPartK<T, At, Bt, Ct> {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, thr: &ThreadInfo<T>) -> () {
+        let k_save = a.push_x_view(self.bsz);
+        b.push_y_view(self.bsz);
+
+        let beta_save = c.get_scalar();
+
+        let mut i = 0;
+        while i < k_save  {
+            a.slide_x_view_to(i, self.bsz);
+            b.slide_y_view_to(i, self.bsz);
+
+            self.child.run(a, b, c, thr);
+            i += self.bsz;
+            c.set_scalar(T::one());
+        }
+
+        a.pop_x_view();
+        b.pop_y_view();
+        c.set_scalar(beta_save);
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        let mut child_desc = self.child.hierarchy_description();
+        child_desc.push(AlgorithmStep::K{ bsz: self.bsz });
+        child_desc
+    }
+    fn describe(&self) -> NodeDescription {
+        describe_part("DynPartK", 'k', self.bsz, self.child.describe())
+    }
 }
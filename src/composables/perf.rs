@@ -0,0 +1,197 @@
+use matrix::{Scalar,Mat};
+use thread_comm::ThreadInfo;
+use composables::{GemmNode,AlgorithmStep,NodeDescription,DynGemmNode,DynNode};
+use core::marker::PhantomData;
+use core::mem;
+use std::sync::Mutex;
+use libc;
+
+//Counts of one run of a subtree by one thread. Events the kernel or the core doesn't support are None.
+//Counts are scaled up when the kernel had to multiplex the counters.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PerfSample {
+    //Id of the thread among all those spawned by SpawnThreads, not within the parallelizer it runs under
+    pub thread_id: usize,
+    pub cycles: Option<u64>,
+    pub instructions: Option<u64>,
+    pub l1d_misses: Option<u64>,
+    pub l2_misses: Option<u64>,
+    pub llc_misses: Option<u64>,
+    pub dtlb_misses: Option<u64>,
+}
+
+//Samples of every run, from every thread, since the last call to take_perf_samples
+static SAMPLES: Mutex<Vec<PerfSample>> = Mutex::new(Vec::new());
+
+pub fn take_perf_samples() -> Vec<PerfSample> {
+    mem::replace(&mut *SAMPLES.lock().unwrap(), Vec::new())
+}
+
+//struct perf_event_attr from linux/perf_event.h, PERF_ATTR_SIZE_VER5
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+    config2: u64,
+    branch_sample_type: u64,
+    sample_regs_user: u64,
+    sample_stack_user: u32,
+    clockid: i32,
+    sample_regs_intr: u64,
+    aux_watermark: u32,
+    sample_max_stack: u16,
+    reserved: u16,
+}
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_TYPE_HW_CACHE: u32 = 3;
+const PERF_TYPE_RAW: u32 = 4;
+const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+//Cache events are the cache | operation << 8 | result << 16
+const PERF_COUNT_HW_CACHE_L1D: u64 = 0;
+const PERF_COUNT_HW_CACHE_DTLB: u64 = 3;
+const PERF_COUNT_HW_CACHE_OP_READ: u64 = 0;
+const PERF_COUNT_HW_CACHE_RESULT_MISS: u64 = 1;
+//There is no generic L2 event, so this is L2_RQSTS.MISS of Intel cores since Skylake
+const INTEL_L2_RQSTS_MISS: u64 = 0x3f24;
+//Models of family 6 where L2_RQSTS.MISS is 0x3f24: Skylake, Skylake-X, Kaby Lake, Coffee Lake,
+//Cannon Lake, Ice Lake, Tiger Lake, Comet Lake and Rocket Lake
+const L2_RQSTS_MISS_MODELS: [u32; 14] = [0x4e, 0x5e, 0x55, 0x8e, 0x9e, 0x66, 0x6a, 0x6c, 0x7d, 0x7e, 0x8c, 0x8d, 0xa5, 0xa7];
+
+//disabled, exclude_kernel and exclude_hv, so unprivileged users can count their own threads
+const FLAGS: u64 = 1 | 1 << 5 | 1 << 6;
+//The count is followed by the time the event was enabled and the time it was counting
+const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1;
+const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 2;
+
+const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+
+//The raw L2 miss event of this core, if it is one whose encoding is known.
+//Raw events mean something else on other cores, and open without error, so they aren't guessed.
+#[cfg(target_arch="x86_64")]
+#[allow(unused_unsafe)]
+fn l2_miss_event() -> Option<u64> {
+    use std::arch::x86_64::__cpuid;
+    let (leaf0, leaf1) = unsafe { (__cpuid(0), __cpuid(1)) };
+    let intel = leaf0.ebx == 0x756e_6547 && leaf0.edx == 0x4965_6e69 && leaf0.ecx == 0x6c65_746e;
+    let family = (leaf1.eax >> 8) & 0xf;
+    let model = (leaf1.eax >> 4) & 0xf | (leaf1.eax >> 12) & 0xf0;
+    if intel && family == 6 && L2_RQSTS_MISS_MODELS.contains(&model) { Some(INTEL_L2_RQSTS_MISS) } else { None }
+}
+
+#[cfg(not(target_arch="x86_64"))]
+fn l2_miss_event() -> Option<u64> {
+    None
+}
+
+//The events of PerfSample, in order, or None for those this core can't count
+fn events() -> [Option<(u32, u64)>; 6] {
+    [Some((PERF_TYPE_HARDWARE, PERF_COUNT_HW_CPU_CYCLES)),
+     Some((PERF_TYPE_HARDWARE, PERF_COUNT_HW_INSTRUCTIONS)),
+     Some((PERF_TYPE_HW_CACHE, PERF_COUNT_HW_CACHE_L1D | PERF_COUNT_HW_CACHE_OP_READ << 8 | PERF_COUNT_HW_CACHE_RESULT_MISS << 16)),
+     l2_miss_event().map(|config| (PERF_TYPE_RAW, config)),
+     Some((PERF_TYPE_HARDWARE, PERF_COUNT_HW_CACHE_MISSES)),
+     Some((PERF_TYPE_HW_CACHE, PERF_COUNT_HW_CACHE_DTLB | PERF_COUNT_HW_CACHE_OP_READ << 8 | PERF_COUNT_HW_CACHE_RESULT_MISS << 16))]
+}
+
+//Counters of the calling thread on whichever core it runs, or None if the event can't be opened
+fn open_counter(type_: u32, config: u64) -> Option<libc::c_int> {
+    let attr = PerfEventAttr{ type_: type_, size: mem::size_of::<PerfEventAttr>() as u32, config: config,
+        read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING, flags: FLAGS,
+        ..PerfEventAttr::default() };
+    let fd = unsafe { libc::syscall(libc::SYS_perf_event_open, &attr as *const PerfEventAttr, 0, -1, -1, 0) };
+    if fd < 0 { None } else { Some(fd as libc::c_int) }
+}
+
+fn read_counter(fd: libc::c_int) -> Option<u64> {
+    let mut values = [0u64; 3];
+    let size = mem::size_of_val(&values);
+    let read = unsafe { libc::read(fd, values.as_mut_ptr() as *mut libc::c_void, size) };
+    let (count, enabled, running) = (values[0], values[1], values[2]);
+    if read as usize != size || running == 0 { return None; }
+    Some((count as f64 * enabled as f64 / running as f64) as u64)
+}
+
+//Counts the events of the calling thread while run runs
+fn count<F: FnOnce()>(thread_id: usize, run: F) {
+    let fds: Vec<Option<libc::c_int>> = events().iter()
+        .map(|event| event.and_then(|(type_, config)| open_counter(type_, config))).collect();
+    for fd in fds.iter().filter_map(|&fd| fd) {
+        unsafe { libc::ioctl(fd, PERF_EVENT_IOC_RESET, 0); libc::ioctl(fd, PERF_EVENT_IOC_ENABLE, 0); }
+    }
+    run();
+    for fd in fds.iter().filter_map(|&fd| fd) {
+        unsafe { libc::ioctl(fd, PERF_EVENT_IOC_DISABLE, 0); }
+    }
+    let counts: Vec<Option<u64>> = fds.iter().map(|&fd| fd.and_then(read_counter)).collect();
+    for fd in fds.iter().filter_map(|&fd| fd) {
+        unsafe { libc::close(fd); }
+    }
+    SAMPLES.lock().unwrap().push(PerfSample{ thread_id: thread_id, cycles: counts[0], instructions: counts[1],
+        l1d_misses: counts[2], l2_misses: counts[3], llc_misses: counts[4], dtlb_misses: counts[5] });
+}
+
+fn describe_perf(name: &'static str, child: NodeDescription) -> NodeDescription {
+    NodeDescription::new(name, "count cycles, instructions and cache and TLB misses of each thread".to_string()).then(child)
+}
+
+//Counts hardware events while its subtree runs, once per thread and run, into take_perf_samples().
+//Below SpawnThreads every thread is counted; above it only the calling thread is.
+pub struct PerfCounters<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S: GemmNode<T, At, Bt, Ct>> {
+    child: S,
+    _t: PhantomData<T>,
+    _at: PhantomData<At>,
+    _bt: PhantomData<Bt>,
+    _ct: PhantomData<Ct>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S: GemmNode<T, At, Bt, Ct>>
+    GemmNode<T, At, Bt, Ct> for PerfCounters<T,At,Bt,Ct,S> {
+    #[inline(always)]
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, thr: &ThreadInfo<T>) -> () {
+        let child = &mut self.child;
+        count(thr.global_thread_id(), || child.run(a, b, c, thr));
+    }
+    fn new() -> Self {
+        PerfCounters{ child: S::new(), _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData }
+    }
+    fn hierarchy_description() -> Vec<AlgorithmStep> {
+        S::hierarchy_description()
+    }
+    fn describe() -> NodeDescription {
+        describe_perf("PerfCounters", S::describe())
+    }
+}
+
+pub struct DynPerfCounters<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> {
+    child: DynNode<T, At, Bt, Ct>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynPerfCounters<T, At, Bt, Ct> {
+    pub fn new(child: DynNode<T, At, Bt, Ct>) -> Self {
+        DynPerfCounters{ child: child }
+    }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynGemmNode<T, At, Bt, Ct> for DynPerfCounters<T, At, Bt, Ct> {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, thr: &ThreadInfo<T>) -> () {
+        let child = &mut self.child;
+        count(thr.global_thread_id(), || child.run(a, b, c, thr));
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        self.child.hierarchy_description()
+    }
+    fn describe(&self) -> NodeDescription {
+        describe_perf("DynPerfCounters", self.child.describe())
+    }
+}
@@ -0,0 +1,140 @@
+use core::mem;
+use matrix::Matrix;
+use composables::{TreeBuilder,Nway,DynNode,DynScalar};
+use MommsError;
+
+//Sizes of the caches in bytes. L1 and L2 are private to each core, and L3 is shared by all threads.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CacheSizes {
+    pub l1: usize,
+    pub l2: usize,
+    pub l3: usize,
+}
+impl Default for CacheSizes {
+    //The caches the block sizes in exper were picked for
+    fn default() -> CacheSizes {
+        CacheSizes{ l1: 32 * 1024, l2: 256 * 1024, l3: 8 * 1024 * 1024 }
+    }
+}
+
+//The algorithm families compared in exper/l3_shapes.rs.
+//They differ in which operand is reused out of the L3 cache.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Family {
+    //A panel of B is resident in the L3 cache and C is updated once per Kc
+    Goto,
+    //A square block of A is resident in the L3 cache
+    L3A,
+    //A square block of B is resident in the L3 cache
+    L3B,
+    //A square block of C is resident in the L3 cache
+    L3C,
+}
+impl Family {
+    pub fn all() -> [Family; 4] {
+        [Family::Goto, Family::L3B, Family::L3A, Family::L3C]
+    }
+}
+
+//Block sizes for one kernel shape
+struct Blocks {
+    mr: usize,
+    nr: usize,
+    //The micro-panels of A and B share the L1 cache
+    kc: usize,
+    //A kc deep block of the operand packed for the L2 cache, in multiples of l2_unit
+    l2: usize,
+    //A kc deep panel of B in the L3 cache, for Goto
+    nc: usize,
+    //Square blocks of the resident operand for L3A and L3B, and of C for L3C
+    s3: usize,
+    s3c: usize,
+}
+
+pub fn round_down(x: usize, unit: usize) -> usize {
+    if x < unit { unit } else { x / unit * unit }
+}
+
+//Chooses among the algorithm families by the shape of the problem.
+//Each family is charged for the elements it moves between memory and the L3 cache,
+//and the one that moves the fewest is built with block sizes derived from the caches.
+pub struct Planner {
+    n_threads: usize,
+    caches: CacheSizes,
+}
+impl Planner {
+    pub fn new(n_threads: usize, caches: CacheSizes) -> Planner {
+        Planner{ n_threads: n_threads, caches: caches }
+    }
+
+    //Goto, L3B and L3C use the first kernel shape of T, and L3A uses its transpose
+    fn kernel_shape<T: DynScalar>(family: Family) -> (usize, usize) {
+        let shapes = T::kernel_shapes();
+        let (mr, nr) = shapes[0];
+        if family == Family::L3A && shapes.contains(&(nr, mr)) { (nr, mr) } else { (mr, nr) }
+    }
+    fn blocks<T: DynScalar>(&self, family: Family) -> Blocks {
+        let elem = mem::size_of::<T>();
+        let (mr, nr) = Self::kernel_shape::<T>(family);
+        let kc = round_down(self.caches.l1 * 3 / 4 / ((mr + nr) * elem), 8);
+        //L3A packs B for the L2 cache, and the others pack A
+        let l2_unit = if family == Family::L3A { nr } else { mr };
+        let l2 = round_down(self.caches.l2 * 3 / 4 / (kc * elem), l2_unit);
+        let nc = round_down(self.caches.l3 / 2 / (kc * elem), nr);
+        let l3_elems = self.caches.l3 * 3 / 5 / elem;
+        let s3 = round_down((l3_elems as f64).sqrt() as usize, kc);
+        //The block of C shares the cache with a kc deep panel each of A and B
+        let s3c = round_down(((kc * kc + l3_elems) as f64).sqrt() as usize - kc, kc);
+        Blocks{ mr: mr, nr: nr, kc: kc, l2: l2, nc: nc, s3: s3, s3c: s3c }
+    }
+
+    //Elements of A, B and C moved between memory and the L3 cache when family computes an m x n x k gemm.
+    //Operands are read once per pass over them, and C is also written back.
+    pub fn traffic<T: DynScalar>(&self, family: Family, m: usize, n: usize, k: usize) -> f64 {
+        let b = self.blocks::<T>(family);
+        let passes = |dim: usize, bsz: usize| ((dim + bsz - 1) / bsz) as f64;
+        let (a_size, b_size, c_size) = ((m * k) as f64, (k * n) as f64, (m * n) as f64);
+        match family {
+            Family::Goto => b_size + a_size * passes(n, b.nc) + 2.0 * c_size * passes(k, b.kc),
+            Family::L3A => a_size + b_size * passes(m, b.s3) + 2.0 * c_size * passes(k, b.s3),
+            Family::L3B => b_size + a_size * passes(n, b.s3) + 2.0 * c_size * passes(k, b.s3),
+            Family::L3C => 2.0 * c_size + a_size * passes(n, b.s3c) + b_size * passes(m, b.s3c),
+        }
+    }
+
+    //The family that moves the fewest elements, preferring the earlier of Family::all() on ties
+    pub fn choose<T: DynScalar>(&self, m: usize, n: usize, k: usize) -> Family {
+        let mut best = Family::Goto;
+        let mut best_traffic = self.traffic::<T>(best, m, n, k);
+        for &family in Family::all().iter() {
+            let traffic = self.traffic::<T>(family, m, n, k);
+            if traffic < best_traffic {
+                best = family;
+                best_traffic = traffic;
+            }
+        }
+        best
+    }
+
+    //The control tree of family for these caches and threads
+    pub fn tree<T: DynScalar>(&self, family: Family) -> TreeBuilder {
+        let b = self.blocks::<T>(family);
+        let root = TreeBuilder::new().spawn(self.n_threads);
+        match family {
+            Family::Goto => root.part_n(b.nc).part_k(b.kc).pack_b(b.nr).part_m(b.l2).pack_a(b.mr)
+                .parallel_n(b.nr, Nway::TheRest).kernel_nm(b.nr, b.mr),
+            Family::L3A => root.part_m(b.s3).part_k(b.s3).pack_a(b.mr).part_n(b.l2).part_k(b.kc).pack_b(b.nr)
+                .parallel_m(b.mr, Nway::TheRest).kernel_mn(b.mr, b.nr),
+            Family::L3B => root.part_n(b.s3).part_k(b.s3).pack_b(b.nr).part_m(b.l2).part_k(b.kc).pack_a(b.mr)
+                .parallel_n(b.nr, Nway::TheRest).kernel_nm(b.nr, b.mr),
+            Family::L3C => root.part_n(b.s3c).part_m(b.s3c).part_k(b.kc).pack_b(b.nr).part_m(b.l2).pack_a(b.mr)
+                .parallel_n(b.nr, Nway::TheRest).kernel_nm(b.nr, b.mr),
+        }
+    }
+
+    //Builds the tree of the family chosen for an m x n x k gemm
+    pub fn plan<T: DynScalar>(&self, m: usize, n: usize, k: usize)
+        -> Result<DynNode<T, Matrix<T>, Matrix<T>, Matrix<T>>, MommsError> {
+        self.tree::<T>(self.choose::<T>(m, n, k)).build::<T>()
+    }
+}
@@ -0,0 +1,58 @@
+use core::marker::PhantomData;
+
+use matrix::{Mat,Matrix,QuantElem,QMatrix};
+use thread_comm::ThreadInfo;
+use composables::{GemmNode,AlgorithmStep,NodeDescription};
+
+//Epilogue of an integer gemm with 8-bit output.
+//The child accumulates A*B into an i32 matrix, which is then requantized into C as
+//    C = saturate(round(scale * AB) + zero_point)
+//C is overwritten, so its scalar is ignored.
+//The scale and zero point are set at runtime, so this node must sit above SpawnThreads.
+pub struct Requantize<So: QuantElem, At: Mat<i32>, Bt: Mat<i32>, S: GemmNode<i32, At, Bt, Matrix<i32>>> {
+    child: S,
+    acc: Matrix<i32>,
+    scale: f32,
+    zero_point: i32,
+    _sot: PhantomData<So>,
+    _at: PhantomData<At>,
+    _bt: PhantomData<Bt>,
+}
+impl<So: QuantElem, At: Mat<i32>, Bt: Mat<i32>, S: GemmNode<i32, At, Bt, Matrix<i32>>> Requantize<So,At,Bt,S> {
+    pub fn set_requantization(&mut self, scale: f32, zero_point: i32) {
+        self.scale = scale;
+        self.zero_point = zero_point;
+    }
+}
+impl<So: QuantElem, At: Mat<i32>, Bt: Mat<i32>, S: GemmNode<i32, At, Bt, Matrix<i32>>>
+    GemmNode<i32, At, Bt, QMatrix<So>> for Requantize<So, At, Bt, S> {
+    #[inline(always)]
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut QMatrix<So>, thr: &ThreadInfo<i32>) -> () {
+        //Each thread would requantize all of C, racing with the others
+        assert_eq!(thr.num_threads(), 1, "Requantize must be above SpawnThreads");
+
+        if self.acc.height() != c.height() || self.acc.width() != c.width() {
+            self.acc = Matrix::new(c.height(), c.width());
+        }
+        self.acc.set_scalar(0);
+        self.child.run(a, b, &mut self.acc, thr);
+
+        for x in 0..c.width() {
+            for y in 0..c.height() {
+                let alpha = (self.acc.get(y,x) as f32 * self.scale).round() as i32;
+                //QMatrix saturates on set
+                c.set(y, x, alpha.saturating_add(self.zero_point));
+            }
+        }
+    }
+    fn new() -> Self {
+        Requantize{ child: S::new(), acc: Matrix::new(0, 0), scale: 1.0, zero_point: 0,
+                    _sot: PhantomData, _at: PhantomData, _bt: PhantomData }
+    }
+    fn hierarchy_description() -> Vec<AlgorithmStep> {
+        S::hierarchy_description()
+    }
+    fn describe() -> NodeDescription {
+        NodeDescription::new("Requantize", "accumulate into a Matrix<i32>, then requantize it into C".to_string()).nest(S::describe())
+    }
+}
@@ -0,0 +1,184 @@
+use core::{cmp, mem};
+use std::sync::{Arc, Mutex};
+use composables::{TreeBuilder,NodeSpec,CacheLevel,DynScalar};
+use matrix::{Scalar,Mat,RoCM,Addressed,Matrix,Traced};
+use thread_comm::ThreadInfo;
+use MommsError;
+
+//Traffic into one level of cache from the level beyond it
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LevelTraffic {
+    pub accesses: u64,
+    pub misses: u64,
+    //Dirty lines evicted to the level beyond. Lines still dirty when the simulation ends are not counted.
+    pub writebacks: u64,
+    //Misses and writebacks, in bytes
+    pub bytes: u64,
+}
+
+//A set associative, write back, write allocate cache with LRU replacement
+struct LruCache {
+    level: CacheLevel,
+    //Each set holds (line, dirty) from the most to the least recently used
+    sets: Vec<Vec<(u64, bool)>>,
+    traffic: LevelTraffic,
+}
+impl LruCache {
+    fn new(level: CacheLevel) -> LruCache {
+        LruCache{ level: level, sets: vec![Vec::new(); level.sets()], traffic: LevelTraffic::default() }
+    }
+}
+
+//A model of one core's caches that control trees run through on Traced matrices.
+//The addresses are those of the real buffers, so a typed tree on any of the formats
+//runs through it just as it runs on them, including on Hierarch matrices.
+//Each level is set associative, write back and write allocate, with LRU replacement.
+//The kernels report their micro-panels and micro-tiles, and the packers each element
+//they copy, so neither prefetching, the TLB nor the order of accesses within a micro-tile are modeled.
+//Threads would interleave their accesses arbitrarily, so trees should run on one thread.
+pub struct CacheSim {
+    caches: Vec<LruCache>,
+}
+impl CacheSim {
+    pub fn new(levels: &[CacheLevel]) -> CacheSim {
+        CacheSim{ caches: levels.iter().map(|&level| LruCache::new(level)).collect() }
+    }
+
+    //The traffic into each level since the last reset, from L1 outwards.
+    //The last entry is the traffic between the last level and memory.
+    pub fn traffic(&self) -> Vec<LevelTraffic> {
+        self.caches.iter().map(|cache| {
+            let t = cache.traffic;
+            LevelTraffic{ bytes: (t.misses + t.writebacks) * cache.level.line_size as u64, ..t }
+        }).collect()
+    }
+
+    //Empties the caches and clears the counts
+    pub fn reset(&mut self) {
+        for cache in self.caches.iter_mut() {
+            *cache = LruCache::new(cache.level);
+        }
+    }
+
+    //Simulates an m x n x k gemm with a runtime tree, starting from the current contents of the caches.
+    //A, B and C are column major. The tree runs on one thread, as if SpawnThreads weren't there.
+    //Fails like TreeBuilder::build if the tree is inconsistent.
+    pub fn run<T: DynScalar>(&mut self, tree: &TreeBuilder, m: usize, n: usize, k: usize) -> Result<Vec<LevelTraffic>, MommsError> {
+        let one_thread = tree.specs().iter().filter(|spec| match **spec { NodeSpec::SpawnThreads(_) => false, _ => true })
+            .fold(TreeBuilder::new(), |tree, &spec| tree.push(spec));
+        let mut node = one_thread.build_traced::<T>()?;
+
+        let mut a : Matrix<T> = Matrix::new(m, k);
+        let mut b : Matrix<T> = Matrix::new(k, n);
+        let mut c : Matrix<T> = Matrix::new(m, n);
+        a.fill_rand(); b.fill_rand(); c.fill_zero();
+
+        //The tree's packing buffers are traced too, so it is dropped with the operands
+        Ok(self.trace(move |sim| {
+            let (mut a, mut b, mut c) = (Traced::new(a, sim), Traced::new(b, sim), Traced::new(c, sim));
+            unsafe{ node.run(&mut a, &mut b, &mut c, &ThreadInfo::single_thread()); }
+        }))
+    }
+
+    //Runs f with these caches shared, so f can wrap operands in Traced and run a tree on them.
+    //Nothing traced, including a tree's packing buffers, may outlive f. Returns the traffic since the last reset.
+    pub fn trace<F: FnOnce(&Arc<Mutex<CacheSim>>)>(&mut self, f: F) -> Vec<LevelTraffic> {
+        let levels : Vec<CacheLevel> = self.caches.iter().map(|cache| cache.level).collect();
+        let sim = Arc::new(Mutex::new(mem::replace(self, CacheSim::new(&levels))));
+        f(&sim);
+        *self = Arc::try_unwrap(sim).ok().expect("traced matrices outlived the simulation").into_inner().unwrap();
+        self.traffic()
+    }
+
+    //Accesses every line holding the bytes from addr to addr + bytes
+    pub fn access(&mut self, addr: usize, bytes: usize, write: bool) {
+        if self.caches.is_empty() || bytes == 0 { return; }
+        let line_size = self.caches[0].level.line_size;
+        for line in addr / line_size..(addr + bytes - 1) / line_size + 1 {
+            self.access_line(0, (line * line_size) as u64, write);
+        }
+    }
+
+    //Accesses the line at addr in the cache at level, filling it from the levels beyond on a miss
+    fn access_line(&mut self, level: usize, addr: u64, write: bool) {
+        if level == self.caches.len() { return; }
+        let evicted = {
+            let cache = &mut self.caches[level];
+            let line = addr / cache.level.line_size as u64;
+            let n_sets = cache.sets.len() as u64;
+            let ways = cache.level.ways;
+            let set = &mut cache.sets[(line % n_sets) as usize];
+            cache.traffic.accesses += 1;
+            if let Some(pos) = set.iter().position(|&(l, _)| l == line) {
+                let (_, dirty) = set.remove(pos);
+                set.insert(0, (line, dirty || write));
+                return;
+            }
+            cache.traffic.misses += 1;
+            set.insert(0, (line, write));
+            if set.len() > ways { set.pop() } else { None }
+        };
+        self.access_line(level + 1, addr, false);
+        if let Some((line, true)) = evicted {
+            self.caches[level].traffic.writebacks += 1;
+            let line_size = self.caches[level].level.line_size as u64;
+            self.access_line(level + 1, line * line_size, true);
+        }
+    }
+}
+
+//Reports the micro-panels of A and B and the micro-tiles of C a kernel touches, finding them as
+//the kernels do. Tiles are visited over n outside of m if n_outer, and over m outside of n otherwise.
+pub(crate) unsafe fn trace_kernel<T: Scalar, At: Mat<T> + RoCM<T>, Bt: Mat<T> + RoCM<T>, Ct: Mat<T> + RoCM<T>>
+    (sim: &Mutex<CacheSim>, a: &At, b: &Bt, c: &Ct, mr: usize, nr: usize, n_outer: bool)
+{
+    let mut sim = sim.lock().unwrap();
+    let elem = mem::size_of::<T>();
+    let (ap, bp, cp) = (a.get_buffer() as usize, b.get_buffer() as usize, c.get_buffer() as usize);
+    let (m, n, k) = (c.height(), c.width(), a.width());
+    let (c_rs, c_cs) = (c.get_leaf_rs(), c.get_leaf_cs());
+    let (a_mr_stride, c_mr_stride) = (a.get_block_rs(1, mr), c.get_block_rs(1, mr));
+    let (b_nr_stride, c_nr_stride) = (b.get_block_cs(1, nr), c.get_block_cs(1, nr));
+
+    let mut tile = |ir: usize, jr: usize| {
+        sim.access(ap + ir / mr * a_mr_stride * elem, mr * k * elem, false);
+        sim.access(bp + jr / nr * b_nr_stride * elem, nr * k * elem, false);
+        let c_tile = cp + (ir / mr * c_mr_stride + jr / nr * c_nr_stride) * elem;
+        let (local_m, local_n) = (cmp::min(mr, m - ir), cmp::min(nr, n - jr));
+        //Columns or rows of the tile that are contiguous are accessed as one run
+        if c_rs == 1 {
+            for jj in 0..local_n { sim.access(c_tile + jj * c_cs * elem, local_m * elem, true); }
+        } else if c_cs == 1 {
+            for ii in 0..local_m { sim.access(c_tile + ii * c_rs * elem, local_n * elem, true); }
+        } else {
+            for jj in 0..local_n { for ii in 0..local_m { sim.access(c_tile + (ii * c_rs + jj * c_cs) * elem, elem, true); } }
+        }
+    };
+    if n_outer {
+        for jr in (0..n).step_by(nr) { for ir in (0..m).step_by(mr) { tile(ir, jr); } }
+    } else {
+        for ir in (0..m).step_by(mr) { for jr in (0..n).step_by(nr) { tile(ir, jr); } }
+    }
+}
+
+//Reports each element a packer reads from a and writes to a_pack, including the zero padding,
+//one panel of a_pack at a time in the order its leaves store them
+pub(crate) fn trace_pack<T: Scalar, At: Addressed<T>, Apt: Addressed<T>>(sim: &Mutex<CacheSim>, a: &At, a_pack: &Apt) {
+    let mut sim = sim.lock().unwrap();
+    let elem = mem::size_of::<T>();
+    let (h, w) = (a.height(), a.width());
+    let (ph, pw) = (a_pack.iter_height(), a_pack.iter_width());
+    let (bh, bw) = Apt::block_multiples();
+    let mut copy = |y: usize, x: usize| {
+        if y < h && x < w { sim.access(a.address(y, x) as usize, elem, false); }
+        sim.access(a_pack.address(y, x) as usize, elem, true);
+    };
+    match Apt::leaf_strides() {
+        //Row by row within each panel of bw columns
+        Some((rs, cs)) if cs < rs =>
+            for x0 in (0..pw).step_by(bw) { for y in 0..ph { for x in x0..cmp::min(pw, x0 + bw) { copy(y, x); } } },
+        //Column by column within each panel of bh rows
+        _ =>
+            for y0 in (0..ph).step_by(bh) { for x in 0..pw { for y in y0..cmp::min(ph, y0 + bh) { copy(y, x); } } },
+    }
+}
@@ -1,22 +1,26 @@
-//extern crate scoped_threadpool;
-extern crate threadpool;
+extern crate scoped_threadpool;
 extern crate thread_local;
+#[cfg(feature="hwloc")]
 extern crate hwloc;
 
 use matrix::{Scalar, Mat};
 use core::marker::{PhantomData};
 use thread_comm::{ThreadComm, ThreadInfo};
-use composables::{GemmNode, AlgorithmStep};
+use composables::{GemmNode, AlgorithmStep, DynGemmNode, DynNode, NodeDescription};
 use std::{
-    sync::{Arc, Mutex},
+    sync::Arc,
     cell::RefCell,
-    ops::{DerefMut}
 };
-use self::threadpool::ThreadPool;
+use self::scoped_threadpool::Pool;
 use self::thread_local::ThreadLocal;
+#[cfg(feature="hwloc")]
+use std::{ops::DerefMut, sync::Mutex};
+#[cfg(feature="hwloc")]
 use self::hwloc::{Topology, ObjectType, CPUBIND_THREAD};
+#[cfg(feature="hwloc")]
 use libc;
 
+#[cfg(feature="hwloc")]
 fn bind_thread_to_core(topology: &mut Topology, idx: usize) -> () {
     let tid = unsafe { libc::pthread_self() };
     {
@@ -34,12 +38,54 @@ fn bind_thread_to_core(topology: &mut Topology, idx: usize) -> () {
     }
 }
 
+//Binds the workers of pool and the calling thread to cores 0..n_threads
+#[cfg(feature="hwloc")]
+fn bind_pool<T: Scalar>(pool: &mut Pool, n_threads: usize) {
+    //Get topology
+    let topo = Mutex::new(Topology::new());
+    let comm : Arc<ThreadComm<T>> = Arc::new(ThreadComm::new(n_threads));
+
+    pool.scoped(|scope| {
+        //Bind workers to cores.
+        for id in 1..n_threads {
+            let topo = &topo;
+            let my_comm  = comm.clone();
+            scope.execute(move || {
+                {
+                    let mut locked_topo = topo.lock().unwrap();
+                    bind_thread_to_core(locked_topo.deref_mut(), id);
+                }
+                //Barrier to make sure thread binding is done.
+                let thr = ThreadInfo::new(id, my_comm);
+                thr.barrier();
+            });
+        }
+
+        //Bind parent thread to a core.
+        {
+            let mut locked_topo = topo.lock().unwrap();
+            bind_thread_to_core(locked_topo.deref_mut(), 0);
+        }
+        let thr = ThreadInfo::new(0, comm.clone());
+        thr.barrier();
+    });
+}
+//Without hwloc the threads are left wherever the OS schedules them.
+#[cfg(not(feature="hwloc"))]
+fn bind_pool<T: Scalar>(_pool: &mut Pool, _n_threads: usize) { }
+
+fn pool_for(n_threads: usize) -> Pool {
+    Pool::new(if n_threads > 1 { (n_threads-1) as u32 } else { 1 })
+}
+
+//The workers run in a scope that joins them before run returns,
+//so the operands they get aliases of may borrow from the caller.
 pub struct SpawnThreads<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S: GemmNode<T, At, Bt, Ct>> 
-    where S: Send, T: 'static, S: 'static, At: 'static, Bt: 'static, Ct: 'static {
+    where S: Send {
     n_threads: usize,
-    pool: ThreadPool,
+    pool: Pool,
 
-    cntl_cache: Arc<ThreadLocal<RefCell<S>>>,
+    cntl_cache: ThreadLocal<RefCell<S>>,
 
     _t: PhantomData<T>,
     _at: PhantomData<At>,
@@ -52,45 +98,16 @@ impl<T: Scalar,At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S: GemmNode<T, At, Bt, Ct>>
     pub fn set_n_threads(&mut self, n_threads: usize){ 
         //Create new thread pool
         self.n_threads = n_threads;
-        if n_threads > 1 {
-            self.pool = ThreadPool::new(n_threads-1);
-        } else {
-            self.pool = ThreadPool::new(1);
-        }
+        self.pool = pool_for(n_threads);
 
         //Clear the control tree cache
-        Arc::get_mut(&mut self.cntl_cache).expect("").clear();
+        self.cntl_cache.clear();
         
         //Bind threads to cores
         self.bind_threads();
     }
     fn bind_threads(&mut self) {
-        //Get topology
-        let topo = Arc::new(Mutex::new(Topology::new()));
-        let comm : Arc<ThreadComm<T>> = Arc::new(ThreadComm::new(self.n_threads));
-
-        //Bind workers to cores.
-        for id in 1..self.n_threads {
-            let my_topo = topo.clone();
-            let my_comm  = comm.clone();
-            self.pool.execute(move || {
-                {
-                    let mut locked_topo = my_topo.lock().unwrap();
-                    bind_thread_to_core(locked_topo.deref_mut(), id);
-                }
-                //Barrier to make sure thread binding is done.
-                let thr = ThreadInfo::new(id, my_comm);
-                thr.barrier();
-            });
-        }
-
-        //Bind parent thread to a core.
-        {
-            let mut locked_topo = topo.lock().unwrap();
-            bind_thread_to_core(locked_topo.deref_mut(), 0);
-        }
-        let thr = ThreadInfo::new(0, comm);
-        thr.barrier();
+        bind_pool::<T>(&mut self.pool, self.n_threads);
     }
 }
 impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S: GemmNode<T, At, Bt, Ct>>
@@ -100,46 +117,101 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, S: GemmNode<T, At, Bt, Ct>>
     unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c:&mut Ct, _thr: &ThreadInfo<T>) -> () {
         //Create global thread communicator
         let comm : Arc<ThreadComm<T>> = Arc::new(ThreadComm::new(self.n_threads));
+        let n_threads = self.n_threads;
+        let cache = &self.cntl_cache;
 
-        //Make some shallow copies here to pass into the scoped,
-        //because self.pool borrows self as mutable
-        //let cache = self.cntl_cache.clone();
-    
-        //Spawn n-1 workers since head thread will do work too.
-        for id in 1..self.n_threads {
-            //Make some shallow copies because of borrow rules
-            let mut my_a = a.make_alias();
-            let mut my_b = b.make_alias();
-            let mut my_c = c.make_alias();
-            let my_comm  = comm.clone();
-            let my_cache = self.cntl_cache.clone();
+        self.pool.scoped(|scope| {
+            //Spawn n-1 workers since head thread will do work too.
+            for id in 1..n_threads {
+                //Make some shallow copies because of borrow rules
+                let mut my_a = a.make_alias();
+                let mut my_b = b.make_alias();
+                let mut my_c = c.make_alias();
+                let my_comm  = comm.clone();
 
-            self.pool.execute(move || {
-                //Make this thread's communicator holder
-                let thr = ThreadInfo::new(id, my_comm);
+                scope.execute(move || {
+                    //Make this thread's communicator holder
+                    let thr = ThreadInfo::new(id, my_comm);
 
-                //Read this thread's cached control tree
-                let cntl_tree_cell = my_cache.get_or(|| Box::new(RefCell::new(S::new())));
+                    //Read this thread's cached control tree
+                    let cntl_tree_cell = cache.get_or(|| Box::new(RefCell::new(S::new())));
 
-                //Run subproblem
-                cntl_tree_cell.borrow_mut().run(&mut my_a, &mut my_b, &mut my_c, &thr);
-                thr.barrier();
-            });
-        }
+                    //Run subproblem
+                    cntl_tree_cell.borrow_mut().run(&mut my_a, &mut my_b, &mut my_c, &thr);
+                    thr.barrier();
+                });
+            }
 
-        //Do parent thread's work
-        let thr = ThreadInfo::new(0, comm);
-        let cntl_tree_cell = self.cntl_cache.get_or(|| Box::new(RefCell::new(S::new())));
-        cntl_tree_cell.borrow_mut().run(a, b, c, &thr);
-        thr.barrier();
+            //Do parent thread's work
+            let thr = ThreadInfo::new(0, comm.clone());
+            let cntl_tree_cell = cache.get_or(|| Box::new(RefCell::new(S::new())));
+            cntl_tree_cell.borrow_mut().run(a, b, c, &thr);
+            thr.barrier();
+        });
     }
     fn new() -> Self {
-        SpawnThreads{ n_threads : 1, pool: ThreadPool::new(1),
-                 cntl_cache: Arc::new(ThreadLocal::new()),
+        SpawnThreads{ n_threads : 1, pool: pool_for(1),
+                 cntl_cache: ThreadLocal::new(),
                  _t: PhantomData, _at:PhantomData, _bt: PhantomData, _ct: PhantomData }
     }
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     }
+    fn describe() -> NodeDescription {
+        NodeDescription::new("SpawnThreads", "spawn threads".to_string()).nest(S::describe())
+    }
 }
 
+
+//Spawns one thread per subtree of a runtime-built tree.
+//Every subtree must have the same shape, since its parallelizers synchronize with the others.
+//Like SpawnThreads, the workers are joined before run returns, so each borrows its own subtree.
+pub struct DynSpawnThreads<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> {
+    pool: Pool,
+    children: Vec<DynNode<T, At, Bt, Ct>>,
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynSpawnThreads<T, At, Bt, Ct> {
+    pub fn new(children: Vec<DynNode<T, At, Bt, Ct>>) -> Self {
+        assert!(!children.is_empty(), "DynSpawnThreads needs at least one subtree");
+        let n_threads = children.len();
+        let mut pool = pool_for(n_threads);
+        bind_pool::<T>(&mut pool, n_threads);
+        DynSpawnThreads{ pool: pool, children: children }
+    }
+    pub fn n_threads(&self) -> usize { self.children.len() }
+}
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> DynGemmNode<T, At, Bt, Ct> for DynSpawnThreads<T, At, Bt, Ct> {
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, _thr: &ThreadInfo<T>) -> () {
+        let n_threads = self.children.len();
+        let comm : Arc<ThreadComm<T>> = Arc::new(ThreadComm::new(n_threads));
+        let (head, workers) = self.children.split_at_mut(1);
+
+        self.pool.scoped(|scope| {
+            for (id, my_tree) in (1..n_threads).zip(workers.iter_mut()) {
+                let mut my_a = a.make_alias();
+                let mut my_b = b.make_alias();
+                let mut my_c = c.make_alias();
+                let my_comm  = comm.clone();
+
+                scope.execute(move || {
+                    let thr = ThreadInfo::new(id, my_comm);
+                    my_tree.run(&mut my_a, &mut my_b, &mut my_c, &thr);
+                    thr.barrier();
+                });
+            }
+
+            //Do parent thread's work
+            let thr = ThreadInfo::new(0, comm.clone());
+            head[0].run(a, b, c, &thr);
+            thr.barrier();
+        });
+    }
+    fn hierarchy_description(&self) -> Vec<AlgorithmStep> {
+        self.children[0].hierarchy_description()
+    }
+    fn describe(&self) -> NodeDescription {
+        let n_threads = self.children.len();
+        NodeDescription::new("DynSpawnThreads", format!("spawn {} threads", n_threads))
+            .param("n_threads", n_threads).nest(self.children[0].describe())
+    }
+}
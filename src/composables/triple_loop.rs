@@ -1,5 +1,5 @@
 pub use matrix::{Scalar,Mat};
-pub use composables::{GemmNode,AlgorithmStep};
+pub use composables::{GemmNode,AlgorithmStep,NodeDescription};
 pub use thread_comm::ThreadInfo;
 
 pub struct TripleLoop{}
@@ -17,4 +17,7 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>>
     }
     fn new() -> Self { TripleLoop{} }
     fn hierarchy_description() -> Vec<AlgorithmStep> { Vec::new() }  
+    fn describe() -> NodeDescription {
+        NodeDescription::new("TripleLoop", "for n, for k, for m: c += a b".to_string())
+    }
 }
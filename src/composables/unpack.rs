@@ -2,10 +2,10 @@ use core::ptr::{self};
 use core::marker::PhantomData;
 use core::cmp;
 
-use matrix::{Scalar,Mat,Hierarch,Matrix,ResizableBuffer,HierarchyNode,RoCM};
+use matrix::{Scalar,Mat,Hierarch,Matrix,ResizableBuffer,HierarchyNode,RoCMMut};
 use typenum::Unsigned;
 use thread_comm::ThreadInfo;
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,NodeDescription};
 
 pub trait Adder <T: Scalar, At: Mat<T>, Apt: Mat<T>> {
     fn add(a: &mut At, a_pack: &mut Apt, thr: &ThreadInfo<T>);
@@ -197,8 +197,7 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned>
         while (thr.num_threads() % index) != 0 {
             index -= 1;
         }
-        let (y_nt, x_nt) = (4,1);
-            if y_score < x_score {
+        let (y_nt, x_nt) = if y_score < x_score {
                 (index, thr.num_threads() / index)
             } else {
                 (thr.num_threads() / index, index)
@@ -267,4 +266,9 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, CPt: Mat<T>, S: GemmNode<T,
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         S::hierarchy_description()
     } 
+    fn describe() -> NodeDescription {
+        let layout = CPt::describe_layout();
+        NodeDescription::new("UnpackC", format!("accumulate into C packed as {}, then add it to C", layout))
+            .param("layout", layout).nest(S::describe())
+    }
 }
@@ -0,0 +1,64 @@
+use std::error::Error;
+use core::fmt;
+
+//Errors returned by the fallible (try_) variants of operations that otherwise panic
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MommsError {
+    //The shapes of op(A), op(B) and C in gemm, as (height, width)
+    NonconformalGemm { a: (usize, usize), b: (usize, usize), c: (usize, usize) },
+    //The shapes of the two operands of an elementwise operation
+    NonconformalMatrices { lhs: (usize, usize), rhs: (usize, usize) },
+    //A buffer of this many elements, each of elem_size bytes, couldn't be allocated
+    AllocationFailed { capacity: usize, elem_size: usize },
+    //A communicator of n_threads threads can't be split n_way ways evenly
+    UnevenSplit { n_threads: usize, n_way: usize },
+    //The core to pin to doesn't exist; n_cores are available
+    NoSuchCore { core: usize, n_cores: usize },
+    //A runtime control tree description is wrong at the node-th node from the root
+    InvalidControlTree { node: usize, reason: &'static str },
+    //The text form of a control tree couldn't be parsed; line and column count from 1
+    MalformedControlTree { line: usize, column: usize, reason: &'static str },
+    //A node partitions a packed operand in blocks of bsz, which don't start on its panels or leaves
+    MisalignedBlockSize { node: &'static str, bsz: usize, multiple: usize },
+    //A kernel reads an operand in leaves with (row stride, column stride) expected, but it is packed with found
+    MismatchedLeaves { node: &'static str, operand: char, expected: (usize, usize), found: (usize, usize) },
+    //The children of a FirstDiffPart node partition the matrices differently
+    MismatchedHierarchies { node: &'static str },
+    //The cache sizes and associativities of this machine couldn't be read, or don't fit the model
+    UnknownCacheTopology { reason: &'static str },
+    //Block sizes can't be derived for a kernel with an empty micro-tile
+    InvalidKernelShape { mr: usize, nr: usize },
+}
+impl fmt::Display for MommsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MommsError::NonconformalGemm{ a, b, c } =>
+                write!(f, "Cannot multiply a {}x{} op(A) by a {}x{} op(B) into a {}x{} C",
+                       a.0, a.1, b.0, b.1, c.0, c.1),
+            MommsError::NonconformalMatrices{ lhs, rhs } =>
+                write!(f, "Cannot operate on nonconformal matrices of {}x{} and {}x{}", lhs.0, lhs.1, rhs.0, rhs.1),
+            MommsError::AllocationFailed{ capacity, elem_size } =>
+                write!(f, "Could not allocate buffer of {} elements of {} bytes for matrix", capacity, elem_size),
+            MommsError::UnevenSplit{ n_threads, n_way } =>
+                write!(f, "Cannot split {} threads {} ways", n_threads, n_way),
+            MommsError::NoSuchCore{ core, n_cores } =>
+                write!(f, "No Core found with id {} ({} cores available)", core, n_cores),
+            MommsError::InvalidControlTree{ node, reason } =>
+                write!(f, "Invalid control tree at node {}: {}", node, reason),
+            MommsError::MalformedControlTree{ line, column, reason } =>
+                write!(f, "Malformed control tree at line {}, column {}: {}", line, column, reason),
+            MommsError::MisalignedBlockSize{ node, bsz, multiple } =>
+                write!(f, "{} partitions in blocks of {}, but the packed operands need multiples of {}", node, bsz, multiple),
+            MommsError::MismatchedLeaves{ node, operand, expected, found } =>
+                write!(f, "{} needs {} packed with leaf row stride {} and column stride {}, not {} and {}",
+                       node, operand, expected.0, expected.1, found.0, found.1),
+            MommsError::MismatchedHierarchies{ node } =>
+                write!(f, "The children of {} partition the matrices differently", node),
+            MommsError::UnknownCacheTopology{ reason } =>
+                write!(f, "Unknown cache topology: {}", reason),
+            MommsError::InvalidKernelShape{ mr, nr } =>
+                write!(f, "Cannot derive block sizes for a {}x{} kernel", mr, nr),
+        }
+    }
+}
+impl Error for MommsError {}
@@ -0,0 +1,91 @@
+#![feature(specialization)]
+
+#![allow(unused_imports)]
+
+extern crate core;
+extern crate typenum;
+extern crate momms;
+
+use std::fmt::LowerExp;
+use typenum::{Unsigned, U32, U128, U512, U2048};
+
+use momms::kern::{KernelNM, KernelCompensated, Kahan, DoubleDouble};
+use momms::matrix::{Scalar, RealScalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix};
+use momms::composables::{GemmNode, PartM, PartN, PartK, PackA, PackB};
+use momms::thread_comm::ThreadInfo;
+use momms::util;
+
+//Measures how the error of gemm grows with k for different PartK block sizes.
+//Errors are relative to a double-double baseline computed without partitioning k,
+//so unlike util::test_c_eq_a_b they aren't swamped by the rounding error of the check itself.
+
+type Nc = typenum::U480;
+type Mc = typenum::U96;
+type Blocked<T, Kc, Mr, Nr>
+    = PartN<T, Matrix<T>, Matrix<T>, Matrix<T>, Nc,
+      PartK<T, Matrix<T>, Matrix<T>, Matrix<T>, Kc,
+      PackB<T, Matrix<T>, Matrix<T>, Matrix<T>, ColumnPanelMatrix<T,Nr>,
+      PartM<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Mc,
+      PackA<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, RowPanelMatrix<T,Mr>,
+      KernelNM<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Nr, Mr>>>>>>;
+type BlockedKahan<T, Kc, Mr, Nr>
+    = PartN<T, Matrix<T>, Matrix<T>, Matrix<T>, Nc,
+      PartK<T, Matrix<T>, Matrix<T>, Matrix<T>, Kc,
+      PackB<T, Matrix<T>, Matrix<T>, Matrix<T>, ColumnPanelMatrix<T,Nr>,
+      PartM<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Mc,
+      PackA<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, RowPanelMatrix<T,Mr>,
+      KernelCompensated<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Nr, Mr, Kahan<T>>>>>>>;
+type Baseline<T, Mr, Nr>
+    = PartN<T, Matrix<T>, Matrix<T>, Matrix<T>, Nc,
+      PartM<T, Matrix<T>, Matrix<T>, Matrix<T>, Mc,
+      KernelCompensated<T, Matrix<T>, Matrix<T>, Matrix<T>, Nr, Mr, DoubleDouble<T>>>>;
+
+//Relative error in the Frobenius norm of c against c_ref
+fn rel_err<T: RealScalar, S: GemmNode<T, Matrix<T>, Matrix<T>, Matrix<T>>>
+    ( a: &mut Matrix<T>, b: &mut Matrix<T>, c_ref: &Matrix<T>, algo: &mut S ) -> T
+{
+    let mut c : Matrix<T> = Matrix::new(c_ref.height(), c_ref.width());
+    c.fill_zero();
+    unsafe{ algo.run( a, b, &mut c, &ThreadInfo::single_thread() ); }
+    c.axpy(T::zero() - T::one(), c_ref);
+    (c.frosqr() / c_ref.frosqr()).sqrt()
+}
+
+fn test<T: RealScalar + LowerExp, Mr: Unsigned, Nr: Unsigned>() {
+    let mut baseline = <Baseline<T, Mr, Nr>>::new();
+    let mut kc32 = <Blocked<T, U32, Mr, Nr>>::new();
+    let mut kc128 = <Blocked<T, U128, Mr, Nr>>::new();
+    let mut kc512 = <Blocked<T, U512, Mr, Nr>>::new();
+    let mut kc2048 = <Blocked<T, U2048, Mr, Nr>>::new();
+    let mut kahan128 = <BlockedKahan<T, U128, Mr, Nr>>::new();
+
+    println!("m\tn\tk\t{: <15}{: <15}{: <15}{: <15}{: <15}", "kc32", "kc128", "kc512", "kc2048", "kahan128");
+    let (m, n) = (96, 96);
+    let mut k = 64;
+    while k <= 16384 {
+        let mut a : Matrix<T> = Matrix::new(m, k);
+        let mut b : Matrix<T> = Matrix::new(k, n);
+        let mut c_ref : Matrix<T> = Matrix::new(m, n);
+        a.fill_rand(); b.fill_rand(); c_ref.fill_zero();
+        unsafe{ baseline.run( &mut a, &mut b, &mut c_ref, &ThreadInfo::single_thread() ); }
+
+        println!("{}\t{}\t{}\t{}{}{}{}{}",
+                 m, n, k,
+                 format!("{: <15.5e}", rel_err(&mut a, &mut b, &c_ref, &mut kc32)),
+                 format!("{: <15.5e}", rel_err(&mut a, &mut b, &c_ref, &mut kc128)),
+                 format!("{: <15.5e}", rel_err(&mut a, &mut b, &c_ref, &mut kc512)),
+                 format!("{: <15.5e}", rel_err(&mut a, &mut b, &c_ref, &mut kc2048)),
+                 format!("{: <15.5e}", rel_err(&mut a, &mut b, &c_ref, &mut kahan128)));
+        k *= 4;
+    }
+}
+
+fn main() {
+    //Usage: exper_accuracy [f32|f64]
+    let precision = std::env::args().nth(1).unwrap_or("f32".to_string());
+    match precision.as_ref() {
+        "f64" => test::<f64, typenum::U4, typenum::U12>( ),
+        "f32" => test::<f32, typenum::U4, typenum::U24>( ),
+        _ => panic!("Unknown precision {}, expected f64 or f32", precision),
+    }
+}
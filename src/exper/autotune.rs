@@ -0,0 +1,217 @@
+#![feature(specialization)]
+#![feature(asm)]
+
+#![allow(unused_imports)]
+
+extern crate core;
+extern crate typenum;
+extern crate momms;
+
+use std::time::{Instant};
+use std::fs::File;
+use std::io::Write;
+
+use momms::matrix::{Scalar, RealScalar, Mat, Matrix};
+use momms::composables::{DynNode, DynScalar, TreeBuilder, NodeSpec, Nway};
+use momms::thread_comm::ThreadInfo;
+use momms::util;
+
+//Searches the block sizes, iotas and thread split of Goto's algorithm for this machine,
+//and writes the fastest as a control tree type alias and as a JSON record.
+//    autotune [f64|f32] [threads] [problem size] [output prefix]
+//writes <output prefix>.rs and <output prefix>.json, tuned_<precision> by default.
+
+//The tunables of
+//    spawn > partN(nc) > partK(kc) > packB > partM(mc) > packA > parM(iota_m, m_way) > parN(iota_n, rest) > kernelNM
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Config {
+    nc: usize,
+    kc: usize,
+    mc: usize,
+    m_way: usize,
+    iota_m: usize,
+    iota_n: usize,
+}
+impl Config {
+    fn tree(&self, n_threads: usize, mr: usize, nr: usize) -> TreeBuilder {
+        let mut tree = TreeBuilder::new().spawn(n_threads).part_n(self.nc).part_k(self.kc).pack_b(nr)
+            .part_m(self.mc).pack_a(mr);
+        if self.m_way > 1 {
+            tree = tree.parallel_m(self.iota_m, Nway::Target(self.m_way));
+        }
+        tree.parallel_n(self.iota_n, Nway::TheRest).kernel_nm(nr, mr)
+    }
+}
+
+fn time_algorithm<T: RealScalar>
+    ( m:usize, n: usize, k: usize, algo: &mut DynNode<T, Matrix<T>, Matrix<T>, Matrix<T>>, flusher: &mut Vec<f64>, n_reps: usize ) -> f64
+{
+    let mut best_time: f64 = 9999999999.0;
+    for _ in 0..n_reps {
+        //Create matrices.
+        let mut a : Matrix<T> = Matrix::new(m, k);
+        let mut b : Matrix<T> = Matrix::new(k, n);
+        let mut c : Matrix<T> = Matrix::new(m, n);
+
+        //Fill the matrices
+        a.fill_rand(); c.fill_zero(); b.fill_rand();
+
+        //Read a buffer so that A, B, and C are cold in cache.
+        for i in flusher.iter_mut() { *i += 1.0; }
+
+        //Time and run algorithm
+        let start = Instant::now();
+        unsafe{ algo.run( &mut a, &mut b, &mut c, &ThreadInfo::single_thread() ); }
+        best_time = best_time.min(util::dur_seconds(start));
+    }
+    best_time
+}
+
+//A typenum type for n, built from the consts typenum provides up to U1024.
+//The consts are named from the crate root so the alias can be included in any module.
+fn typenum_type(n: usize) -> String {
+    if n <= 1024 {
+        format!("::typenum::U{}", n)
+    } else {
+        format!("UInt<{}, B{}>", typenum_type(n >> 1), n & 1)
+    }
+}
+
+//The compile time control tree equivalent to specs
+fn rust_alias(specs: &[NodeSpec], t: &str) -> String {
+    let mut at = format!("Matrix<{}>", t);
+    let mut bt = format!("Matrix<{}>", t);
+    let ct = format!("Matrix<{}>", t);
+    let mut alias = String::new();
+    let mut closing = String::new();
+    for spec in specs {
+        let operands = format!("{}, {}, {}, {}", t, at, bt, ct);
+        let node = match *spec {
+            NodeSpec::SpawnThreads(_) => format!("SpawnThreads<{},", operands),
+            NodeSpec::PartM(bsz) => format!("PartM<{}, {},", operands, typenum_type(bsz)),
+            NodeSpec::PartN(bsz) => format!("PartN<{}, {},", operands, typenum_type(bsz)),
+            NodeSpec::PartK(bsz) => format!("PartK<{}, {},", operands, typenum_type(bsz)),
+            NodeSpec::ParallelM(iota, nway) | NodeSpec::ParallelN(iota, nway) => {
+                let nwayer = match nway {
+                    Nway::Target(n_way) => format!("Target<{}>", typenum_type(n_way)),
+                    Nway::TheRest => "TheRest".to_string(),
+                };
+                let name = if let NodeSpec::ParallelM(..) = *spec { "ParallelM" } else { "ParallelN" };
+                format!("{}<{}, {}, {},", name, operands, typenum_type(iota), nwayer)
+            },
+            NodeSpec::PackA(mr) => {
+                at = format!("RowPanelMatrix<{}, {}>", t, typenum_type(mr));
+                format!("PackA<{}, {},", operands, at)
+            },
+            NodeSpec::PackB(nr) => {
+                bt = format!("ColumnPanelMatrix<{}, {}>", t, typenum_type(nr));
+                format!("PackB<{}, {},", operands, bt)
+            },
+            NodeSpec::KernelNM{ nr, mr } => format!("KernelNM<{}, {}, {}", operands, typenum_type(nr), typenum_type(mr)),
+            NodeSpec::KernelMN{ mr, nr } => format!("KernelMN<{}, {}, {}", operands, typenum_type(mr), typenum_type(nr)),
+            NodeSpec::TripleLoop => "TripleLoop".to_string(),
+        };
+        if !alias.is_empty() { alias.push_str("\n    "); }
+        alias.push_str(&node);
+        if *spec != NodeSpec::TripleLoop { closing.push('>'); }
+    }
+    alias + &closing
+}
+
+//Tries each value of one parameter in the best configuration so far, keeping the fastest
+fn tune_parameter<E: FnMut(&Config) -> f64, S: Fn(Config, usize) -> Config>
+    (evaluate: &mut E, best: &mut Config, best_gflops: &mut f64, values: &[usize], set: S) {
+    for &value in values {
+        let candidate = set(*best, value);
+        if candidate == *best { continue; }
+        let gflops = evaluate(&candidate);
+        if gflops > *best_gflops {
+            *best = candidate;
+            *best_gflops = gflops;
+        }
+    }
+}
+
+fn divisors(n: usize) -> Vec<usize> {
+    (1..n+1).filter(|d| n % d == 0).collect()
+}
+
+fn tune<T: RealScalar + DynScalar>(precision: &str, n_threads: usize, size: usize, prefix: &str) {
+    let (mr, nr) = T::kernel_shapes()[0];
+
+    //Initialize array to flush cache with
+    let flusher_len = 2*1024*1024; //16MB
+    let mut flusher : Vec<f64> = Vec::with_capacity(flusher_len);
+    for _ in 0..flusher_len { flusher.push(0.0); }
+
+    let mut evaluate = |config: &Config| -> f64 {
+        let tree = config.tree(n_threads, mr, nr);
+        let mut algo = tree.build::<T>().unwrap_or_else(|e| panic!("{}: {}", tree, e));
+        let gflops = util::gflops(size, size, size, time_algorithm(size, size, size, &mut algo, &mut flusher, 3));
+        println!("{: <13.5}{}", gflops, tree);
+        gflops
+    };
+
+    //Start from the hand-picked block sizes, and tune one parameter at a time until none improves
+    let mut best = Config{ nc: 3000 / nr * nr, kc: 192, mc: 120 / mr * mr, m_way: 1, iota_m: mr, iota_n: nr };
+    let mut best_gflops = evaluate(&best);
+    for _pass in 0..3 {
+        let start = best;
+        let kcs: Vec<usize> = (1..17).map(|i| 32*i).collect();
+        let mcs: Vec<usize> = (1..21).map(|i| 6*mr*i).collect();
+        let ncs: Vec<usize> = (1..11).map(|i| 50*nr*i).collect();
+        tune_parameter(&mut evaluate, &mut best, &mut best_gflops, &kcs, |c, v| Config{ kc: v, ..c });
+        tune_parameter(&mut evaluate, &mut best, &mut best_gflops, &mcs, |c, v| Config{ mc: v, ..c });
+        tune_parameter(&mut evaluate, &mut best, &mut best_gflops, &ncs, |c, v| Config{ nc: v, ..c });
+        tune_parameter(&mut evaluate, &mut best, &mut best_gflops, &divisors(n_threads), |c, v| Config{ m_way: v, ..c });
+        if best.m_way > 1 {
+            tune_parameter(&mut evaluate, &mut best, &mut best_gflops, &[mr, 2*mr, 4*mr, 8*mr], |c, v| Config{ iota_m: v, ..c });
+        }
+        tune_parameter(&mut evaluate, &mut best, &mut best_gflops, &[nr, 2*nr, 4*nr], |c, v| Config{ iota_n: v, ..c });
+        if best == start { break; }
+    }
+
+    let tree = best.tree(n_threads, mr, nr);
+    println!("# best {: <13.5}{}", best_gflops, tree);
+
+    let alias = format!("\
+//Generated by autotune for {precision} on {size}x{size}x{size} problems with {n_threads} threads: {gflops:.5} GFLOPS
+//    {tree}
+//Instantiate it with new() and call set_n_threads(TUNED_N_THREADS).
+#[allow(unused_imports)]
+use typenum::{{UInt, B0, B1}};
+#[allow(unused_imports)]
+use momms::composables::{{GemmNode, SpawnThreads, PartM, PartN, PartK, PackA, PackB, ParallelM, ParallelN, Target, TheRest}};
+#[allow(unused_imports)]
+use momms::kern::{{KernelNM, KernelMN}};
+#[allow(unused_imports)]
+use momms::matrix::{{Matrix, RowPanelMatrix, ColumnPanelMatrix}};
+
+pub const TUNED_N_THREADS: usize = {n_threads};
+pub type Tuned = {alias};
+",
+        precision = precision, size = size, n_threads = n_threads, gflops = best_gflops, tree = tree,
+        alias = rust_alias(tree.specs(), precision));
+    let json = format!("{{\"precision\": \"{}\", \"n_threads\": {}, \"size\": {}, \"gflops\": {:.5}, \"mr\": {}, \"nr\": {}, \
+\"nc\": {}, \"kc\": {}, \"mc\": {}, \"m_way\": {}, \"iota_m\": {}, \"iota_n\": {}, \"tree\": \"{}\"}}\n",
+        precision, n_threads, size, best_gflops, mr, nr, best.nc, best.kc, best.mc, best.m_way, best.iota_m, best.iota_n, tree);
+
+    for &(ext, contents) in [("rs", &alias), ("json", &json)].iter() {
+        let path = format!("{}.{}", prefix, ext);
+        File::create(&path).and_then(|mut f| f.write_all(contents.as_bytes()))
+            .unwrap_or_else(|e| panic!("Could not write {}: {}", path, e));
+        println!("# wrote {}", path);
+    }
+}
+
+fn main() {
+    let precision = std::env::args().nth(1).unwrap_or("f64".to_string());
+    let n_threads = std::env::args().nth(2).map_or(4, |s| s.parse().expect("threads must be a number"));
+    let size = std::env::args().nth(3).map_or(2000, |s| s.parse().expect("problem size must be a number"));
+    let prefix = std::env::args().nth(4).unwrap_or(format!("tuned_{}", precision));
+    match precision.as_ref() {
+        "f64" => tune::<f64>(&precision, n_threads, size, &prefix),
+        "f32" => tune::<f32>(&precision, n_threads, size, &prefix),
+        _ => panic!("Unknown precision {}, expected f64 or f32", precision),
+    }
+}
@@ -0,0 +1,133 @@
+#![feature(specialization)]
+
+#![allow(unused_imports)]
+
+extern crate typenum;
+extern crate momms;
+
+use std::fs::File;
+use std::io::Read;
+use typenum::{Unsigned,U1};
+
+use momms::kern::KernelNM;
+use momms::matrix::{RealScalar, Mat, Hierarch, Traced};
+use momms::composables::{GemmNode, AlgorithmStep, CacheSim, LevelTraffic, CacheTopology, DynScalar, parse_algorithms,
+    PartM, PartN, PartK, SpawnThreads, ParallelN, TheRest};
+use momms::thread_comm::ThreadInfo;
+
+//Simulates a typed tree on A, B and C pre-packed into Hierarch, as exper_l4 runs them
+fn simulate_hier<T: RealScalar, Mr: Unsigned, Nr: Unsigned, Kc: Unsigned,
+    S: GemmNode<T, Traced<Hierarch<T, Mr, Kc, U1, Mr>>, Traced<Hierarch<T, Kc, Nr, Nr, U1>>, Traced<Hierarch<T, Mr, Nr, Nr, U1>>>>
+    (sim: &mut CacheSim, algo: &mut S, m: usize, n: usize, k: usize) -> Vec<LevelTraffic>
+{
+    let algo_desc = S::hierarchy_description();
+    let mut a : Hierarch<T, Mr, Kc, U1, Mr> = Hierarch::new(m, k, &algo_desc, AlgorithmStep::M{bsz: 0}, AlgorithmStep::K{bsz: 0});
+    let mut b : Hierarch<T, Kc, Nr, Nr, U1> = Hierarch::new(k, n, &algo_desc, AlgorithmStep::K{bsz: 0}, AlgorithmStep::N{bsz: 0});
+    let mut c : Hierarch<T, Mr, Nr, Nr, U1> = Hierarch::new(m, n, &algo_desc, AlgorithmStep::M{bsz: 0}, AlgorithmStep::N{bsz: 0});
+    a.fill_rand(); b.fill_rand(); c.fill_zero();
+
+    sim.reset();
+    sim.trace(|s| {
+        let (mut a, mut b, mut c) = (Traced::new(a, s), Traced::new(b, s), Traced::new(c, s));
+        unsafe{ algo.run(&mut a, &mut b, &mut c, &ThreadInfo::single_thread()); }
+    })
+}
+
+fn test<T: DynScalar + RealScalar, Mr: Unsigned + Send + 'static, Nr: Unsigned + Send + 'static>(config: &str) {
+    //exper_l4's Goto and L4C, on matrices pre-packed into Hierarch, which the algorithm files can't describe
+    use typenum::{UInt, B0};
+    type U3000 = UInt<UInt<typenum::U750, B0>, B0>;
+    type U3600 = UInt<UInt<typenum::U900, B0>, B0>;
+    type Kc = typenum::U192;
+    type Mc = typenum::U120;
+    type GotoA<T,Mr> = Traced<Hierarch<T, Mr, Kc, U1, Mr>>;
+    type GotoB<T,Nr> = Traced<Hierarch<T, Kc, Nr, Nr, U1>>;
+    type GotoC<T,Mr,Nr> = Traced<Hierarch<T, Mr, Nr, Nr, U1>>;
+    type Goto<T,MTA,MTB,MTC,Mr,Nr>
+        = SpawnThreads<T, MTA, MTB, MTC,
+          PartN<T, MTA, MTB, MTC, U3000,
+          PartK<T, MTA, MTB, MTC, Kc,
+          PartM<T, MTA, MTB, MTC, Mc,
+          ParallelN<T, MTA, MTB, MTC, Nr, TheRest,
+          KernelNM<T, MTA, MTB, MTC, Nr, Mr>>>>>>;
+    type L4C<T,MTA,MTB,MTC,Mr,Nr>
+        = SpawnThreads<T, MTA, MTB, MTC,
+          PartM<T, MTA, MTB, MTC, U3600,
+          PartN<T, MTA, MTB, MTC, U3600,
+          PartK<T, MTA, MTB, MTC, Kc,
+          PartM<T, MTA, MTB, MTC, Mc,
+          ParallelN<T, MTA, MTB, MTC, Nr, TheRest,
+          KernelNM<T, MTA, MTB, MTC, Nr, Mr>>>>>>>;
+    let mut goto = <Goto<T, GotoA<T,Mr>, GotoB<T,Nr>, GotoC<T,Mr,Nr>, Mr, Nr>>::new();
+    let mut l4c = <L4C<T, GotoA<T,Mr>, GotoB<T,Nr>, GotoC<T,Mr,Nr>, Mr, Nr>>::new();
+    //Accesses from several threads would interleave arbitrarily
+    goto.set_n_threads(1);
+    l4c.set_n_threads(1);
+
+    //Read the algorithms to compare
+    let mut text = String::new();
+    File::open(config).and_then(|mut f| f.read_to_string(&mut text))
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", config, e));
+    let algorithms = parse_algorithms(&text).unwrap_or_else(|e| panic!("{}: {}", config, e));
+    for &(ref name, ref tree) in algorithms.iter() {
+        println!("# {}: {}", name, tree);
+    }
+    println!("# goto_hier, l4c: exper_l4's Goto and L4C on Hierarch, with mr {} and nr {}", Mr::to_usize(), Nr::to_usize());
+    let names : Vec<String> = algorithms.iter().map(|&(ref name, _)| name.clone())
+        .chain(vec!["goto_hier".to_string(), "l4c".to_string()]).collect();
+
+    //Simulate the caches of this machine, or of a Haswell core if they can't be read
+    let topology = CacheTopology::detect().unwrap_or_else(|e| {
+        println!("# {}, simulating the default caches", e);
+        CacheTopology::default()
+    });
+    for (i, level) in topology.levels.iter().enumerate() {
+        println!("# L{}: {} bytes, {} ways, {} byte lines", i + 1, level.size, level.ways, level.line_size);
+    }
+    let mut sim = CacheSim::new(&topology.levels);
+
+    //Misses of each level, then the bytes moved into each level from the one beyond it
+    print!("m\tn\tk\t");
+    for name in names.iter() {
+        for i in 0..topology.levels.len() { print!("{: <13}", format!("{} L{}", name, i + 1)); }
+    }
+    for name in names.iter() {
+        for i in 0..topology.levels.len() { print!("{: <15}", format!("{} L{}", name, i + 1)); }
+    }
+    println!();
+    for index in 01..9 {
+        let size = index*250;
+        let (m, n, k) = (size, size, size);
+
+        let mut misses = String::new();
+        let mut bytes = String::new();
+        let mut traffics = Vec::new();
+        for &(_, ref tree) in algorithms.iter() {
+            //Start each algorithm with cold caches
+            sim.reset();
+            traffics.push(sim.run::<T>(tree, m, n, k).unwrap_or_else(|e| panic!("{}: {}", tree, e)));
+        }
+        traffics.push(simulate_hier(&mut sim, &mut goto, m, n, k));
+        traffics.push(simulate_hier(&mut sim, &mut l4c, m, n, k));
+        for traffic in traffics {
+            for level in traffic {
+                misses.push_str(&format!("{: <13}", level.misses));
+                bytes.push_str(&format!("{: <15}", level.bytes));
+            }
+        }
+        println!("{}\t{}\t{}\t{}{}", m, n, k, misses, bytes);
+    }
+}
+
+fn main() {
+    //Usage: exper_cache_sim [f64|f32] [algorithm file]
+    //The algorithms default to those in algorithms/exper_cache_sim_<precision>.txt
+    let precision = std::env::args().nth(1).unwrap_or("f64".to_string());
+    let config = std::env::args().nth(2).unwrap_or(
+        format!("{}/algorithms/exper_cache_sim_{}.txt", env!("CARGO_MANIFEST_DIR"), precision));
+    match precision.as_ref() {
+        "f64" => test::<f64, typenum::U4, typenum::U12>(&config),
+        "f32" => test::<f32, typenum::U4, typenum::U24>(&config),
+        _ => panic!("Unknown precision {}, expected f64 or f32", precision),
+    }
+}
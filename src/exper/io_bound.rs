@@ -0,0 +1,72 @@
+#![feature(specialization)]
+
+#![allow(unused_imports)]
+
+extern crate momms;
+
+use std::fs::File;
+use std::io::Read;
+
+use momms::composables::{DynScalar, CacheTopology, parse_algorithms};
+
+fn test<T: DynScalar>(config: &str) {
+    //Read the algorithms to compare
+    let mut text = String::new();
+    File::open(config).and_then(|mut f| f.read_to_string(&mut text))
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", config, e));
+    let algorithms = parse_algorithms(&text).unwrap_or_else(|e| panic!("{}: {}", config, e));
+    let mut names = Vec::new();
+    let mut descriptions = Vec::new();
+    for (name, tree) in algorithms {
+        println!("# {}: {}", name, tree);
+        let algo = tree.build::<T>().unwrap_or_else(|e| panic!("{}: {}: {}", config, name, e));
+        descriptions.push(algo.describe());
+        names.push(name);
+    }
+
+    //Model the caches of this machine, or of a Haswell core if they can't be read
+    let topology = CacheTopology::detect().unwrap_or_else(|e| {
+        println!("# {}, modeling the default caches", e);
+        CacheTopology::default()
+    });
+    for (i, level) in topology.levels.iter().enumerate() {
+        println!("# L{}: {} bytes", i + 1, level.size);
+    }
+
+    //Predicted words moved into each level, then how many times the lower bound that is
+    print!("m\tn\tk\t");
+    for name in &names {
+        for i in 0..topology.levels.len() { print!("{: <15}", format!("{} L{}", name, i + 1)); }
+    }
+    for name in &names {
+        for i in 0..topology.levels.len() { print!("{: <13}", format!("{} L{}", name, i + 1)); }
+    }
+    println!();
+    for index in 01..41 {
+        let size = index*500;
+        let (m, n, k) = (size, size, size);
+
+        let mut words = String::new();
+        let mut ratios = String::new();
+        for desc in descriptions.iter() {
+            for level in desc.data_movement::<T>(m, n, k, &topology.levels) {
+                words.push_str(&format!("{: <15.5e}", level.words));
+                ratios.push_str(&format!("{: <13.5}", level.words / level.lower_bound));
+            }
+        }
+        println!("{}\t{}\t{}\t{}{}", m, n, k, words, ratios);
+    }
+}
+
+fn main() {
+    //Usage: exper_io_bound [f64|f32] [algorithm file]
+    //The algorithms default to those in algorithms/exper_cache_sim_<precision>.txt
+    let precision = std::env::args().nth(1).unwrap_or("f64".to_string());
+    let config = std::env::args().nth(2).unwrap_or(
+        format!("{}/algorithms/exper_cache_sim_{}.txt", env!("CARGO_MANIFEST_DIR"), precision));
+    match precision.as_ref() {
+        "f64" => test::<f64>(&config),
+        "f32" => test::<f32>(&config),
+        _ => panic!("Unknown precision {}, expected f64 or f32", precision),
+    }
+}
@@ -0,0 +1,276 @@
+#![feature(specialization)]
+
+#![allow(unused_imports)]
+
+extern crate core;
+extern crate typenum;
+extern crate momms;
+
+use std::time::{Instant};
+use std::fmt::LowerExp;
+use typenum::{U1, U4, U6, U8, U12, U14, U16, U24, U32, Unsigned};
+
+use momms::kern::{KernelNM, QKernelNM, cpu_features};
+use momms::matrix::{Scalar, RealScalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix};
+use momms::matrix::{QuantElem, QMatrix, QRowPanelMatrix, QColumnPanelMatrix};
+use momms::matrix::{HalfElem, HalfMatrix, bf16, f16};
+use momms::matrix::Complex;
+use momms::matrix::{Transposed, MatrixRef};
+use momms::composables::{GemmNode, PartM, PartN, PartK, PackA, PackB, TripleLoop};
+use momms::thread_comm::ThreadInfo;
+use momms::util;
+
+//Checks every hand-tuned micro-kernel shape against the reference gemm in util.
+//Kernels whose instructions the CPU lacks fall back to the portable kernel,
+//so the same binary cross-checks the fallback on older hardware.
+
+type Nc = typenum::U480;
+type Kc = typenum::U192;
+type Mc = typenum::U96;
+type Goto<T, Mr, Nr>
+    = PartN<T, Matrix<T>, Matrix<T>, Matrix<T>, Nc,
+      PartK<T, Matrix<T>, Matrix<T>, Matrix<T>, Kc,
+      PackB<T, Matrix<T>, Matrix<T>, Matrix<T>, ColumnPanelMatrix<T,Nr>,
+      PartM<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Mc,
+      PackA<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, RowPanelMatrix<T,Mr>,
+      KernelNM<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Nr, Mr>>>>>>;
+
+type QGoto<Sa, Sb, Mr, Nr>
+    = PartN<i32, QMatrix<Sa>, QMatrix<Sb>, Matrix<i32>, Nc,
+      PartK<i32, QMatrix<Sa>, QMatrix<Sb>, Matrix<i32>, Kc,
+      PackB<i32, QMatrix<Sa>, QMatrix<Sb>, Matrix<i32>, QColumnPanelMatrix<Sb,Nr>,
+      PartM<i32, QMatrix<Sa>, QColumnPanelMatrix<Sb,Nr>, Matrix<i32>, Mc,
+      PackA<i32, QMatrix<Sa>, QColumnPanelMatrix<Sb,Nr>, Matrix<i32>, QRowPanelMatrix<Sa,Mr>,
+      QKernelNM<Sa, Sb, Matrix<i32>, Nr, Mr>>>>>>;
+
+type HGoto<H, Mr, Nr>
+    = PartN<f32, HalfMatrix<H>, HalfMatrix<H>, Matrix<f32>, Nc,
+      PartK<f32, HalfMatrix<H>, HalfMatrix<H>, Matrix<f32>, Kc,
+      PackB<f32, HalfMatrix<H>, HalfMatrix<H>, Matrix<f32>, ColumnPanelMatrix<f32,Nr>,
+      PartM<f32, HalfMatrix<H>, ColumnPanelMatrix<f32,Nr>, Matrix<f32>, Mc,
+      PackA<f32, HalfMatrix<H>, ColumnPanelMatrix<f32,Nr>, Matrix<f32>, RowPanelMatrix<f32,Mr>,
+      KernelNM<f32, RowPanelMatrix<f32,Mr>, ColumnPanelMatrix<f32,Nr>, Matrix<f32>, Nr, Mr>>>>>>;
+
+//Goto with A and B of any layout, for the transposed operands
+type TGoto<T, At, Bt, Mr, Nr>
+    = PartN<T, At, Bt, Matrix<T>, Nc,
+      PartK<T, At, Bt, Matrix<T>, Kc,
+      PackB<T, At, Bt, Matrix<T>, ColumnPanelMatrix<T,Nr>,
+      PartM<T, At, ColumnPanelMatrix<T,Nr>, Matrix<T>, Mc,
+      PackA<T, At, ColumnPanelMatrix<T,Nr>, Matrix<T>, RowPanelMatrix<T,Mr>,
+      KernelNM<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Nr, Mr>>>>>>;
+
+fn test_algorithm<T: RealScalar, S: GemmNode<T, Matrix<T>, Matrix<T>, Matrix<T>>>
+    ( m: usize, n: usize, k: usize, row_major_c: bool, algo: &mut S ) -> (f64, T)
+{
+    let mut a : Matrix<T> = Matrix::new(m, k);
+    let mut b : Matrix<T> = Matrix::new(k, n);
+    let mut c : Matrix<T> = if row_major_c {
+        let mut c = Matrix::new(n, m);
+        c.transpose();
+        c
+    } else {
+        Matrix::new(m, n)
+    };
+    a.fill_rand(); c.fill_zero(); b.fill_rand();
+
+    let start = Instant::now();
+    unsafe{ algo.run( &mut a, &mut b, &mut c, &ThreadInfo::single_thread() ); }
+    let time = util::dur_seconds(start);
+    (time, util::test_c_eq_a_b( &mut a, &mut b, &mut c))
+}
+
+fn check<T: RealScalar + LowerExp, Mr: Unsigned, Nr: Unsigned>(name: &str) {
+    let mut goto = <Goto<T, Mr, Nr>>::new();
+
+    //Edge cases smaller than, equal to, and not a multiple of the register block
+    let mut worst_err = T::zero();
+    for &(m, n, k) in &[(1, 1, 1), (Mr::to_usize(), Nr::to_usize(), 1), (Mr::to_usize()+1, Nr::to_usize()-1, 7), (123, 77, 200)] {
+        for &row_major_c in &[false, true] {
+            let (_, err) = test_algorithm(m, n, k, row_major_c, &mut goto);
+            worst_err = worst_err.max(err);
+        }
+    }
+
+    let size = 480;
+    let (time, err) = test_algorithm(size, size, size, false, &mut goto);
+    worst_err = worst_err.max(err);
+
+    println!("{: <16}{: <13.5}{: <15.5e}", name, util::gflops(size, size, size, time), worst_err.sqrt());
+}
+
+//Complex scalars run on the portable kernel, with A and B conjugated in every combination.
+//fill_rand leaves the imaginary parts all positive, so the products and their errors are larger than for reals.
+fn check_complex<R: RealScalar + LowerExp, Mr: Unsigned, Nr: Unsigned>(name: &str) {
+    let mut goto = <Goto<Complex<R>, Mr, Nr>>::new();
+    let mut worst_err = R::zero();
+    let mut time = 0.0;
+    let size = 480;
+    for &(m, n, k) in &[(1, 1, 1), (Mr::to_usize()+1, Nr::to_usize()-1, 7), (123, 77, 200), (size, size, size)] {
+        for &(conj_a, conj_b) in &[(false, false), (true, false), (false, true), (true, true)] {
+            let mut a : Matrix<Complex<R>> = Matrix::new(m, k);
+            let mut b : Matrix<Complex<R>> = Matrix::new(k, n);
+            let mut c : Matrix<Complex<R>> = Matrix::new(m, n);
+            a.fill_rand(); b.fill_rand(); c.fill_zero();
+            if conj_a { a.conjugate(); }
+            if conj_b { b.conjugate(); }
+
+            let start = Instant::now();
+            unsafe{ goto.run( &mut a, &mut b, &mut c, &ThreadInfo::single_thread() ); }
+            time = util::dur_seconds(start);
+            worst_err = worst_err.max(util::test_c_eq_a_b( &mut a, &mut b, &mut c));
+        }
+    }
+    println!("{: <16}{: <13.5}{: <15.5e}", name, util::gflops(size, size, size, time), worst_err.sqrt());
+}
+
+//Half precision inputs are widened exactly, so the error is that of the f32 kernel
+fn check_half<H: HalfElem, Mr: Unsigned, Nr: Unsigned>(name: &str) {
+    let mut goto = <HGoto<H, Mr, Nr>>::new();
+    let mut worst_err = 0.0;
+    let mut time = 0.0;
+    let size = 480;
+    for &(m, n, k) in &[(1, 1, 1), (Mr::to_usize()+1, Nr::to_usize()-1, 7), (123, 77, 200), (size, size, size)] {
+        let mut a : HalfMatrix<H> = HalfMatrix::new(m, k);
+        let mut b : HalfMatrix<H> = HalfMatrix::new(k, n);
+        let mut c : Matrix<f32> = Matrix::new(m, n);
+        a.fill_rand(); b.fill_rand(); c.fill_zero();
+
+        let start = Instant::now();
+        unsafe{ goto.run( &mut a, &mut b, &mut c, &ThreadInfo::single_thread() ); }
+        time = util::dur_seconds(start);
+        worst_err = worst_err.max(util::test_c_eq_a_b( &mut a, &mut b, &mut c));
+    }
+    println!("{: <16}{: <13.5}{: <15.5e}", name, util::gflops(size, size, size, time), worst_err.sqrt());
+}
+
+//Integer gemm is exact, so the error is the largest difference from a triple loop
+fn check_quantized<Sa: QuantElem, Sb: QuantElem, Mr: Unsigned, Nr: Unsigned>(name: &str) {
+    let mut goto = <QGoto<Sa, Sb, Mr, Nr>>::new();
+    let mut worst_err = 0;
+    let mut time = 0.0;
+    let size = 480;
+    for &(m, n, k) in &[(1, 1, 1), (Mr::to_usize()+1, Nr::to_usize()-1, 7), (123, 77, 201), (size, size, size)] {
+        let mut a : QMatrix<Sa> = QMatrix::new(m, k);
+        let mut b : QMatrix<Sb> = QMatrix::new(k, n);
+        let mut c : Matrix<i32> = Matrix::new(m, n);
+        let mut c_ref : Matrix<i32> = Matrix::new(m, n);
+        a.fill_rand(); b.fill_rand(); c.fill_zero(); c_ref.fill_zero();
+
+        let start = Instant::now();
+        unsafe{ goto.run( &mut a, &mut b, &mut c, &ThreadInfo::single_thread() ); }
+        time = util::dur_seconds(start);
+        unsafe{ TripleLoop{}.run( &mut a, &mut b, &mut c_ref, &ThreadInfo::single_thread() ); }
+
+        for x in 0..n {
+            for y in 0..m {
+                worst_err = std::cmp::max(worst_err, (c.get(y,x) - c_ref.get(y,x)).abs());
+            }
+        }
+    }
+    println!("{: <16}{: <13.5}{: <15}", name, util::gflops(size, size, size, time), worst_err);
+}
+
+fn rand_data<T: Scalar>(len: usize) -> Vec<T> {
+    let mut src : Matrix<T> = Matrix::new(len, 1);
+    src.fill_rand();
+    (0..len).map(|i| src.get(i, 0)).collect()
+}
+
+//The h x w column major matrix in data
+fn owned<T: Scalar>(data: &[T], h: usize, w: usize) -> Matrix<T> {
+    let mut mat : Matrix<T> = Matrix::new(h, w);
+    mat.copy_from(&MatrixRef::new(data, h, w, 1, h));
+    mat
+}
+
+//The transpose of the w x h column major matrix in data, copied element by element
+fn transposed_copy<T: Scalar>(data: &[T], h: usize, w: usize) -> Matrix<T> {
+    let mut mat : Matrix<T> = Matrix::new(h, w);
+    for x in 0..w {
+        for y in 0..h {
+            mat.set(y, x, data[x + y*w]);
+        }
+    }
+    mat
+}
+
+fn test_transposed<T: RealScalar, At: Mat<T>, Bt: Mat<T>, S: GemmNode<T, At, Bt, Matrix<T>>>
+    ( mut a: At, mut b: Bt, mut a_ref: Matrix<T>, mut b_ref: Matrix<T> ) -> T
+{
+    let mut c : Matrix<T> = Matrix::new(a.height(), b.width());
+    c.fill_zero();
+    unsafe{ S::new().run( &mut a, &mut b, &mut c, &ThreadInfo::single_thread() ); }
+    util::test_c_eq_a_b( &mut a_ref, &mut b_ref, &mut c)
+}
+
+//NN, NT, TN and TT, with owned and borrowed operands. A transposed operand is stored
+//as its transpose and checked against a copy transposed by hand, so packing must absorb it.
+fn check_transposed<T: RealScalar + LowerExp, Mr: Unsigned, Nr: Unsigned>(name: &str) {
+    let mut worst_err = T::zero();
+    for &(m, n, k) in &[(1, 1, 1), (Mr::to_usize()+1, Nr::to_usize()-1, 7), (123, 77, 200), (480, 480, 480)] {
+        let (a_n, a_t) = (rand_data::<T>(m*k), rand_data::<T>(k*m));
+        let (b_n, b_t) = (rand_data::<T>(k*n), rand_data::<T>(n*k));
+        let errs = [
+            test_transposed::<T, _, _, TGoto<T, Matrix<T>, Matrix<T>, Mr, Nr>>(
+                owned(&a_n, m, k), owned(&b_n, k, n), owned(&a_n, m, k), owned(&b_n, k, n)),
+            test_transposed::<T, _, _, TGoto<T, Matrix<T>, Transposed<Matrix<T>>, Mr, Nr>>(
+                owned(&a_n, m, k), Transposed::new(owned(&b_t, n, k)), owned(&a_n, m, k), transposed_copy(&b_t, k, n)),
+            test_transposed::<T, _, _, TGoto<T, Transposed<Matrix<T>>, Matrix<T>, Mr, Nr>>(
+                Transposed::new(owned(&a_t, k, m)), owned(&b_n, k, n), transposed_copy(&a_t, m, k), owned(&b_n, k, n)),
+            test_transposed::<T, _, _, TGoto<T, Transposed<Matrix<T>>, Transposed<Matrix<T>>, Mr, Nr>>(
+                Transposed::new(owned(&a_t, k, m)), Transposed::new(owned(&b_t, n, k)), transposed_copy(&a_t, m, k), transposed_copy(&b_t, k, n)),
+            test_transposed::<T, _, _, TGoto<T, Transposed<MatrixRef<T>>, MatrixRef<T>, Mr, Nr>>(
+                Transposed::new(MatrixRef::new(&a_t, k, m, 1, k)), MatrixRef::new(&b_n, k, n, 1, k),
+                transposed_copy(&a_t, m, k), owned(&b_n, k, n)),
+            test_transposed::<T, _, _, TGoto<T, MatrixRef<T>, Transposed<MatrixRef<T>>, Mr, Nr>>(
+                MatrixRef::new(&a_n, m, k, 1, m), Transposed::new(MatrixRef::new(&b_t, n, k, 1, n)),
+                owned(&a_n, m, k), transposed_copy(&b_t, k, n)),
+        ];
+        for &err in errs.iter() { worst_err = worst_err.max(err); }
+    }
+    println!("{: <16}{: <13}{: <15.5e}", name, "", worst_err.sqrt());
+}
+
+fn main() {
+    println!("{:?}", cpu_features());
+    println!("{: <16}{: <13}{: <15}", "kernel", "gflops", "error");
+
+    //Haswell
+    check::<f64, U6, U8>("dgemm 6x8");
+    check::<f64, U4, U12>("dgemm 4x12");
+    check::<f64, U12, U4>("dgemm 12x4");
+    check::<f64, U8, U6>("dgemm 8x6");
+    check::<f32, U6, U16>("sgemm 6x16");
+    check::<f32, U4, U24>("sgemm 4x24");
+    check::<f32, U24, U4>("sgemm 24x4");
+    check::<f32, U16, U6>("sgemm 16x6");
+
+    //Sandy Bridge
+    check::<f32, U8, U8>("sgemm 8x8");
+
+    //Skylake-X
+    check::<f64, U16, U14>("dgemm 16x14");
+    check::<f64, U14, U16>("dgemm 14x16");
+    check::<f32, U32, U14>("sgemm 32x14");
+    check::<f32, U14, U32>("sgemm 14x32");
+
+    //Portable
+    check::<f64, U4, U4>("dgemm 4x4");
+
+    //Complex
+    check_complex::<f64, U4, U4>("zgemm 4x4");
+    check_complex::<f32, U4, U8>("cgemm 4x8");
+
+    //Transposed operands
+    check_transposed::<f64, U4, U12>("dgemm NN-TT");
+    check_transposed::<f32, U6, U16>("sgemm NN-TT");
+
+    //Half precision
+    check_half::<bf16, U6, U16>("bf16 6x16");
+    check_half::<f16, U6, U16>("f16 6x16");
+
+    //Integer
+    check_quantized::<u8, i8, U4, U16>("u8i8 4x16");
+    check_quantized::<i8, i8, U4, U16>("i8i8 4x16");
+}
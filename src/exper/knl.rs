@@ -10,12 +10,12 @@ use std::time::{Instant};
 use typenum::{Unsigned,U1};
 
 use momms::kern::KernelNM;
-use momms::matrix::{Scalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
+use momms::matrix::{Scalar, RealScalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
 use momms::composables::{GemmNode, AlgorithmStep, PartM, PartN, PartK, PackA, PackB, UnpackC, SpawnThreads, ParallelM, ParallelN, TheRest, Target};
 use momms::thread_comm::ThreadInfo;
 use momms::util;
 
-fn test_algorithm_flat<T: Scalar, S: GemmNode<T, Matrix<T>, Matrix<T>, Matrix<T>>>
+fn test_algorithm_flat<T: RealScalar, S: GemmNode<T, Matrix<T>, Matrix<T>, Matrix<T>>>
     ( m:usize, n: usize, k: usize, algo: &mut S, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, T) 
 {
     let mut best_time: f64 = 9999999999.0;
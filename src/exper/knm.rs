@@ -10,12 +10,12 @@ use std::time::{Instant};
 use typenum::{Unsigned,U1};
 
 use momms::kern::KnmKernel;
-use momms::matrix::{Scalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
+use momms::matrix::{Scalar, RealScalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
 use momms::composables::{GemmNode, AlgorithmStep, PartM, PartN, PartK, PackA, PackB, UnpackC, SpawnThreads, ParallelM, ParallelN, TheRest, Target};
 use momms::thread_comm::ThreadInfo;
 use momms::util;
 
-fn test_algorithm_flat<T: Scalar, S: GemmNode<T, Matrix<T>, Matrix<T>, Matrix<T>>>
+fn test_algorithm_flat<T: RealScalar, S: GemmNode<T, Matrix<T>, Matrix<T>, Matrix<T>>>
     ( m:usize, n: usize, k: usize, algo: &mut S, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, T) 
 {
     let mut best_time: f64 = 9999999999.0;
@@ -43,7 +43,7 @@ fn test_algorithm_flat<T: Scalar, S: GemmNode<T, Matrix<T>, Matrix<T>, Matrix<T>
     (best_time, worst_err)
 }
     
-fn test_algorithm<T: Scalar, Mr: Unsigned, Nr: Unsigned, Kr:Unsigned, 
+fn test_algorithm<T: RealScalar, Mr: Unsigned, Nr: Unsigned, Kr:Unsigned, 
     S: GemmNode<T, Hierarch<T, Mr, Kr, U1, Mr>, Hierarch<T, Kr, Nr, U1, Kr>, Hierarch<T, Mr, Nr, U1, Mr>>>
     ( m:usize, n: usize, k: usize, algo: &mut S, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, T) 
 {
@@ -8,24 +8,32 @@ extern crate typenum;
 extern crate momms;
 
 use std::time::{Instant};
+use std::fmt::LowerExp;
 use typenum::{Unsigned,U1};
+use std::fs::File;
+use std::io::Read;
 
 use momms::kern::KernelNM;
-use momms::matrix::{Scalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
-use momms::composables::{GemmNode, AlgorithmStep, PartM, PartN, PartK, PackA, PackB, SpawnThreads, ParallelM, ParallelN, TheRest};
+use momms::matrix::{Scalar, RealScalar, Mat, Matrix, Hierarch};
+use momms::composables::{GemmNode, AlgorithmStep, PartM, PartN, PartK, SpawnThreads, ParallelN, TheRest};
+use momms::composables::{DynNode, DynScalar, parse_algorithms};
 use momms::thread_comm::ThreadInfo;
 use momms::util;
-/*
-fn test_blas_dgemm ( m:usize, n: usize, k: usize, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, f64) 
+
+//A reference gemm computing C += A B, e.g. util::blas_dgemm
+type BlasGemm<T> = fn(&mut Matrix<T>, &mut Matrix<T>, &mut Matrix<T>);
+
+fn test_blas<T: RealScalar>
+    ( m:usize, n: usize, k: usize, blas_gemm: BlasGemm<T>, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, T) 
 {
     let mut best_time: f64 = 9999999999.0;
-    let mut worst_err: f64 = 0.0;
+    let mut worst_err: T = T::zero();
 
     for _ in 0..n_reps {
         //Create matrices.
-        let mut a : Matrix<f64> = Matrix::new(m, k);
-        let mut b : Matrix<f64> = Matrix::new(k, n);
-        let mut c : Matrix<f64> = Matrix::new(m, n);
+        let mut a : Matrix<T> = Matrix::new(m, k);
+        let mut b : Matrix<T> = Matrix::new(k, n);
+        let mut c : Matrix<T> = Matrix::new(m, n);
 
         //Fill the matrices
         a.fill_rand(); c.fill_zero(); b.fill_rand();
@@ -35,15 +43,16 @@ fn test_blas_dgemm ( m:usize, n: usize, k: usize, flusher: &mut Vec<f64>, n_reps
             
         //Time and run algorithm
         let start = Instant::now();
-        util::blas_dgemm( &mut a, &mut b, &mut c);
+        blas_gemm( &mut a, &mut b, &mut c);
         best_time = best_time.min(util::dur_seconds(start));
         let err = util::test_c_eq_a_b( &mut a, &mut b, &mut c);
         worst_err = worst_err.max(err);
     }
     (best_time, worst_err)
-}*/
+}
 
-fn test_algorithm<T: Scalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned, 
+//Times algorithms on matrices already in the hierarchical layout their kernels read, so nothing is packed
+fn test_hierarch<T: RealScalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned, 
     S: GemmNode<T, Hierarch<T, Mr, Kc, U1, Mr>, Hierarch<T, Kc, Nr, Nr, U1>, Hierarch<T, Mr, Nr, Nr, U1>>>
     ( m:usize, n: usize, k: usize, algo: &mut S, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, T) 
 {
@@ -73,18 +82,44 @@ fn test_algorithm<T: Scalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned,
     (best_time, worst_err)
 }
 
-fn test() {
-    use typenum::{UInt, UTerm, B0, B1, Unsigned};
+fn test_algorithm<T: RealScalar>
+    ( m:usize, n: usize, k: usize, algo: &mut DynNode<T, Matrix<T>, Matrix<T>, Matrix<T>>, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, T) 
+{
+    let mut best_time: f64 = 9999999999.0;
+    let mut worst_err: T = T::zero();
+
+    for _ in 0..n_reps {
+        //Create matrices.
+        let mut a : Matrix<T> = Matrix::new(m, k);
+        let mut b : Matrix<T> = Matrix::new(k, n);
+        let mut c : Matrix<T> = Matrix::new(m, n);
+
+        //Fill the matrices
+        a.fill_rand(); c.fill_zero(); b.fill_rand();
+
+        //Read a buffer so that A, B, and C are cold in cache.
+        for i in flusher.iter_mut() { *i += 1.0; }
+
+        //Time and run algorithm
+        let start = Instant::now();
+        unsafe{ algo.run( &mut a, &mut b, &mut c, &ThreadInfo::single_thread() ); }
+        best_time = best_time.min(util::dur_seconds(start));
+        let err = util::test_c_eq_a_b( &mut a, &mut b, &mut c);
+        worst_err = worst_err.max(err);
+    }
+    (best_time, worst_err)
+}
+
+fn test<T: RealScalar + LowerExp + DynScalar, MR: Unsigned + Send + 'static, NR: Unsigned + Send + 'static>(config: &str, blas_gemm: Option<BlasGemm<T>>) {
+    use typenum::{UInt, B0};
     type U3000 = UInt<UInt<typenum::U750, B0>, B0>;
     type NC = U3000;
     type KC = typenum::U192; 
     type MC = typenum::U120; 
-    type MR = typenum::U4;
-    type NR = typenum::U12;
-    type GotoA<T> = Hierarch<T, MR, KC, U1, MR>;
-    type GotoB<T> = Hierarch<T, KC, NR, NR, U1>;
-    type GotoC<T> = Hierarch<T, MR, NR, NR, U1>;
-    type Goto<T,MTA,MTB,MTC> 
+    type GotoA<T,MR> = Hierarch<T, MR, KC, U1, MR>;
+    type GotoB<T,NR> = Hierarch<T, KC, NR, NR, U1>;
+    type GotoC<T,MR,NR> = Hierarch<T, MR, NR, NR, U1>;
+    type Goto<T,MTA,MTB,MTC,MR,NR> 
         = SpawnThreads<T, MTA, MTB, MTC,
           PartN<T, MTA, MTB, MTC, NC,
           PartK<T, MTA, MTB, MTC, KC,
@@ -96,10 +131,10 @@ fn test() {
     type KcL3 = typenum::U768;
     type McL2 = typenum::U120;
     type KcL2 = typenum::U192;
-    type L3bA<T> = Hierarch<T, MR, KcL2, U1, MR>;
-    type L3bB<T> = Hierarch<T, KcL2, NR, NR, U1>;
-    type L3bC<T> = Hierarch<T, MR, NR, NR, U1>;
-    type L3B<T,MTA,MTB,MTC> 
+    type L3bA<T,MR> = Hierarch<T, MR, KcL2, U1, MR>;
+    type L3bB<T,NR> = Hierarch<T, KcL2, NR, NR, U1>;
+    type L3bC<T,MR,NR> = Hierarch<T, MR, NR, NR, U1>;
+    type L3B<T,MTA,MTB,MTC,MR,NR> 
         = SpawnThreads<T, MTA, MTB, MTC,
           PartN<T, MTA, MTB, MTC, NcL3,
           PartK<T, MTA, MTB, MTC, KcL3,
@@ -108,32 +143,56 @@ fn test() {
           ParallelN<T, MTA, MTB, MTC, NR, TheRest,
           KernelNM<T, MTA, MTB, MTC, NR, MR>>>>>>>;
 
-    let mut goto = <Goto<f64, GotoA<f64>, GotoB<f64>, GotoC<f64>>>::new();
-    let mut l3b = <L3B<f64, L3bA<f64>, L3bB<f64>, L3bC<f64>>>::new();
+    let mut goto = <Goto<T, GotoA<T,MR>, GotoB<T,NR>, GotoC<T,MR,NR>, MR, NR>>::new();
+    let mut l3b = <L3B<T, L3bA<T,MR>, L3bB<T,NR>, L3bC<T,MR,NR>, MR, NR>>::new();
     goto.set_n_threads(4);
     l3b.set_n_threads(4);
 
+    //Read the algorithms to compare
+    let mut text = String::new();
+    File::open(config).and_then(|mut f| f.read_to_string(&mut text))
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", config, e));
+    let algorithms = parse_algorithms(&text).unwrap_or_else(|e| panic!("{}: {}", config, e));
+    let mut names = Vec::new();
+    let mut trees = Vec::new();
+    for (name, tree) in algorithms {
+        println!("# {}: {}", name, tree);
+        trees.push(tree.build::<T>().unwrap_or_else(|e| panic!("{}: {}: {}", config, name, e)));
+        names.push(name);
+    }
+
     //Initialize array to flush cache with
     let flusher_len = 2*1024*1024; //16MB
     let mut flusher : Vec<f64> = Vec::with_capacity(flusher_len);
     for _ in 0..flusher_len { flusher.push(0.0); }
 
-    println!("m\tn\tk\t{: <13}{: <13}{: <15}{: <15}", "goto", "l3b", "goto", "l3b");
+    print!("m\tn\tk\t{: <13}{: <13}", "goto", "l3b");
+    for name in &names { print!("{: <13}", name); }
+    if blas_gemm.is_some() { print!("{: <13}", "blas"); }
+    print!("{: <15}{: <15}", "goto", "l3b");
+    for name in &names { print!("{: <15}", name); }
+    if blas_gemm.is_some() { print!("{: <15}", "blas"); }
+    println!();
     for index in 01..81 {
         let size = index*50;
         let (m, n, k) = (size, size, size);
 
         let n_reps = 5;
-        let (goto_time, goto_err) = test_algorithm(m, n, k, &mut goto, &mut flusher, n_reps);
-        let (l3b_time, l3b_err) = test_algorithm(m, n, k, &mut l3b, &mut flusher, n_reps);
-
-        println!("{}\t{}\t{}\t{}{}{}{}", 
-                 m, n, k,
-                 format!("{: <13.5}", util::gflops(m,n,k,goto_time)), 
-                 format!("{: <13.5}", util::gflops(m,n,k,l3b_time)), 
-                 format!("{: <15.5e}", goto_err.sqrt()),
-                 format!("{: <15.5e}", l3b_err.sqrt()));
-
+        let (goto_time, goto_err) = test_hierarch(m, n, k, &mut goto, &mut flusher, n_reps);
+        let (l3b_time, l3b_err) = test_hierarch(m, n, k, &mut l3b, &mut flusher, n_reps);
+        let mut gflops = format!("{: <13.5}{: <13.5}", util::gflops(m,n,k,goto_time), util::gflops(m,n,k,l3b_time));
+        let mut errs = format!("{: <15.5e}{: <15.5e}", goto_err.sqrt(), l3b_err.sqrt());
+        for tree in trees.iter_mut() {
+            let (time, err) = test_algorithm(m, n, k, tree, &mut flusher, n_reps);
+            gflops.push_str(&format!("{: <13.5}", util::gflops(m,n,k,time)));
+            errs.push_str(&format!("{: <15.5e}", err.sqrt()));
+        }
+        if let Some(blas_gemm) = blas_gemm {
+            let (time, err) = test_blas(m, n, k, blas_gemm, &mut flusher, n_reps);
+            gflops.push_str(&format!("{: <13.5}", util::gflops(m,n,k,time)));
+            errs.push_str(&format!("{: <15.5e}", err.sqrt()));
+        }
+        println!("{}\t{}\t{}\t{}{}", m, n, k, gflops, errs);
     }
 
     let mut sum = 0.0;
@@ -144,5 +203,20 @@ fn test() {
 }
 
 fn main() {
-    test( );
+    //Usage: exper_l3 [f64|f32] [algorithm file]
+    //Goto and L3B on pre-packed Hierarch matrices are always timed, next to
+    //the algorithms in algorithms/exper_l3_<precision>.txt by default
+    let precision = std::env::args().nth(1).unwrap_or("f64".to_string());
+    let config = std::env::args().nth(2).unwrap_or(
+        format!("{}/algorithms/exper_l3_{}.txt", env!("CARGO_MANIFEST_DIR"), precision));
+    //With BLIS, its gemm is timed too
+    #[cfg(feature="blis")]
+    let (dgemm, sgemm) = (Some(util::blas_dgemm as BlasGemm<f64>), Some(util::blas_sgemm as BlasGemm<f32>));
+    #[cfg(not(feature="blis"))]
+    let (dgemm, sgemm) = (None, None);
+    match precision.as_ref() {
+        "f64" => test::<f64, typenum::U4, typenum::U12>(&config, dgemm),
+        "f32" => test::<f32, typenum::U4, typenum::U24>(&config, sgemm),
+        _ => panic!("Unknown precision {}, expected f64 or f32", precision),
+    }
 }
@@ -11,12 +11,12 @@ use std::time::{Instant};
 use typenum::{U1, B0, UInt, Unsigned};
 
 use momms::kern::{KernelNM,KernelMN};
-use momms::matrix::{Scalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
-use momms::composables::{GemmNode, AlgorithmStep, PartM, PartN, PartK, PackA, PackB, SpawnThreads, ParallelM, ParallelN, TheRest};
+use momms::matrix::{Scalar, RealScalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
+use momms::composables::{GemmNode, AlgorithmStep, PartM, PartN, PartK, PackA, PackB, SpawnThreads, ParallelM, ParallelN, TheRest, Planner, CacheSizes};
 use momms::thread_comm::ThreadInfo;
 use momms::util;
 
-fn test_algorithm<T: Scalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned, CLRS: Unsigned, CLCS: Unsigned, 
+fn test_algorithm<T: RealScalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned, CLRS: Unsigned, CLCS: Unsigned, 
     S: GemmNode<T, Hierarch<T, Mr, Kc, U1, Mr>, Hierarch<T, Kc, Nr, Nr, U1>, Hierarch<T, Mr, Nr, CLRS, CLCS>>>
     ( m:usize, n: usize, k: usize, algo: &mut S, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, T) 
 {
@@ -128,7 +128,10 @@ fn test(m_selector: isize, n_selector: isize, k_selector: isize) {
         flusher.push(0.0);
     }
 
-    println!("m\tn\tk\t{: <13}{: <13}{: <13}{: <13}{: <15}{: <15}{: <15}{: <15}", "goto", "l3a", "l3b", "l3c", "goto", "l3a", "l3b", "l3c");
+    //What the planner would pick for each shape
+    let planner = Planner::new(4, CacheSizes::default());
+
+    println!("m\tn\tk\t{: <13}{: <13}{: <13}{: <13}{: <15}{: <15}{: <15}{: <15}{}", "goto", "l3a", "l3b", "l3c", "goto", "l3a", "l3b", "l3c", "planned");
     for index in 01..81 {
         let size = index*50;
 //        let (m, n, k) = (size, size, size);
@@ -142,7 +145,7 @@ fn test(m_selector: isize, n_selector: isize, k_selector: isize) {
         let (l3b_time, l3b_err) = test_algorithm(m, n, k, &mut l3b, &mut flusher, n_reps);
         let (l3c_time, l3c_err) = test_algorithm(m, n, k, &mut l3c, &mut flusher, n_reps);
 
-        println!("{}\t{}\t{}\t{}{}{}{}{}{}{}{}", 
+        println!("{}\t{}\t{}\t{}{}{}{}{}{}{}{}{:?}", 
                  m, n, k,
                  format!("{: <13.5}", util::gflops(m,n,k,goto_time)), 
                  format!("{: <13.5}", util::gflops(m,n,k,l3a_time)), 
@@ -151,7 +154,8 @@ fn test(m_selector: isize, n_selector: isize, k_selector: isize) {
                  format!("{: <15.5e}", goto_err.sqrt()),
                  format!("{: <15.5e}", l3a_err.sqrt()),
                  format!("{: <15.5e}", l3b_err.sqrt()),
-                 format!("{: <15.5e}", l3c_err.sqrt()));
+                 format!("{: <15.5e}", l3c_err.sqrt()),
+                 planner.choose::<f64>(m, n, k));
     }
 
     let mut sum = 0.0;
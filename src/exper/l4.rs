@@ -7,15 +7,16 @@ extern crate typenum;
 extern crate momms;
 
 use std::time::{Instant};
+use std::fmt::LowerExp;
 use typenum::{Unsigned,U1};
 
 use momms::kern::KernelNM;
-use momms::matrix::{Scalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
-use momms::composables::{GemmNode, AlgorithmStep, PartM, PartN, PartK, PackA, PackB, SpawnThreads, ParallelM, ParallelN, TheRest};
+use momms::matrix::{Scalar, RealScalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
+use momms::composables::{GemmNode, AlgorithmStep, CacheTopology, PartM, PartN, PartK, PackA, PackB, SpawnThreads, ParallelM, ParallelN, TheRest};
 use momms::thread_comm::ThreadInfo;
 use momms::util;
 
-fn test_algorithm<T: Scalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned, CLRS: Unsigned, CLCS: Unsigned, 
+fn test_algorithm<T: RealScalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned, CLRS: Unsigned, CLCS: Unsigned, 
     S: GemmNode<T, Hierarch<T, Mr, Kc, U1, Mr>, Hierarch<T, Kc, Nr, Nr, U1>, Hierarch<T, Mr, Nr, CLRS, CLCS>>>
     ( m:usize, n: usize, k: usize, algo: &mut S, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, T) 
 {
@@ -45,19 +46,17 @@ fn test_algorithm<T: Scalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned, CLRS: Unsi
     (best_time, worst_err)
 }
 
-fn test() {
+fn test<T: RealScalar + LowerExp + 'static, Mr: Unsigned + Send + 'static, Nr: Unsigned + Send + 'static>() {
     use typenum::{UInt, B0};
     type U3000 = UInt<UInt<typenum::U750, B0>, B0>;
     type Nc = U3000;
     type Kc = typenum::U192; 
     type Mc = typenum::U120; 
-    type Mr = typenum::U4;
-    type Nr = typenum::U12;
-    type GotoA<T> = Hierarch<T, Mr, Kc, U1, Mr>;
-    type GotoB<T> = Hierarch<T, Kc, Nr, Nr, U1>;
-    type GotoC<T> = Hierarch<T, Mr, Nr, Nr, U1>;
+    type GotoA<T,Mr> = Hierarch<T, Mr, Kc, U1, Mr>;
+    type GotoB<T,Nr> = Hierarch<T, Kc, Nr, Nr, U1>;
+    type GotoC<T,Mr,Nr> = Hierarch<T, Mr, Nr, Nr, U1>;
 
-    type Goto<T,MTA,MTB,MTC> 
+    type Goto<T,MTA,MTB,MTC,Mr,Nr> 
         = SpawnThreads<T, MTA, MTB, MTC,
           PartN<T, MTA, MTB, MTC, Nc,
           PartK<T, MTA, MTB, MTC, Kc,
@@ -68,7 +67,7 @@ fn test() {
     type U3600 = UInt<UInt<typenum::U900, B0>, B0>;
     type NcL4 = U3600;
     type McL4 = U3600;
-    type L4C<T,MTA,MTB,MTC> 
+    type L4C<T,MTA,MTB,MTC,Mr,Nr> 
         = SpawnThreads<T, MTA, MTB, MTC,
           PartM<T, MTA, MTB, MTC, McL4,
           PartN<T, MTA, MTB, MTC, NcL4,
@@ -77,9 +76,14 @@ fn test() {
           ParallelN<T, MTA, MTB, MTC, Nr, TheRest,
           KernelNM<T, MTA, MTB, MTC, Nr, Mr>>>>>>>;
 
-    let mut goto = <Goto<f64, GotoA<f64>, GotoB<f64>, GotoC<f64>>>::new();
-    let mut l4c = <L4C<f64, GotoA<f64>, GotoB<f64>, GotoC<f64>>>::new();
+    let mut goto = <Goto<T, GotoA<T,Mr>, GotoB<T,Nr>, GotoC<T,Mr,Nr>, Mr, Nr>>::new();
+    let mut l4c = <L4C<T, GotoA<T,Mr>, GotoB<T,Nr>, GotoC<T,Mr,Nr>, Mr, Nr>>::new();
     goto.set_n_threads(4);
+    //The block sizes above were picked by hand; print what the analytical model derives for this machine
+    match CacheTopology::detect().and_then(|topology| topology.blocking_plan::<T>(Mr::to_usize(), Nr::to_usize())) {
+        Ok(plan) => println!("# analytical block sizes: kc {} mc {} nc {}", plan.kc, plan.mc, plan.nc),
+        Err(e) => println!("# {}", e),
+    }
     l4c.set_n_threads(4);
 
     let flusher_len = 32*1024*1024; //256MB
@@ -113,5 +117,11 @@ fn test() {
 }
 
 fn main() {
-    test( );
+    //Usage: exper_l4 [f64|f32]
+    let precision = std::env::args().nth(1).unwrap_or("f64".to_string());
+    match precision.as_ref() {
+        "f64" => test::<f64, typenum::U4, typenum::U12>( ),
+        "f32" => test::<f32, typenum::U4, typenum::U24>( ),
+        _ => panic!("Unknown precision {}, expected f64 or f32", precision),
+    }
 }
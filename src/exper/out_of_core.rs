@@ -11,12 +11,12 @@ use std::time::{Instant};
 use typenum::{Unsigned,U1};
 
 use momms::kern::KernelNM;
-use momms::matrix::{Scalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
+use momms::matrix::{Scalar, RealScalar, Mat, ColumnPanelMatrix, RowPanelMatrix, Matrix, Hierarch};
 use momms::composables::{GemmNode, AlgorithmStep, PartM, PartN, PartK, PackA, PackB, SpawnThreads, ParallelM, ParallelN, TheRest, Barrier};
 use momms::thread_comm::ThreadInfo;
 use momms::util;
 
-fn test_algorithm<T: Scalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned, 
+fn test_algorithm<T: RealScalar, Mr: Unsigned, Nr: Unsigned, Kc:Unsigned, 
     S: GemmNode<T, Hierarch<T, Mr, Kc, U1, Mr>, Hierarch<T, Kc, Nr, Nr, U1>, Hierarch<T, Mr, Nr, Nr, U1>>>
     ( m:usize, n: usize, k: usize, algo: &mut S, flusher: &mut Vec<f64>, n_reps: usize ) -> (f64, T) 
 {
@@ -91,6 +91,15 @@ fn test() {
     goto.set_n_threads(4);
     l3b.set_n_threads(4);
 
+    //With --dot, print the algorithms as Graphviz graphs instead of timing them
+    if std::env::args().any(|arg| arg == "--dot") {
+        print!("{}", <Goto<f64, GotoA<f64>, GotoB<f64>, GotoC<f64>>>::describe().to_dot());
+        print!("{}", <L3B<f64, L3bA<f64>, L3bB<f64>, L3bC<f64>>>::describe().to_dot());
+        return;
+    }
+    println!("goto:\n{}", <Goto<f64, GotoA<f64>, GotoB<f64>, GotoC<f64>>>::describe().loop_nest());
+    println!("l3b:\n{}", <L3B<f64, L3bA<f64>, L3bB<f64>, L3bC<f64>>>::describe().loop_nest());
+
     //Initialize array to flush cache with
     let flusher_len = 2*1024*1024; //16MB
     let mut flusher : Vec<f64> = Vec::with_capacity(flusher_len);
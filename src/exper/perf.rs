@@ -0,0 +1,127 @@
+#![feature(specialization)]
+
+#![allow(unused_imports)]
+
+extern crate momms;
+
+use std::time::{Instant};
+use std::fs::File;
+use std::io::Read;
+
+use momms::matrix::{Scalar, RealScalar, Mat, Matrix};
+use momms::composables::{DynNode, DynScalar, DynSpawnThreads, DynPerfCounters, NodeSpec, TreeBuilder,
+    PerfSample, parse_algorithms, take_perf_samples};
+use momms::thread_comm::ThreadInfo;
+use momms::util;
+
+//Counts every thread of tree: below SpawnThreads, each thread's subtree is wrapped in DynPerfCounters
+fn instrument<T: DynScalar>(tree: &TreeBuilder) -> Result<DynNode<T, Matrix<T>, Matrix<T>, Matrix<T>>, momms::MommsError> {
+    if let Some(&NodeSpec::SpawnThreads(n_threads)) = tree.specs().first() {
+        //Building the whole tree checks that its threads are split consistently
+        tree.build::<T>()?;
+        let rest = tree.specs()[1..].iter().fold(TreeBuilder::new(), |rest, &spec| rest.push(spec));
+        let mut children: Vec<DynNode<T, Matrix<T>, Matrix<T>, Matrix<T>>> = Vec::new();
+        for _ in 0..n_threads {
+            children.push(Box::new(DynPerfCounters::new(rest.build::<T>()?)));
+        }
+        Ok(Box::new(DynSpawnThreads::new(children)))
+    } else {
+        Ok(Box::new(DynPerfCounters::new(tree.build::<T>()?)))
+    }
+}
+
+//Returns the best time, and the counts of each thread during that run
+fn test_algorithm<T: RealScalar>
+    ( m:usize, n: usize, k: usize, algo: &mut DynNode<T, Matrix<T>, Matrix<T>, Matrix<T>>, flusher: &mut Vec<f64>, n_reps: usize )
+    -> (f64, Vec<PerfSample>)
+{
+    let mut best_time: f64 = 9999999999.0;
+    let mut best_samples = Vec::new();
+    for _ in 0..n_reps {
+        //Create matrices.
+        let mut a : Matrix<T> = Matrix::new(m, k);
+        let mut b : Matrix<T> = Matrix::new(k, n);
+        let mut c : Matrix<T> = Matrix::new(m, n);
+
+        //Fill the matrices
+        a.fill_rand(); c.fill_zero(); b.fill_rand();
+
+        //Read a buffer so that A, B, and C are cold in cache.
+        for i in flusher.iter_mut() { *i += 1.0; }
+
+        //Time and run algorithm
+        take_perf_samples();
+        let start = Instant::now();
+        unsafe{ algo.run( &mut a, &mut b, &mut c, &ThreadInfo::single_thread() ); }
+        let time = util::dur_seconds(start);
+        let mut samples = take_perf_samples();
+        samples.sort_by_key(|s| s.thread_id);
+        if time < best_time {
+            best_time = time;
+            best_samples = samples;
+        }
+    }
+    (best_time, best_samples)
+}
+
+fn count(c: Option<u64>) -> String {
+    c.map_or("-".to_string(), |c| c.to_string())
+}
+
+fn test<T: RealScalar + DynScalar>(config: &str) {
+    //Read the algorithms to compare
+    let mut text = String::new();
+    File::open(config).and_then(|mut f| f.read_to_string(&mut text))
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", config, e));
+    let algorithms = parse_algorithms(&text).unwrap_or_else(|e| panic!("{}: {}", config, e));
+    let mut names = Vec::new();
+    let mut trees = Vec::new();
+    for (name, tree) in algorithms {
+        println!("# {}: {}", name, tree);
+        trees.push(instrument::<T>(&tree).unwrap_or_else(|e| panic!("{}: {}: {}", config, name, e)));
+        names.push(name);
+    }
+
+    //Initialize array to flush cache with
+    let flusher_len = 2*1024*1024; //16MB
+    let mut flusher : Vec<f64> = Vec::with_capacity(flusher_len);
+    for _ in 0..flusher_len { flusher.push(0.0); }
+
+    //One line per thread. Events that can't be counted here are shown as -.
+    println!("m\tn\tk\t{: <13}{: <8}{: <13}{: <15}{: <15}{: <15}{: <15}{: <15}{: <15}",
+             "algo", "thread", "gflops", "cycles", "instructions", "l1d misses", "l2 misses", "llc misses", "dtlb misses");
+    for index in 01..17 {
+        let size = index*250;
+        let (m, n, k) = (size, size, size);
+
+        let n_reps = 3;
+        for (name, tree) in names.iter().zip(trees.iter_mut()) {
+            let (time, samples) = test_algorithm(m, n, k, tree, &mut flusher, n_reps);
+            for s in samples {
+                println!("{}\t{}\t{}\t{: <13}{: <8}{: <13.5}{: <15}{: <15}{: <15}{: <15}{: <15}{: <15}",
+                         m, n, k, name, s.thread_id, util::gflops(m,n,k,time),
+                         count(s.cycles), count(s.instructions), count(s.l1d_misses),
+                         count(s.l2_misses), count(s.llc_misses), count(s.dtlb_misses));
+            }
+        }
+    }
+
+    let mut sum = 0.0;
+    for a in flusher.iter() {
+        sum += *a;
+    }
+    println!("Flush value {}", sum);
+}
+
+fn main() {
+    //Usage: exper_perf [f64|f32] [algorithm file]
+    //The algorithms default to those in algorithms/exper_l3_<precision>.txt
+    let precision = std::env::args().nth(1).unwrap_or("f64".to_string());
+    let config = std::env::args().nth(2).unwrap_or(
+        format!("{}/algorithms/exper_l3_{}.txt", env!("CARGO_MANIFEST_DIR"), precision));
+    match precision.as_ref() {
+        "f64" => test::<f64>(&config),
+        "f32" => test::<f32>(&config),
+        _ => panic!("Unknown precision {}, expected f64 or f32", precision),
+    }
+}
@@ -0,0 +1,107 @@
+#![feature(specialization)]
+
+#![allow(unused_imports)]
+
+extern crate core;
+extern crate typenum;
+extern crate momms;
+
+use typenum::{Unsigned, U1};
+
+use momms::kern::KernelNM;
+use momms::matrix::{Scalar, RealScalar, Mat, Matrix, ColumnPanelMatrix, RowPanelMatrix};
+use momms::composables::{GemmNode, PartM, PartN, PartK, PackA, PackB, SpawnThreads, ParallelM, ParallelN, TheRest, Target};
+use momms::thread_comm::ThreadInfo;
+
+//Checks that results are bitwise identical for 1, 4 and 64 threads.
+//Each element of C is reduced over k in the same order whatever the thread count,
+//as long as the parallelizers split on multiples of the micro-tile.
+
+type Nc = typenum::U480;
+type Kc = typenum::U192;
+type Mc = typenum::U96;
+type Goto<T, Mr, Nr>
+    = SpawnThreads<T, Matrix<T>, Matrix<T>, Matrix<T>,
+      PartN<T, Matrix<T>, Matrix<T>, Matrix<T>, Nc,
+      PartK<T, Matrix<T>, Matrix<T>, Matrix<T>, Kc,
+      PackB<T, Matrix<T>, Matrix<T>, Matrix<T>, ColumnPanelMatrix<T,Nr>,
+      PartM<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Mc,
+      PackA<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, RowPanelMatrix<T,Mr>,
+      ParallelN<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Nr, TheRest,
+      KernelNM<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Nr, Mr>>>>>>>>;
+type GotoMN<T, Mr, Nr>
+    = SpawnThreads<T, Matrix<T>, Matrix<T>, Matrix<T>,
+      PartN<T, Matrix<T>, Matrix<T>, Matrix<T>, Nc,
+      PartK<T, Matrix<T>, Matrix<T>, Matrix<T>, Kc,
+      PackB<T, Matrix<T>, Matrix<T>, Matrix<T>, ColumnPanelMatrix<T,Nr>,
+      PartM<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Mc,
+      PackA<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, RowPanelMatrix<T,Mr>,
+      ParallelM<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Mr, Target<typenum::U2>,
+      ParallelN<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Nr, TheRest,
+      KernelNM<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Nr, Mr>>>>>>>>>;
+
+fn run<T: RealScalar, S: GemmNode<T, Matrix<T>, Matrix<T>, Matrix<T>>>
+    ( a: &mut Matrix<T>, b: &mut Matrix<T>, c0: &Matrix<T>, algo: &mut S ) -> Matrix<T>
+{
+    let mut c : Matrix<T> = Matrix::new(c0.height(), c0.width());
+    c.fill_zero();
+    c.copy_from(c0);
+    //A beta other than 0 or 1 makes full and partial tiles round the update of C differently
+    //unless they use the same arithmetic.
+    let half = T::one() / (T::one() + T::one());
+    c.set_scalar(half);
+    unsafe{ algo.run( a, b, &mut c, &ThreadInfo::single_thread() ); }
+    c
+}
+
+//Number of elements that differ from the single threaded result
+fn count_diffs<T: RealScalar>(c: &Matrix<T>, c_ref: &Matrix<T>) -> usize {
+    let mut diffs = 0;
+    for x in 0..c.width() {
+        for y in 0..c.height() {
+            if c.get(y,x) != c_ref.get(y,x) { diffs += 1; }
+        }
+    }
+    diffs
+}
+
+fn check<T: RealScalar, S: GemmNode<T, Matrix<T>, Matrix<T>, Matrix<T>>>
+    (name: &str, thread_counts: &[usize], algos: &mut [S])
+{
+    for &(m, n, k) in &[(1, 1, 1), (13, 29, 7), (123, 77, 200), (500, 500, 500)] {
+        let mut a : Matrix<T> = Matrix::new(m, k);
+        let mut b : Matrix<T> = Matrix::new(k, n);
+        let mut c0 : Matrix<T> = Matrix::new(m, n);
+        a.fill_rand(); b.fill_rand(); c0.fill_rand();
+
+        let c_ref = run(&mut a, &mut b, &c0, &mut algos[0]);
+        print!("{: <12}{}\t{}\t{}", name, m, n, k);
+        for i in 1..algos.len() {
+            let c = run(&mut a, &mut b, &c0, &mut algos[i]);
+            print!("\t{} threads: {} diffs", thread_counts[i], count_diffs(&c, &c_ref));
+        }
+        println!("");
+    }
+}
+
+fn test<T: RealScalar + 'static, Mr: Unsigned + Send + 'static, Nr: Unsigned + Send + 'static>(name: &str) {
+    let thread_counts = [1, 4, 64];
+
+    let mut gotos : Vec<Goto<T, Mr, Nr>> = Vec::new();
+    let mut goto_mns : Vec<GotoMN<T, Mr, Nr>> = Vec::new();
+    for &nt in &thread_counts {
+        let mut goto = <Goto<T, Mr, Nr>>::new();
+        goto.set_n_threads(nt);
+        gotos.push(goto);
+        let mut goto_mn = <GotoMN<T, Mr, Nr>>::new();
+        goto_mn.set_n_threads(nt);
+        goto_mns.push(goto_mn);
+    }
+    check(&format!("{} goto", name), &thread_counts, &mut gotos);
+    check(&format!("{} goto_mn", name), &thread_counts, &mut goto_mns);
+}
+
+fn main() {
+    test::<f64, typenum::U4, typenum::U12>("f64");
+    test::<f32, typenum::U4, typenum::U24>("f32");
+}
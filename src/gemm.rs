@@ -0,0 +1,122 @@
+use std::cell::RefCell;
+use typenum::{self, UInt, B0};
+
+use kern::KernelNM;
+use matrix::{Scalar, RealScalar, Mat, RoCM, RoCMMut, Matrix, MatrixRef, MatrixMut, ColumnPanelMatrix, RowPanelMatrix};
+use composables::{GemmNode, PartM, PartN, PartK, PackA, PackB};
+use thread_comm::ThreadInfo;
+use error::MommsError;
+
+//op(X) in C = alpha op(A) op(B) + beta C
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transpose {
+    NoTrans,
+    Trans,
+    ConjTrans,
+}
+
+//General strided matrices, which gemm can read A and B from without copying
+pub trait StridedMat<T: Scalar>: Mat<T> + RoCM<T> {}
+impl<T: Scalar> StridedMat<T> for Matrix<T> {}
+impl<'a, T: Scalar> StridedMat<T> for MatrixRef<'a, T> {}
+impl<'a, T: Scalar> StridedMat<T> for MatrixMut<'a, T> {}
+
+//General strided matrices that gemm can also write C into. A MatrixRef only borrows its slice for reading.
+pub trait StridedMatMut<T: Scalar>: StridedMat<T> + RoCMMut<T> {}
+impl<T: Scalar> StridedMatMut<T> for Matrix<T> {}
+impl<'a, T: Scalar> StridedMatMut<T> for MatrixMut<'a, T> {}
+
+//An unpartitioned Matrix over the current view of x, so its views and scalar are left alone.
+//Only C's alias is written through, and its buffer comes from get_mut_buffer.
+unsafe fn alias_of<T: Scalar, M: StridedMat<T>>(x: &M, buffer: *mut T) -> Matrix<T> {
+    let mut alias = Matrix::from_raw_parts(buffer, x.height(), x.width(),
+                                           x.get_leaf_rs(), x.get_leaf_cs());
+    if x.is_conjugated() { alias.conjugate(); }
+    alias
+}
+
+fn apply_op<T: Scalar>(x: &mut Matrix<T>, op: Transpose) {
+    match op {
+        Transpose::NoTrans => (),
+        Transpose::Trans => x.transpose(),
+        Transpose::ConjTrans => { x.transpose(); x.conjugate(); },
+    }
+}
+
+type Nc = UInt<UInt<typenum::U750, B0>, B0>;
+type Kc = typenum::U192;
+type Mc = typenum::U120;
+type Goto<T, Mr, Nr>
+    = PartN<T, Matrix<T>, Matrix<T>, Matrix<T>, Nc,
+      PartK<T, Matrix<T>, Matrix<T>, Matrix<T>, Kc,
+      PackB<T, Matrix<T>, Matrix<T>, Matrix<T>, ColumnPanelMatrix<T,Nr>,
+      PartM<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Mc,
+      PackA<T, Matrix<T>, ColumnPanelMatrix<T,Nr>, Matrix<T>, RowPanelMatrix<T,Mr>,
+      KernelNM<T, RowPanelMatrix<T,Mr>, ColumnPanelMatrix<T,Nr>, Matrix<T>, Nr, Mr>>>>>>;
+
+//Scalars with a default control tree.
+//The default trees don't spawn threads, so run_default only uses the calling thread.
+//Each thread keeps its own tree so the packing buffers are reused across calls.
+pub trait GemmScalar: RealScalar {
+    fn run_default(a: &mut Matrix<Self>, b: &mut Matrix<Self>, c: &mut Matrix<Self>);
+}
+impl GemmScalar for f64 {
+    fn run_default(a: &mut Matrix<f64>, b: &mut Matrix<f64>, c: &mut Matrix<f64>) {
+        thread_local!(static TREE: RefCell<Goto<f64, typenum::U4, typenum::U12>> = RefCell::new(GemmNode::new()));
+        TREE.with(|tree| unsafe{ tree.borrow_mut().run(a, b, c, &ThreadInfo::single_thread()) });
+    }
+}
+impl GemmScalar for f32 {
+    fn run_default(a: &mut Matrix<f32>, b: &mut Matrix<f32>, c: &mut Matrix<f32>) {
+        thread_local!(static TREE: RefCell<Goto<f32, typenum::U4, typenum::U24>> = RefCell::new(GemmNode::new()));
+        TREE.with(|tree| unsafe{ tree.borrow_mut().run(a, b, c, &ThreadInfo::single_thread()) });
+    }
+}
+
+//C = alpha op(A) op(B) + beta C on the calling thread, with the semantics of BLAS xGEMM:
+//A and B aren't read when alpha or k is zero, and C isn't read when beta is zero.
+//Only the current views of a, b and c are used, and their scalars are ignored.
+//gemm is single threaded. For more threads, build a control tree under SpawnThreads.
+pub fn gemm<T: GemmScalar, At: StridedMat<T>, Bt: StridedMat<T>, Ct: StridedMatMut<T>>
+    (transa: Transpose, transb: Transpose, alpha: T, a: &At, b: &Bt, beta: T, c: &mut Ct)
+    -> Result<(), MommsError>
+{
+    let mut a = unsafe{ alias_of(a, a.get_buffer() as *mut T) };
+    let mut b = unsafe{ alias_of(b, b.get_buffer() as *mut T) };
+    let mut c = unsafe{ let buffer = c.get_mut_buffer(); alias_of(c, buffer) };
+    apply_op(&mut a, transa);
+    apply_op(&mut b, transb);
+    //Kernels can't update a conjugated C, so update conj(C) = conj(alpha) conj(op(A)) conj(op(B)) + conj(beta) conj(C)
+    let (alpha, beta) = if c.is_conjugated() {
+        a.conjugate();
+        b.conjugate();
+        c.conjugate();
+        (alpha.conj(), beta.conj())
+    } else {
+        (alpha, beta)
+    };
+
+    let (m, n, k) = (c.height(), c.width(), a.width());
+    if a.height() != m || b.width() != n || b.height() != k {
+        return Err(MommsError::NonconformalGemm{ a: (a.height(), a.width()), b: (b.height(), b.width()), c: (m, n) });
+    }
+    if m == 0 || n == 0 {
+        return Ok(());
+    }
+
+    if alpha == T::zero() || k == 0 {
+        for x in 0..n {
+            for y in 0..m {
+                let gamma = if beta == T::zero() { T::zero() } else { beta * c.get(y,x) };
+                c.set(y, x, gamma);
+            }
+        }
+        return Ok(());
+    }
+
+    a.set_scalar(alpha);
+    b.set_scalar(T::one());
+    c.set_scalar(beta);
+    T::run_default(&mut a, &mut b, &mut c);
+    Ok(())
+}
@@ -0,0 +1,23 @@
+//The parts of BLIS's bli_type_defs.h that the BLIS micro-kernels take, declared by hand
+#![allow(non_camel_case_types, non_upper_case_globals, dead_code)]
+
+use std::os::raw::c_void;
+
+pub type inc_t = i64;
+pub type pack_t = u32;
+pub type num_t = u32;
+
+pub const pack_t_BLIS_PACKED_ROW_PANELS: pack_t = (1 << 22) | (1 << 17);
+pub const pack_t_BLIS_PACKED_COL_PANELS: pack_t = (1 << 22) | (1 << 17) | (1 << 16);
+pub const num_t_BLIS_DOUBLE: num_t = 2;
+
+#[repr(C)]
+pub struct auxinfo_t {
+    pub schema_a: pack_t,
+    pub schema_b: pack_t,
+    pub a_next: *mut c_void,
+    pub b_next: *mut c_void,
+    pub is_a: inc_t,
+    pub is_b: inc_t,
+    pub dt_on_output: num_t,
+}
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+//Instruction set extensions that the micro-kernels can be dispatched on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CpuFeatures {
+    pub avx: bool,
+    pub avx2: bool,
+    pub fma: bool,
+    pub avx512f: bool,
+    //The 4FMAPS instructions of Knights Mill
+    pub avx5124fmaps: bool,
+}
+impl CpuFeatures {
+    pub fn hsw(&self) -> bool { self.avx2 && self.fma }
+}
+
+const PROBED: usize = 1;
+const AVX: usize = 1 << 1;
+const AVX2: usize = 1 << 2;
+const FMA: usize = 1 << 3;
+const AVX512F: usize = 1 << 4;
+const AVX5124FMAPS: usize = 1 << 5;
+
+//Zero until the CPU has been probed.
+//Racing threads may each probe, but they all store the same value.
+static FEATURES: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(target_arch="x86_64")]
+#[allow(unused_unsafe)]
+fn probe() -> usize {
+    let mut bits = PROBED;
+    if is_x86_feature_detected!("avx") { bits |= AVX; }
+    if is_x86_feature_detected!("avx2") { bits |= AVX2; }
+    if is_x86_feature_detected!("fma") { bits |= FMA; }
+    if is_x86_feature_detected!("avx512f") {
+        bits |= AVX512F;
+        //AVX512_4FMAPS is bit 3 of edx in leaf 7. The OS saving the zmm state is covered by avx512f.
+        let leaf7 = unsafe { ::std::arch::x86_64::__cpuid_count(7, 0) };
+        if leaf7.edx & (1 << 3) != 0 { bits |= AVX5124FMAPS; }
+    }
+    bits
+}
+
+#[cfg(not(target_arch="x86_64"))]
+fn probe() -> usize {
+    PROBED
+}
+
+#[inline(always)]
+pub fn cpu_features() -> CpuFeatures {
+    let mut bits = FEATURES.load(Ordering::Relaxed);
+    if bits == 0 {
+        bits = probe();
+        FEATURES.store(bits, Ordering::Relaxed);
+    }
+    CpuFeatures {
+        avx: bits & AVX != 0,
+        avx2: bits & AVX2 != 0,
+        fma: bits & FMA != 0,
+        avx512f: bits & AVX512F != 0,
+        avx5124fmaps: bits & AVX5124FMAPS != 0,
+    }
+}
@@ -0,0 +1,15 @@
+use core::arch::x86_64::*;
+use core::ptr;
+
+//Haswell micro-kernels written with AVX2 and FMA intrinsics.
+//Kernels named Mr x Nr with a vectorized Nr prefer row-major C, the others prefer column-major C.
+
+row_ukernel!("avx2,fma", dgemm_6x8, f64, 4, 6, 2, _mm256_setzero_pd, _mm256_set1_pd, _mm256_loadu_pd, _mm256_storeu_pd, _mm256_add_pd, _mm256_mul_pd, _mm256_fmadd_pd);
+row_ukernel!("avx2,fma", dgemm_4x12, f64, 4, 4, 3, _mm256_setzero_pd, _mm256_set1_pd, _mm256_loadu_pd, _mm256_storeu_pd, _mm256_add_pd, _mm256_mul_pd, _mm256_fmadd_pd);
+col_ukernel!("avx2,fma", dgemm_12x4, f64, 4, 3, 4, _mm256_setzero_pd, _mm256_set1_pd, _mm256_loadu_pd, _mm256_storeu_pd, _mm256_add_pd, _mm256_mul_pd, _mm256_fmadd_pd);
+col_ukernel!("avx2,fma", dgemm_8x6, f64, 4, 2, 6, _mm256_setzero_pd, _mm256_set1_pd, _mm256_loadu_pd, _mm256_storeu_pd, _mm256_add_pd, _mm256_mul_pd, _mm256_fmadd_pd);
+
+row_ukernel!("avx2,fma", sgemm_6x16, f32, 8, 6, 2, _mm256_setzero_ps, _mm256_set1_ps, _mm256_loadu_ps, _mm256_storeu_ps, _mm256_add_ps, _mm256_mul_ps, _mm256_fmadd_ps);
+row_ukernel!("avx2,fma", sgemm_4x24, f32, 8, 4, 3, _mm256_setzero_ps, _mm256_set1_ps, _mm256_loadu_ps, _mm256_storeu_ps, _mm256_add_ps, _mm256_mul_ps, _mm256_fmadd_ps);
+col_ukernel!("avx2,fma", sgemm_24x4, f32, 8, 3, 4, _mm256_setzero_ps, _mm256_set1_ps, _mm256_loadu_ps, _mm256_storeu_ps, _mm256_add_ps, _mm256_mul_ps, _mm256_fmadd_ps);
+col_ukernel!("avx2,fma", sgemm_16x6, f32, 8, 2, 6, _mm256_setzero_ps, _mm256_set1_ps, _mm256_loadu_ps, _mm256_storeu_ps, _mm256_add_ps, _mm256_mul_ps, _mm256_fmadd_ps);
@@ -0,0 +1,158 @@
+use matrix::{RealScalar,Mat,RoCM,RoCMMut};
+use core::ptr;
+use core::marker::{PhantomData};
+use composables::{GemmNode,AlgorithmStep,NodeDescription,NodeRole};
+use thread_comm::{ThreadInfo};
+use typenum::Unsigned;
+
+//Accumulates the dot products of a leaf kernel with extra precision.
+pub trait Accumulator<T: RealScalar> {
+    fn new() -> Self;
+    fn add_product(&mut self, a: T, b: T);
+    fn result(&self) -> T;
+}
+
+//Kahan summation of the rounded products
+pub struct Kahan<T: RealScalar> {
+    sum: T,
+    comp: T,
+}
+impl<T: RealScalar> Accumulator<T> for Kahan<T> {
+    #[inline(always)]
+    fn new() -> Self { Kahan{ sum: T::zero(), comp: T::zero() } }
+    #[inline(always)]
+    fn add_product(&mut self, a: T, b: T) {
+        let y = a * b - self.comp;
+        let t = self.sum + y;
+        self.comp = (t - self.sum) - y;
+        self.sum = t;
+    }
+    #[inline(always)]
+    fn result(&self) -> T { self.sum }
+}
+
+//Double-double accumulation (Dot2 of Ogita, Rump and Oishi).
+//Products and sums are split into exact hi + lo pairs with TwoProd and TwoSum,
+//so the result is as accurate as if computed in twice the working precision.
+pub struct DoubleDouble<T: RealScalar> {
+    hi: T,
+    lo: T,
+}
+impl<T: RealScalar> Accumulator<T> for DoubleDouble<T> {
+    #[inline(always)]
+    fn new() -> Self { DoubleDouble{ hi: T::zero(), lo: T::zero() } }
+    #[inline(always)]
+    fn add_product(&mut self, a: T, b: T) {
+        //TwoProd
+        let p = a * b;
+        let p_err = a.mul_add(b, T::zero() - p);
+        //TwoSum
+        let s = self.hi + p;
+        let z = s - self.hi;
+        let s_err = (self.hi - (s - z)) + (p - z);
+
+        self.hi = s;
+        self.lo += p_err + s_err;
+    }
+    #[inline(always)]
+    fn result(&self) -> T { self.hi + self.lo }
+}
+
+//Leaf that computes each element of C with an Accumulator instead of a micro-kernel.
+//It is far slower than KernelNM, but has the same loop structure and works on any RoCM A and B,
+//so the blocking above it can be compared against a high-accuracy baseline.
+//The update of C itself (across PartK for instance) is still rounded once per leaf.
+pub struct KernelCompensated<T: RealScalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned, Acc: Accumulator<T>> {
+    _t: PhantomData<T>,
+    _at: PhantomData<At>,
+    _bt: PhantomData<Bt>,
+    _ct: PhantomData<Ct>,
+    _nrt: PhantomData<Nr>,
+    _mrt: PhantomData<Mr>,
+    _acct: PhantomData<Acc>,
+}
+impl<T: RealScalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned, Acc: Accumulator<T>>
+    GemmNode<T, At, Bt, Ct> for KernelCompensated<T, At, Bt, Ct, Nr, Mr, Acc>
+    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCMMut<T>
+{
+    #[inline(always)]
+    unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, _thr: &ThreadInfo<T>) -> () {
+        super::check_unconjugated("KernelCompensated", a, b, c);
+        let ap = a.get_buffer() as *mut T;
+        let bp = b.get_buffer() as *mut T;
+        let cp = c.get_mut_buffer();
+
+        let m = c.height() as isize;
+        let n = c.width() as isize;
+        let k = a.width() as isize;
+
+        let alpha = a.get_scalar() * b.get_scalar();
+        let beta = c.get_scalar();
+
+        let a_leaf_rs = a.get_leaf_rs() as isize;
+        let a_leaf_cs = a.get_leaf_cs() as isize;
+        let b_leaf_rs = b.get_leaf_rs() as isize;
+        let b_leaf_cs = b.get_leaf_cs() as isize;
+        let c_leaf_rs = c.get_leaf_rs() as isize;
+        let c_leaf_cs = c.get_leaf_cs() as isize;
+
+        let c_nr_stride = c.get_block_cs(1, Nr::to_usize()) as isize;
+        let b_nr_stride = b.get_block_cs(1, Nr::to_usize()) as isize;
+
+        let c_mr_stride = c.get_block_rs(1, Mr::to_usize()) as isize;
+        let a_mr_stride = a.get_block_rs(1, Mr::to_usize()) as isize;
+
+        let mut c_jr = cp;
+        let mut b_jr = bp;
+        let mut jr : isize = 0;
+        while jr < n {
+            b.establish_leaf(0, (jr as usize) / Nr::to_usize(), k as usize, Nr::to_usize());
+            let local_n = if n-jr >= Nr::to_isize() { Nr::to_isize() } else { n-jr };
+            let mut ir : isize = 0;
+            let mut a_ir = ap;
+            let mut c_ir = c_jr;
+            while ir < m {
+                let local_m = if m-ir >= Mr::to_isize() { Mr::to_isize() } else { m-ir };
+                for jj in 0..local_n {
+                    for ii in 0..local_m {
+                        let mut acc = Acc::new();
+                        for p in 0..k {
+                            acc.add_product(ptr::read(a_ir.offset(ii * a_leaf_rs + p * a_leaf_cs)),
+                                            ptr::read(b_jr.offset(p * b_leaf_rs + jj * b_leaf_cs)));
+                        }
+                        let c_ij = c_ir.offset(ii * c_leaf_rs + jj * c_leaf_cs);
+                        let t = alpha * acc.result();
+                        if beta == T::zero() {
+                            ptr::write(c_ij, t);
+                        } else {
+                            ptr::write(c_ij, t + beta * ptr::read(c_ij));
+                        }
+                    }
+                }
+
+                ir += Mr::to_isize();
+                a_ir = a_ir.offset(a_mr_stride);
+                c_ir = c_ir.offset(c_mr_stride);
+            }
+            jr += Nr::to_isize();
+            c_jr = c_jr.offset(c_nr_stride);
+            b_jr = b_jr.offset(b_nr_stride);
+        }
+    }
+    fn new() -> Self {
+        KernelCompensated{ _t: PhantomData, _at: PhantomData, _bt: PhantomData, _ct: PhantomData,
+                           _nrt: PhantomData, _mrt: PhantomData, _acct: PhantomData }
+    }
+    fn hierarchy_description() -> Vec<AlgorithmStep> {
+        let mut desc = Vec::new();
+        desc.push(AlgorithmStep::M{bsz: Mr::to_usize()});
+        desc.push(AlgorithmStep::N{bsz: Nr::to_usize()});
+        desc
+    }
+    fn describe() -> NodeDescription {
+        let (mr, nr) = (Mr::to_usize(), Nr::to_usize());
+        NodeDescription::new("KernelCompensated", format!("for n in blocks of {}, for m in blocks of {}: {}x{} compensated kernel", nr, mr, mr, nr))
+            .param("mr", mr).param("nr", nr)
+            .role(NodeRole::Kernel{ mr: mr, nr: nr, m_outer: false })
+    }
+}
@@ -1,7 +1,7 @@
-use matrix::{Scalar,Mat,RoCM,Matrix};
+use matrix::{Scalar,Mat,RoCM,RoCMMut,Addressed,Matrix,Traced};
 use core::ptr;
 use core::marker::{PhantomData};
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,NodeDescription,NodeRole,trace_kernel};
 use thread_comm::{ThreadInfo};
 use typenum::Unsigned;
 use super::ukernel_wrapper::{UkernelWrapper,GenericUkernelWrapper};
@@ -16,15 +16,16 @@ pub struct KernelMN<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Mr: Unsigned,
 }
 impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Mr: Unsigned, Nr: Unsigned> 
     GemmNode<T, At, Bt, Ct> for KernelMN<T, At, Bt, Ct, Mr, Nr> 
-    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCM<T> {
+    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCMMut<T> {
     #[inline(always)]
     default unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, _thr: &ThreadInfo<T>) -> () {
         //A must be column major and B must be row major 
         debug_assert!(a.get_leaf_rs() == 1 && a.get_leaf_cs() == Mr::to_usize());
         debug_assert!(b.get_leaf_cs() == 1 && b.get_leaf_rs() == Nr::to_usize());
+        super::check_unconjugated("KernelMN", a, b, c);
 
-        let ap = a.get_mut_buffer();
-        let bp = b.get_mut_buffer();
+        let ap = a.get_buffer() as *mut T;
+        let bp = b.get_buffer() as *mut T;
         let cp = c.get_mut_buffer();
 
         let m = c.height() as isize;
@@ -84,6 +85,9 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Mr: Unsigned, Nr: Unsigned>
         }
     }
     fn new() -> Self {
+        if let Err(e) = super::check_packed_leaves::<T, At, Bt>("KernelMN", Mr::to_usize(), Nr::to_usize()) {
+            panic!("{}", e);
+        }
         let mut tmp = <Matrix<T>>::new(Nr::to_usize(), Mr::to_usize());
         tmp.transpose();
         KernelMN{ tmp: tmp, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _nrt: PhantomData, _mrt: PhantomData } 
@@ -94,4 +98,23 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Mr: Unsigned, Nr: Unsigned>
         desc.push(AlgorithmStep::M{bsz: Mr::to_usize()});
         desc
     }  
+    fn describe() -> NodeDescription {
+        let (mr, nr) = (Mr::to_usize(), Nr::to_usize());
+        NodeDescription::new("KernelMN", format!("for m in blocks of {}, for n in blocks of {}: {}x{} micro-kernel", mr, nr, mr, nr))
+            .param("mr", mr).param("nr", nr)
+            .role(NodeRole::Kernel{ mr: mr, nr: nr, m_outer: true })
+    }
+}
+
+//On Traced matrices, report the micro-panels and micro-tiles to C's simulator, then run on the matrices themselves
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Mr: Unsigned, Nr: Unsigned>
+    GemmNode<T, Traced<At>, Traced<Bt>, Traced<Ct>> for KernelMN<T, Traced<At>, Traced<Bt>, Traced<Ct>, Mr, Nr>
+    where At: Addressed<T> + RoCM<T>, Bt: Addressed<T> + RoCM<T>, Ct: Addressed<T> + RoCMMut<T>
+{
+    unsafe fn run(&mut self, a: &mut Traced<At>, b: &mut Traced<Bt>, c: &mut Traced<Ct>, thr: &ThreadInfo<T>) -> () {
+        if let Some(sim) = c.sim() {
+            trace_kernel(sim, a.inner(), b.inner(), c.inner(), Mr::to_usize(), Nr::to_usize(), false);
+        }
+        <KernelMN<T, At, Bt, Ct, Mr, Nr> as GemmNode<T, At, Bt, Ct>>::new().run(a.inner_mut(), b.inner_mut(), c.inner_mut(), thr);
+    }
 }
@@ -1,18 +1,17 @@
-use matrix::{Scalar,Mat,RoCM,Matrix};
+use matrix::{Scalar,Mat,RoCM,RoCMMut,Addressed,Matrix,Traced};
 use core::ptr;
+use core::arch::asm;
 use core::marker::{PhantomData};
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,NodeDescription,NodeRole,trace_kernel};
 use thread_comm::{ThreadInfo};
 use typenum::Unsigned;
 use super::ukernel_wrapper::{UkernelWrapper,GenericUkernelWrapper};
 
 #[inline(always)]
 unsafe fn prefetch_c_row<T: Scalar>(ptr: *mut T) {
-    asm!(" 
-         prefetchw ($0)
-         prefetchw 64($0)
-         " 
-         : : "r"(ptr));
+    asm!("prefetchw [{0}]",
+         "prefetchw [{0} + 64]",
+         in(reg) ptr, options(nostack, preserves_flags));
 }
 
 pub struct KernelNM<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned> {
@@ -25,16 +24,17 @@ pub struct KernelNM<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned,
 }
 impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned> 
     GemmNode<T, At, Bt, Ct> for KernelNM<T, At, Bt, Ct, Nr, Mr> 
-    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCM<T>
+    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCMMut<T>
 {
     #[inline(always)]
     default unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, _thr: &ThreadInfo<T>) -> () {
         //A must be column major and B must be row major 
         debug_assert!(a.get_leaf_rs() == 1 && a.get_leaf_cs() == Mr::to_usize());
         debug_assert!(b.get_leaf_cs() == 1 && b.get_leaf_rs() == Nr::to_usize());
+        super::check_unconjugated("KernelNM", a, b, c);
 
-        let ap = a.get_mut_buffer();
-        let bp = b.get_mut_buffer();
+        let ap = a.get_buffer() as *mut T;
+        let bp = b.get_buffer() as *mut T;
         let cp = c.get_mut_buffer();
 
         let m = c.height() as isize;
@@ -84,12 +84,16 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned>
                     let local_m = if m-ir >= Mr::to_isize() { Mr::to_isize() } else { m-ir };
                     let local_n = if n-jr >= Nr::to_isize() { Nr::to_isize() } else { n-jr };
 
-					//Add t to c
+					//Add t to c. Like the micro-kernels, don't read c when beta is zero.
                     for ii in 0..local_m {
                         for jj in 0..local_n {
                             let tau = ptr::read(tp.offset(ii * t_rs + jj * t_cs));
-                            let chi = ptr::read(c_ir.offset(ii * c_leaf_rs + jj * c_leaf_cs));
-                            ptr::write(c_ir.offset(ii * c_leaf_rs + jj * c_leaf_cs), tau+beta*chi);
+                            let gamma = c_ir.offset(ii * c_leaf_rs + jj * c_leaf_cs);
+                            if beta == T::zero() {
+                                ptr::write(gamma, tau);
+                            } else {
+                                ptr::write(gamma, tau+beta*ptr::read(gamma));
+                            }
                         }
                     }
                 }
@@ -105,6 +109,9 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned>
 
     }
     fn new() -> Self {
+        if let Err(e) = super::check_packed_leaves::<T, At, Bt>("KernelNM", Mr::to_usize(), Nr::to_usize()) {
+            panic!("{}", e);
+        }
         let mut tmp = <Matrix<T>>::new(Nr::to_usize(), Mr::to_usize());
         tmp.transpose();
         KernelNM{ tmp: tmp, _at: PhantomData, _bt: PhantomData, _ct: PhantomData, _nrt: PhantomData, _mrt: PhantomData } 
@@ -115,4 +122,23 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned>
         desc.push(AlgorithmStep::N{bsz: Nr::to_usize()});
         desc
     }  
+    fn describe() -> NodeDescription {
+        let (mr, nr) = (Mr::to_usize(), Nr::to_usize());
+        NodeDescription::new("KernelNM", format!("for n in blocks of {}, for m in blocks of {}: {}x{} micro-kernel", nr, mr, mr, nr))
+            .param("mr", mr).param("nr", nr)
+            .role(NodeRole::Kernel{ mr: mr, nr: nr, m_outer: false })
+    }
+}
+
+//On Traced matrices, report the micro-panels and micro-tiles to C's simulator, then run on the matrices themselves
+impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned>
+    GemmNode<T, Traced<At>, Traced<Bt>, Traced<Ct>> for KernelNM<T, Traced<At>, Traced<Bt>, Traced<Ct>, Nr, Mr>
+    where At: Addressed<T> + RoCM<T>, Bt: Addressed<T> + RoCM<T>, Ct: Addressed<T> + RoCMMut<T>
+{
+    unsafe fn run(&mut self, a: &mut Traced<At>, b: &mut Traced<Bt>, c: &mut Traced<Ct>, thr: &ThreadInfo<T>) -> () {
+        if let Some(sim) = c.sim() {
+            trace_kernel(sim, a.inner(), b.inner(), c.inner(), Mr::to_usize(), Nr::to_usize(), true);
+        }
+        <KernelNM<T, At, Bt, Ct, Nr, Mr> as GemmNode<T, At, Bt, Ct>>::new().run(a.inner_mut(), b.inner_mut(), c.inner_mut(), thr);
+    }
 }
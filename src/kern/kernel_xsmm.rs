@@ -1,6 +1,7 @@
-use matrix::{Scalar,Mat,RoCM};
+use matrix::{Scalar,Mat,RoCM,RoCMMut};
 use core::marker::{PhantomData};
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,NodeDescription,NodeRole};
+use core::arch::asm;
 use thread_comm::{ThreadInfo};
 use typenum::Unsigned;
 use super::xsmm_wrapper::*;
@@ -13,12 +14,13 @@ pub struct Xsmm<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>> {
 }
 impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>>
     GemmNode<T, At, Bt, Ct> for Xsmm<T, At, Bt, Ct> 
-    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCM<T>
+    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCMMut<T>
 {
     #[inline(always)]
     default unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, _thr: &ThreadInfo<T>) -> () {
-        let ap = a.get_mut_buffer();
-        let bp = b.get_mut_buffer();
+        super::check_unconjugated("Xsmm", a, b, c);
+        let ap = a.get_buffer() as *mut T;
+        let bp = b.get_buffer() as *mut T;
         let cp = c.get_mut_buffer();
 
         let m = c.height() as isize;
@@ -49,6 +51,9 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>>
     fn hierarchy_description() -> Vec<AlgorithmStep> {
         Vec::new()
     }
+    fn describe() -> NodeDescription {
+        NodeDescription::new("Xsmm", "libxsmm kernel".to_string())
+    }
 }
 
 pub struct KernelXsmmA2<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned> {
@@ -61,15 +66,16 @@ pub struct KernelXsmmA2<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsig
 }
 impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned> 
     GemmNode<T, At, Bt, Ct> for KernelXsmmA2<T, At, Bt, Ct, Nr, Mr> 
-    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCM<T>
+    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCMMut<T>
 {
     #[inline(always)]
     default unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, _thr: &ThreadInfo<T>) -> () {
         //A, B, and C must be row major
         debug_assert!(a.get_leaf_cs() == 1 && b.get_leaf_cs() == 1 && c.get_leaf_cs() == 1);
+        super::check_unconjugated("KernelXsmmA2", a, b, c);
 
-        let ap = a.get_mut_buffer();
-        let bp = b.get_mut_buffer();
+        let ap = a.get_buffer() as *mut T;
+        let bp = b.get_buffer() as *mut T;
         let cp = c.get_mut_buffer();
 
         let m = c.height() as isize;
@@ -101,14 +107,10 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned>
                 //prefetch next C
                 let next_c_ir = c_ir.offset(c_mr_stride);
                 if cfg!(feature="asm_snippets") {
-                    asm!(" prefetcht2 ($0)
-                           prefetcht2 64($0)" : : "r"(next_c_ir));
-                    asm!(" prefetcht2 ($0)
-                           prefetcht2 64($0)" : : "r"(next_c_ir.offset(c_leaf_rs)));
-                    asm!(" prefetcht2 ($0)
-                           prefetcht2 64($0)" : : "r"(next_c_ir.offset(2*c_leaf_rs)));
-                    asm!(" prefetcht2 ($0)
-                           prefetcht2 64($0)" : : "r"(next_c_ir.offset(3*c_leaf_rs)));
+                    asm!("prefetcht2 [{0}]", "prefetcht2 [{0} + 64]", in(reg) next_c_ir, options(nostack, preserves_flags));
+                    asm!("prefetcht2 [{0}]", "prefetcht2 [{0} + 64]", in(reg) next_c_ir.offset(c_leaf_rs), options(nostack, preserves_flags));
+                    asm!("prefetcht2 [{0}]", "prefetcht2 [{0} + 64]", in(reg) next_c_ir.offset(2*c_leaf_rs), options(nostack, preserves_flags));
+                    asm!("prefetcht2 [{0}]", "prefetcht2 [{0} + 64]", in(reg) next_c_ir.offset(3*c_leaf_rs), options(nostack, preserves_flags));
                 }
 
                 let u_m = if m-ir >= Mr::to_isize() { Mr::to_isize() } else { m-ir };
@@ -136,4 +138,10 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Nr: Unsigned, Mr: Unsigned>
         desc.push(AlgorithmStep::N{bsz: Nr::to_usize()});
         desc
     }  
+    fn describe() -> NodeDescription {
+        let (mr, nr) = (Mr::to_usize(), Nr::to_usize());
+        NodeDescription::new("KernelXsmmA2", format!("for n in blocks of {}, for m in blocks of {}: {}x{} libxsmm kernel", nr, mr, mr, nr))
+            .param("mr", mr).param("nr", nr)
+            .role(NodeRole::Kernel{ mr: mr, nr: nr, m_outer: false })
+    }
 }
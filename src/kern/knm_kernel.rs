@@ -1,6 +1,6 @@
-use matrix::{Scalar,Mat,RoCM,Matrix};
+use matrix::{Scalar,Mat,RoCM,RoCMMut,Matrix};
 use core::{ptr, marker::PhantomData};
-use composables::{GemmNode,AlgorithmStep};
+use composables::{GemmNode,AlgorithmStep,NodeDescription,NodeRole};
 use thread_comm::{ThreadInfo};
 use typenum::Unsigned;
 use super::knm_kernel_wrapper::{KnmKernelWrapper,GenericKnmKernelWrapper};
@@ -35,18 +35,24 @@ impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Mr: Unsigned, Nr: Unsigned>
         desc.push(AlgorithmStep::K{bsz: 4});
         desc
     }
+    fn describe() -> NodeDescription {
+        let (mr, nr) = (Mr::to_usize(), Nr::to_usize());
+        NodeDescription::new("KnmKernel", format!("{}x{} micro-kernel, k in blocks of 4", mr, nr)).param("mr", mr).param("nr", nr)
+            .role(NodeRole::Kernel{ mr: mr, nr: nr, m_outer: false })
+    }
 }
 
 impl<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>, Mr: Unsigned, Nr: Unsigned> 
     GemmNode<T, At, Bt, Ct> for KnmKernel<T, At, Bt, Ct, Mr, Nr> 
-    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCM<T>
+    where At: RoCM<T>, Bt: RoCM<T>, Ct: RoCMMut<T>
 {
     #[inline(always)]
     unsafe fn run(&mut self, a: &mut At, b: &mut Bt, c: &mut Ct, _thr: &ThreadInfo<T>) -> () {
         debug_assert!(c.height() <= Mr::to_usize());
         debug_assert!(c.width() <= Nr::to_usize());
-        let ap = a.get_mut_buffer();
-        let bp = b.get_mut_buffer();
+        super::check_unconjugated("KnmKernel", a, b, c);
+        let ap = a.get_buffer() as *mut T;
+        let bp = b.get_buffer() as *mut T;
         let cp = c.get_mut_buffer();
 
         let c_leaf_rs = c.get_leaf_rs() as isize;
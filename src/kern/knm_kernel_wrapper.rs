@@ -1,14 +1,7 @@
-#[allow(dead_code, non_snake_case, non_camel_case_types, non_upper_case_globals)]
-#[cfg(feature="blis")]
-pub mod blis_types 
-{
-    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
-}
-
 use matrix::{Scalar};
 use core::marker::{PhantomData};
-use typenum::{Unsigned};
-
+use typenum::{Unsigned,U4};
+use super::portable_ukernel;
 
 pub trait GenericKnmKernelWrapper<Mr: Unsigned, Nr: Unsigned, T: Scalar> {
     unsafe fn run( k: isize, alpha: *mut T, a: *mut T, b: *mut T, beta: *mut T, c: *mut T, rs_c: isize, cs_c: isize) -> (); 
@@ -23,8 +16,9 @@ impl<Mr: Unsigned, Nr: Unsigned, T: Scalar> KnmKernelWrapper<Mr, Nr, T> {
 }
 impl<Mr: Unsigned, Nr: Unsigned, T: Scalar> GenericKnmKernelWrapper<Mr, Nr, T> for KnmKernelWrapper<Mr, Nr, T> {
     #[inline(always)]
-    default unsafe fn run( _: isize, _: *mut T, _: *mut T, _: *mut T, _: *mut T, _: *mut T, _: isize, _: isize) {
-        panic!("KnmKernel Wrapper not implemented for Mr {} Nr {} and this datatype!", Mr::to_usize(), Nr::to_usize());
+    default unsafe fn run( k: isize, alpha: *mut T, a: *mut T, b: *mut T, beta: *mut T, c: *mut T, rs_c: isize, cs_c: isize) {
+        //KnmKernel packs B in groups of 4 rows for the 4FMA instructions.
+        portable_ukernel::run::<T, Mr, Nr, U4>(k, alpha, a, b, beta, c, rs_c, cs_c);
     }
 }
 
@@ -33,9 +27,11 @@ pub mod knm
 {
     extern crate libc;
     use self::libc::{ c_float, int64_t };
-    use typenum::{U16,U24};
+    use typenum::{U4,U16,U24};
     use kern::knm_kernel_wrapper::{GenericKnmKernelWrapper,KnmKernelWrapper};
-   // use kern::knm_kernel_wrapper::blis_types::{self,auxinfo_t,inc_t};
+    use kern::portable_ukernel;
+    use kern::cpu_features::cpu_features;
+   // use kern::blis_types::{self,auxinfo_t,inc_t};
 
     //Haswell ukernels
     extern{
@@ -50,6 +46,9 @@ pub mod knm
     impl GenericKnmKernelWrapper<U16, U24, f32> for KnmKernelWrapper<U16, U24, f32> {
         #[inline(always)]
         unsafe fn run( k: isize, alpha: *mut f32, a: *mut f32, b: *mut f32, beta: *mut f32, c: *mut f32, rs_c: isize, cs_c: isize) {
+            if !cpu_features().avx5124fmaps {
+                return portable_ukernel::run::<f32, U16, U24, U4>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
 
 /*            let mut info = auxinfo_t{
 				schema_a: blis_types::pack_t_BLIS_PACKED_ROW_PANELS,
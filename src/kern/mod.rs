@@ -3,14 +3,55 @@ mod kernel_mn;
 mod ukernel;
 mod kernel_xsmm;
 mod knm_kernel;
+mod qkernel_nm;
+mod kernel_compensated;
 
 pub use self::kernel_nm::KernelNM;
 pub use self::kernel_mn::KernelMN;
 pub use self::ukernel::Ukernel;
 pub use self::kernel_xsmm::{Xsmm,KernelXsmmA2};
 pub use self::knm_kernel::KnmKernel;
+pub use self::qkernel_nm::QKernelNM;
+pub use self::kernel_compensated::{KernelCompensated,Accumulator,Kahan,DoubleDouble};
+pub use self::cpu_features::{CpuFeatures,cpu_features};
 
 //Private
 mod knm_kernel_wrapper;
 mod ukernel_wrapper;
+mod portable_ukernel;
+#[cfg(target_arch="x86_64")]
+#[macro_use]
+mod simd_ukernel;
+#[cfg(target_arch="x86_64")]
+mod hsw_ukernel;
+#[cfg(target_arch="x86_64")]
+mod skx_ukernel;
+#[cfg(target_arch="x86_64")]
+mod snb_ukernel;
+mod cpu_features;
 mod xsmm_wrapper;
+#[cfg(feature="blis")]
+mod blis_types;
+
+use matrix::{Scalar,Mat};
+use MommsError;
+
+//Kernels read A and B's buffers and write C's directly, so none of them may be logically conjugated.
+//Packing absorbs the conjugation of A and B, but nothing can absorb C's.
+#[inline(always)]
+fn check_unconjugated<T: Scalar, At: Mat<T>, Bt: Mat<T>, Ct: Mat<T>>(node: &'static str, a: &At, b: &Bt, c: &Ct) {
+    assert!(!a.is_conjugated() && !b.is_conjugated(), "{} needs the conjugation of A and B absorbed by packing", node);
+    assert!(!c.is_conjugated(), "{} can't update a conjugated C", node);
+}
+
+//Micro-kernels read A in column major leaves of Mr rows and B in row major leaves of Nr columns.
+//Formats whose leaf strides are only known at runtime are left to the kernels' debug_assert!s.
+fn check_packed_leaves<T: Scalar, At: Mat<T>, Bt: Mat<T>>(node: &'static str, mr: usize, nr: usize) -> Result<(), MommsError> {
+    let check = |operand, expected, found: Option<(usize, usize)>| match found {
+        Some(found) if found != expected =>
+            Err(MommsError::MismatchedLeaves{ node: node, operand: operand, expected: expected, found: found }),
+        _ => Ok(()),
+    };
+    check('A', (1, mr), At::leaf_strides())?;
+    check('B', (nr, 1), Bt::leaf_strides())
+}
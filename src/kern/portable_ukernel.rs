@@ -0,0 +1,76 @@
+use matrix::{Scalar};
+use core::{ptr, cmp};
+use typenum::{Unsigned};
+
+//Size of the block of C that is accumulated in registers.
+//An Mr x Nr micro-tile is covered by these blocks, each one streaming through the packed
+//micro-panels of A and B again, so Mr and Nr need not be multiples of them.
+const RB_M: isize = 4;
+const RB_N: isize = 4;
+
+//Portable micro-kernel. Computes C := beta C + alpha A B for an Mr x Nr block of C.
+//A is a packed column-major Mr x k micro-panel.
+//B is a packed k x Nr micro-panel in which each group of Kb consecutive rows is stored column-major,
+//so Kb = 1 is the usual row-major BLIS layout.
+//Like the BLIS kernels, C is not read when beta is zero.
+#[inline(always)]
+pub unsafe fn run<T: Scalar, Mr: Unsigned, Nr: Unsigned, Kb: Unsigned>
+    (k: isize, alpha: *mut T, a: *mut T, b: *mut T, beta: *mut T, c: *mut T, rs_c: isize, cs_c: isize) {
+    let mr = Mr::to_isize();
+    let nr = Nr::to_isize();
+    let kb = Kb::to_isize();
+
+    let alpha = ptr::read(alpha);
+    let beta = ptr::read(beta);
+
+    let mut ir : isize = 0;
+    while ir < mr {
+        let m_blk = cmp::min(RB_M, mr - ir);
+        let mut jr : isize = 0;
+        while jr < nr {
+            let n_blk = cmp::min(RB_N, nr - jr);
+            let mut ab = [T::zero(); (RB_M * RB_N) as usize];
+
+            if m_blk == RB_M && n_blk == RB_N {
+                //Full register block. Trip counts are constant so these loops get unrolled.
+                for p in 0..k {
+                    let a_p = a.offset(p * mr + ir);
+                    let b_p = b.offset((p / kb) * kb * nr + p % kb + jr * kb);
+                    for j in 0..RB_N {
+                        let beta_pj = ptr::read(b_p.offset(j * kb));
+                        for i in 0..RB_M {
+                            ab[(j * RB_M + i) as usize] += ptr::read(a_p.offset(i)) * beta_pj;
+                        }
+                    }
+                }
+            } else {
+                for p in 0..k {
+                    let a_p = a.offset(p * mr + ir);
+                    let b_p = b.offset((p / kb) * kb * nr + p % kb + jr * kb);
+                    for j in 0..n_blk {
+                        let beta_pj = ptr::read(b_p.offset(j * kb));
+                        for i in 0..m_blk {
+                            ab[(j * RB_M + i) as usize] += ptr::read(a_p.offset(i)) * beta_pj;
+                        }
+                    }
+                }
+            }
+
+            //Update C
+            let c_blk = c.offset(ir * rs_c + jr * cs_c);
+            for j in 0..n_blk {
+                for i in 0..m_blk {
+                    let gamma = c_blk.offset(i * rs_c + j * cs_c);
+                    let t = alpha * ab[(j * RB_M + i) as usize];
+                    if beta == T::zero() {
+                        ptr::write(gamma, t);
+                    } else {
+                        ptr::write(gamma, t + beta * ptr::read(gamma));
+                    }
+                }
+            }
+            jr += RB_N;
+        }
+        ir += RB_M;
+    }
+}
@@ -0,0 +1,131 @@
+use matrix::{Mat,RoCMMut,QuantElem,QRowPanelMatrix,QColumnPanelMatrix,K_GROUP};
+use core::ptr;
+use core::marker::{PhantomData};
+use composables::{GemmNode,AlgorithmStep,NodeDescription,NodeRole};
+use thread_comm::{ThreadInfo};
+use typenum::Unsigned;
+
+//Portable integer micro-kernel. Computes the m x n corner of
+//C = alpha A B + beta C, with A and B in the interleaved layout of QRowPanelMatrix
+//and QColumnPanelMatrix and products accumulated in i32.
+//Like other integer gemms, sums past the range of i32 wrap around instead of panicking;
+//u8 x i8 products can overflow once k passes about 66000.
+//acc is Mr x Nr scratch space; C is not read when beta is zero.
+#[inline(always)]
+unsafe fn qgemm_ukernel<Sa: QuantElem, Sb: QuantElem, Mr: Unsigned, Nr: Unsigned>
+    (k: usize, alpha: i32, a: *const Sa, b: *const Sb, beta: i32, c: *mut i32, rs_c: isize, cs_c: isize,
+     m: usize, n: usize, acc: &mut [i32])
+{
+    let mr = Mr::to_usize();
+    let nr = Nr::to_usize();
+    for x in acc.iter_mut() { *x = 0; }
+
+    let n_groups = (k + K_GROUP - 1) / K_GROUP;
+    for g in 0..n_groups {
+        let a_g = a.offset((g * K_GROUP * mr) as isize);
+        let b_g = b.offset((g * K_GROUP * nr) as isize);
+        for i in 0..mr {
+            let a_i = a_g.offset((i * K_GROUP) as isize);
+            let a0 = ptr::read(a_i).widen();
+            let a1 = ptr::read(a_i.offset(1)).widen();
+            let a2 = ptr::read(a_i.offset(2)).widen();
+            let a3 = ptr::read(a_i.offset(3)).widen();
+            let acc_i = &mut acc[i*nr .. (i+1)*nr];
+            for (j, gamma) in acc_i.iter_mut().enumerate() {
+                let b_j = b_g.offset((j * K_GROUP) as isize);
+                let dot = a0.wrapping_mul(ptr::read(b_j).widen())
+                    .wrapping_add(a1.wrapping_mul(ptr::read(b_j.offset(1)).widen()))
+                    .wrapping_add(a2.wrapping_mul(ptr::read(b_j.offset(2)).widen()))
+                    .wrapping_add(a3.wrapping_mul(ptr::read(b_j.offset(3)).widen()));
+                *gamma = gamma.wrapping_add(dot);
+            }
+        }
+    }
+
+    for i in 0..m {
+        for j in 0..n {
+            let c_ij = c.offset(i as isize * rs_c + j as isize * cs_c);
+            let t = alpha.wrapping_mul(acc[i*nr + j]);
+            if beta == 0 {
+                ptr::write(c_ij, t);
+            } else {
+                ptr::write(c_ij, t.wrapping_add(beta.wrapping_mul(ptr::read(c_ij))));
+            }
+        }
+    }
+}
+
+//Leaf of an integer gemm. A and B are 8-bit packed panels and C accumulates in i32.
+pub struct QKernelNM<Sa: QuantElem, Sb: QuantElem, Ct: Mat<i32>, Nr: Unsigned, Mr: Unsigned> {
+    acc: Vec<i32>,
+    _sat: PhantomData<Sa>,
+    _sbt: PhantomData<Sb>,
+    _ct: PhantomData<Ct>,
+    _nrt: PhantomData<Nr>,
+    _mrt: PhantomData<Mr>,
+}
+impl<Sa: QuantElem, Sb: QuantElem, Ct: Mat<i32>, Nr: Unsigned, Mr: Unsigned>
+    GemmNode<i32, QRowPanelMatrix<Sa,Mr>, QColumnPanelMatrix<Sb,Nr>, Ct> for QKernelNM<Sa, Sb, Ct, Nr, Mr>
+    where Ct: RoCMMut<i32>
+{
+    #[inline(always)]
+    unsafe fn run(&mut self, a: &mut QRowPanelMatrix<Sa,Mr>, b: &mut QColumnPanelMatrix<Sb,Nr>, c: &mut Ct, _thr: &ThreadInfo<i32>) -> () {
+        let ap = a.get_buffer();
+        let bp = b.get_buffer();
+        let cp = c.get_mut_buffer();
+
+        let m = c.height();
+        let n = c.width();
+        let k = a.width();
+
+        let alpha = a.get_scalar() * b.get_scalar();
+        let beta = c.get_scalar();
+
+        let c_leaf_rs = c.get_leaf_rs() as isize;
+        let c_leaf_cs = c.get_leaf_cs() as isize;
+
+        let c_nr_stride = c.get_block_cs(1, Nr::to_usize()) as isize;
+        let b_nr_stride = b.get_panel_stride() as isize;
+
+        let c_mr_stride = c.get_block_rs(1, Mr::to_usize()) as isize;
+        let a_mr_stride = a.get_panel_stride() as isize;
+
+        let mut c_jr = cp;
+        let mut b_jr = bp;
+        let mut jr = 0;
+        while jr < n {
+            let local_n = if n-jr >= Nr::to_usize() { Nr::to_usize() } else { n-jr };
+            let mut ir = 0;
+            let mut a_ir = ap;
+            let mut c_ir = c_jr;
+            while ir < m {
+                let local_m = if m-ir >= Mr::to_usize() { Mr::to_usize() } else { m-ir };
+                qgemm_ukernel::<Sa, Sb, Mr, Nr>(k, alpha, a_ir, b_jr, beta, c_ir, c_leaf_rs, c_leaf_cs,
+                                                local_m, local_n, &mut self.acc);
+
+                ir += Mr::to_usize();
+                a_ir = a_ir.offset(a_mr_stride);
+                c_ir = c_ir.offset(c_mr_stride);
+            }
+            jr += Nr::to_usize();
+            c_jr = c_jr.offset(c_nr_stride);
+            b_jr = b_jr.offset(b_nr_stride);
+        }
+    }
+    fn new() -> Self {
+        QKernelNM{ acc: vec![0; Mr::to_usize() * Nr::to_usize()],
+                   _sat: PhantomData, _sbt: PhantomData, _ct: PhantomData, _nrt: PhantomData, _mrt: PhantomData }
+    }
+    fn hierarchy_description() -> Vec<AlgorithmStep> {
+        let mut desc = Vec::new();
+        desc.push(AlgorithmStep::M{bsz: Mr::to_usize()});
+        desc.push(AlgorithmStep::N{bsz: Nr::to_usize()});
+        desc
+    }
+    fn describe() -> NodeDescription {
+        let (mr, nr) = (Mr::to_usize(), Nr::to_usize());
+        NodeDescription::new("QKernelNM", format!("for n in blocks of {}, for m in blocks of {}: {}x{} integer micro-kernel", nr, mr, mr, nr))
+            .param("mr", mr).param("nr", nr)
+            .role(NodeRole::Kernel{ mr: mr, nr: nr, m_outer: false })
+    }
+}
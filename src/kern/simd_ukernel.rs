@@ -0,0 +1,141 @@
+//Register-blocked micro-kernels written with SIMD intrinsics.
+//These follow the same contract as the BLIS kernels:
+//A is a packed column-major Mr x k micro-panel, B is a packed row-major k x Nr micro-panel,
+//C := beta C + alpha A B, and C is not read when beta is zero.
+//The update of C rounds alpha AB and beta C separately, exactly as the partial tiles in KernelNM do,
+//so every element of C gets the same arithmetic no matter where the tile boundaries fall.
+//The instantiating module must bring core::arch::x86_64::* and core::ptr into scope.
+
+//Kernels that keep rows of the Mr x Nr block of C in vector registers.
+//Each iteration of k loads one row of B and broadcasts each element of a column of A.
+//These are fastest when C is row major (cs_c == 1).
+macro_rules! row_ukernel {
+    ($feature:tt, $name:ident, $t:ty, $vl:expr, $mr:expr, $nv:expr,
+     $setzero:ident, $set1:ident, $loadu:ident, $storeu:ident, $add:ident, $mul:ident, $fmadd:ident) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn $name(k: isize, alpha: *mut $t, a: *mut $t, b: *mut $t, beta: *mut $t,
+                            c: *mut $t, rs_c: isize, cs_c: isize) {
+            let mut ab = [[$setzero(); $nv]; $mr];
+
+            let mut a_p = a as *const $t;
+            let mut b_p = b as *const $t;
+            for _ in 0..k {
+                let mut b_row = [$setzero(); $nv];
+                for j in 0..$nv {
+                    b_row[j] = $loadu(b_p.offset((j * $vl) as isize));
+                }
+                for i in 0..$mr {
+                    let alpha_ip = $set1(ptr::read(a_p.offset(i as isize)));
+                    for j in 0..$nv {
+                        ab[i][j] = $fmadd(alpha_ip, b_row[j], ab[i][j]);
+                    }
+                }
+                a_p = a_p.offset($mr);
+                b_p = b_p.offset($nv * $vl);
+            }
+
+            let alpha = ptr::read(alpha);
+            let beta = ptr::read(beta);
+            let alpha_v = $set1(alpha);
+            let beta_v = $set1(beta);
+            if cs_c == 1 {
+                for i in 0..$mr {
+                    for j in 0..$nv {
+                        let c_ij = c.offset(i as isize * rs_c + (j * $vl) as isize);
+                        let t = $mul(alpha_v, ab[i][j]);
+                        if beta == 0.0 {
+                            $storeu(c_ij, t);
+                        } else {
+                            $storeu(c_ij, $add(t, $mul(beta_v, $loadu(c_ij))));
+                        }
+                    }
+                }
+            } else {
+                let mut t = [0.0 as $t; $mr * $nv * $vl];
+                for i in 0..$mr {
+                    for j in 0..$nv {
+                        $storeu(t.as_mut_ptr().offset((i * $nv * $vl + j * $vl) as isize), $mul(alpha_v, ab[i][j]));
+                    }
+                }
+                for j in 0..($nv * $vl) {
+                    for i in 0..$mr {
+                        let gamma = c.offset(i as isize * rs_c + j as isize * cs_c);
+                        let tau = t[i * $nv * $vl + j];
+                        if beta == 0.0 {
+                            ptr::write(gamma, tau);
+                        } else {
+                            ptr::write(gamma, tau + beta * ptr::read(gamma));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+//Kernels that keep columns of the Mr x Nr block of C in vector registers.
+//Each iteration of k loads one column of A and broadcasts each element of a row of B.
+//These are fastest when C is column major (rs_c == 1).
+macro_rules! col_ukernel {
+    ($feature:tt, $name:ident, $t:ty, $vl:expr, $mv:expr, $nr:expr,
+     $setzero:ident, $set1:ident, $loadu:ident, $storeu:ident, $add:ident, $mul:ident, $fmadd:ident) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn $name(k: isize, alpha: *mut $t, a: *mut $t, b: *mut $t, beta: *mut $t,
+                            c: *mut $t, rs_c: isize, cs_c: isize) {
+            let mut ab = [[$setzero(); $mv]; $nr];
+
+            let mut a_p = a as *const $t;
+            let mut b_p = b as *const $t;
+            for _ in 0..k {
+                let mut a_col = [$setzero(); $mv];
+                for i in 0..$mv {
+                    a_col[i] = $loadu(a_p.offset((i * $vl) as isize));
+                }
+                for j in 0..$nr {
+                    let beta_pj = $set1(ptr::read(b_p.offset(j as isize)));
+                    for i in 0..$mv {
+                        ab[j][i] = $fmadd(a_col[i], beta_pj, ab[j][i]);
+                    }
+                }
+                a_p = a_p.offset($mv * $vl);
+                b_p = b_p.offset($nr);
+            }
+
+            let alpha = ptr::read(alpha);
+            let beta = ptr::read(beta);
+            let alpha_v = $set1(alpha);
+            let beta_v = $set1(beta);
+            if rs_c == 1 {
+                for j in 0..$nr {
+                    for i in 0..$mv {
+                        let c_ij = c.offset(j as isize * cs_c + (i * $vl) as isize);
+                        let t = $mul(alpha_v, ab[j][i]);
+                        if beta == 0.0 {
+                            $storeu(c_ij, t);
+                        } else {
+                            $storeu(c_ij, $add(t, $mul(beta_v, $loadu(c_ij))));
+                        }
+                    }
+                }
+            } else {
+                let mut t = [0.0 as $t; $mv * $vl * $nr];
+                for j in 0..$nr {
+                    for i in 0..$mv {
+                        $storeu(t.as_mut_ptr().offset((j * $mv * $vl + i * $vl) as isize), $mul(alpha_v, ab[j][i]));
+                    }
+                }
+                for i in 0..($mv * $vl) {
+                    for j in 0..$nr {
+                        let gamma = c.offset(i as isize * rs_c + j as isize * cs_c);
+                        let tau = t[j * $mv * $vl + i];
+                        if beta == 0.0 {
+                            ptr::write(gamma, tau);
+                        } else {
+                            ptr::write(gamma, tau + beta * ptr::read(gamma));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
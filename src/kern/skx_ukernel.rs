@@ -0,0 +1,11 @@
+use core::arch::x86_64::*;
+use core::ptr;
+
+//Skylake-X micro-kernels written with AVX-512F intrinsics.
+//Kernels named Mr x Nr with a vectorized Nr prefer row-major C, the others prefer column-major C.
+
+col_ukernel!("avx512f", dgemm_16x14, f64, 8, 2, 14, _mm512_setzero_pd, _mm512_set1_pd, _mm512_loadu_pd, _mm512_storeu_pd, _mm512_add_pd, _mm512_mul_pd, _mm512_fmadd_pd);
+row_ukernel!("avx512f", dgemm_14x16, f64, 8, 14, 2, _mm512_setzero_pd, _mm512_set1_pd, _mm512_loadu_pd, _mm512_storeu_pd, _mm512_add_pd, _mm512_mul_pd, _mm512_fmadd_pd);
+
+col_ukernel!("avx512f", sgemm_32x14, f32, 16, 2, 14, _mm512_setzero_ps, _mm512_set1_ps, _mm512_loadu_ps, _mm512_storeu_ps, _mm512_add_ps, _mm512_mul_ps, _mm512_fmadd_ps);
+row_ukernel!("avx512f", sgemm_14x32, f32, 16, 14, 2, _mm512_setzero_ps, _mm512_set1_ps, _mm512_loadu_ps, _mm512_storeu_ps, _mm512_add_ps, _mm512_mul_ps, _mm512_fmadd_ps);
@@ -0,0 +1,13 @@
+use core::arch::x86_64::*;
+use core::ptr;
+
+//Sandy Bridge micro-kernels written with AVX intrinsics.
+//AVX has no fused multiply-add, so the kernels multiply and add separately.
+
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn mul_add_ps(a: __m256, b: __m256, c: __m256) -> __m256 {
+    _mm256_add_ps(_mm256_mul_ps(a, b), c)
+}
+
+col_ukernel!("avx", sgemm_8x8, f32, 8, 1, 8, _mm256_setzero_ps, _mm256_set1_ps, _mm256_loadu_ps, _mm256_storeu_ps, _mm256_add_ps, _mm256_mul_ps, mul_add_ps);
@@ -1,13 +1,7 @@
-#[allow(dead_code, non_snake_case, non_camel_case_types, non_upper_case_globals)]
-#[cfg(feature="blis")]
-pub mod blis_types 
-{
-    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
-}
-
 use matrix::{Scalar};
 use core::marker::{PhantomData};
-use typenum::{Unsigned};
+use typenum::{Unsigned,U1};
+use super::portable_ukernel;
 
 pub trait GenericUkernelWrapper<Mr: Unsigned, Nr: Unsigned, T: Scalar> {
     unsafe fn run( k: isize, alpha: *mut T, a: *mut T, b: *mut T, beta: *mut T, c: *mut T, rs_c: isize, cs_c: isize) -> (); 
@@ -22,88 +16,171 @@ impl<Mr: Unsigned, Nr: Unsigned, T: Scalar> UkernelWrapper<Mr, Nr, T> {
 }
 impl<Mr: Unsigned, Nr: Unsigned, T: Scalar> GenericUkernelWrapper<Mr, Nr, T> for UkernelWrapper<Mr, Nr, T> {
     #[inline(always)]
-    default unsafe fn run( _: isize, _: *mut T, _: *mut T, _: *mut T, _: *mut T, _: *mut T, _: isize, _: isize) {
-        panic!("Ukernel Wrapper not implemented for Mr {} Nr {} and this datatype!", Mr::to_usize(), Nr::to_usize());
+    default unsafe fn run( k: isize, alpha: *mut T, a: *mut T, b: *mut T, beta: *mut T, c: *mut T, rs_c: isize, cs_c: isize) {
+        //No hand-tuned kernel for this shape and datatype, so use the portable one.
+        portable_ukernel::run::<T, Mr, Nr, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
     }
 }
 
-#[cfg(feature="hsw")]
+#[cfg(target_arch="x86_64")]
 pub mod hsw
 {
-    extern crate libc;
-    use self::libc::{ c_double, int64_t };
-    use typenum::{U4,U6,U8,U12};
+    use typenum::{U1,U4,U6,U8,U12,U16,U24};
     use kern::ukernel_wrapper::{GenericUkernelWrapper,UkernelWrapper};
-    use kern::ukernel_wrapper::blis_types::{self,auxinfo_t,inc_t};
-
-    //Haswell ukernels
-    extern{
-        fn bli_dgemm_haswell_asm_6x8 (k: int64_t,
-            alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, 
-            c: *mut f64, rs_c: int64_t, cs_c: int64_t,
-            auxinfo: *mut auxinfo_t) -> (); 
-        fn bli_dgemm_haswell_asm_4x12 (k: int64_t,
-            alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, 
-            c: *mut f64, rs_c: int64_t, cs_c: int64_t,
-            auxinfo: *mut auxinfo_t) -> (); 
-        fn bli_dgemm_haswell_asm_12x4 (k: int64_t,
-            alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, 
-            c: *mut f64, rs_c: int64_t, cs_c: int64_t,
-            auxinfo: *mut auxinfo_t) -> (); 
-    }
+    use kern::{hsw_ukernel,portable_ukernel};
+    use kern::cpu_features::cpu_features;
 
+    //Haswell ukernels, used when the CPU supports AVX2 and FMA
     impl GenericUkernelWrapper<U4, U12, f64> for UkernelWrapper<U4, U12, f64> {
         #[inline(always)]
         unsafe fn run( k: isize, alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, c: *mut f64, rs_c: isize, cs_c: isize) {
-
-            let mut info = auxinfo_t{
-				schema_a: blis_types::pack_t_BLIS_PACKED_ROW_PANELS,
-				schema_b: blis_types::pack_t_BLIS_PACKED_COL_PANELS,
-				a_next: a as *mut ::std::os::raw::c_void,
-				b_next: b as *mut ::std::os::raw::c_void,
-				is_a: 1 as inc_t,
-				is_b: 1 as inc_t,
-                dt_on_output: blis_types::num_t_BLIS_DOUBLE,
-            };
-            
-            bli_dgemm_haswell_asm_4x12(k as int64_t, alpha as *mut c_double, a as *mut c_double, b as *mut c_double,
-                beta as *mut c_double, c as *mut c_double, rs_c as int64_t, cs_c as int64_t, &mut info as *mut auxinfo_t);
+            if cpu_features().hsw() {
+                hsw_ukernel::dgemm_4x12(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f64, U4, U12, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
         }
     }
     impl GenericUkernelWrapper<U6, U8, f64> for UkernelWrapper<U6, U8, f64> {
         #[inline(always)]
         unsafe fn run( k: isize, alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, c: *mut f64, rs_c: isize, cs_c: isize) {
-
-            let mut info = auxinfo_t{
-				schema_a: blis_types::pack_t_BLIS_PACKED_ROW_PANELS,
-				schema_b: blis_types::pack_t_BLIS_PACKED_COL_PANELS,
-				a_next: a as *mut ::std::os::raw::c_void,
-				b_next: b as *mut ::std::os::raw::c_void,
-				is_a: 1 as inc_t,
-				is_b: 1 as inc_t,
-                dt_on_output: blis_types::num_t_BLIS_DOUBLE,
-            };
-            
-            bli_dgemm_haswell_asm_6x8(k as int64_t, alpha as *mut c_double, a as *mut c_double, b as *mut c_double,
-                beta as *mut c_double, c as *mut c_double, rs_c as int64_t, cs_c as int64_t, &mut info as *mut auxinfo_t);
+            if cpu_features().hsw() {
+                hsw_ukernel::dgemm_6x8(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f64, U6, U8, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
         }
     }
     impl GenericUkernelWrapper<U12, U4, f64> for UkernelWrapper<U12, U4, f64> {
         #[inline(always)]
         unsafe fn run( k: isize, alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, c: *mut f64, rs_c: isize, cs_c: isize) {
+            if cpu_features().hsw() {
+                hsw_ukernel::dgemm_12x4(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f64, U12, U4, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+    impl GenericUkernelWrapper<U8, U6, f64> for UkernelWrapper<U8, U6, f64> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, c: *mut f64, rs_c: isize, cs_c: isize) {
+            if cpu_features().hsw() {
+                hsw_ukernel::dgemm_8x6(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f64, U8, U6, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+    impl GenericUkernelWrapper<U4, U24, f32> for UkernelWrapper<U4, U24, f32> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f32, a: *mut f32, b: *mut f32, beta: *mut f32, c: *mut f32, rs_c: isize, cs_c: isize) {
+            if cpu_features().hsw() {
+                hsw_ukernel::sgemm_4x24(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f32, U4, U24, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+    impl GenericUkernelWrapper<U6, U16, f32> for UkernelWrapper<U6, U16, f32> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f32, a: *mut f32, b: *mut f32, beta: *mut f32, c: *mut f32, rs_c: isize, cs_c: isize) {
+            if cpu_features().hsw() {
+                hsw_ukernel::sgemm_6x16(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f32, U6, U16, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+    impl GenericUkernelWrapper<U24, U4, f32> for UkernelWrapper<U24, U4, f32> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f32, a: *mut f32, b: *mut f32, beta: *mut f32, c: *mut f32, rs_c: isize, cs_c: isize) {
+            if cpu_features().hsw() {
+                hsw_ukernel::sgemm_24x4(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f32, U24, U4, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+    impl GenericUkernelWrapper<U16, U6, f32> for UkernelWrapper<U16, U6, f32> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f32, a: *mut f32, b: *mut f32, beta: *mut f32, c: *mut f32, rs_c: isize, cs_c: isize) {
+            if cpu_features().hsw() {
+                hsw_ukernel::sgemm_16x6(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f32, U16, U6, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+}
 
-            let mut info = auxinfo_t{
-				schema_a: blis_types::pack_t_BLIS_PACKED_ROW_PANELS,
-				schema_b: blis_types::pack_t_BLIS_PACKED_COL_PANELS,
-				a_next: a as *mut ::std::os::raw::c_void,
-				b_next: b as *mut ::std::os::raw::c_void,
-				is_a: 1 as inc_t,
-				is_b: 1 as inc_t,
-                dt_on_output: blis_types::num_t_BLIS_DOUBLE,
-            };
+#[cfg(target_arch="x86_64")]
+pub mod skx
+{
+    use typenum::{U1,U14,U16,U32};
+    use kern::ukernel_wrapper::{GenericUkernelWrapper,UkernelWrapper};
+    use kern::{skx_ukernel,portable_ukernel};
+    use kern::cpu_features::cpu_features;
 
-            bli_dgemm_haswell_asm_12x4(k as int64_t, alpha as *mut c_double, a as *mut c_double, b as *mut c_double,
-                beta as *mut c_double, c as *mut c_double, rs_c as int64_t, cs_c as int64_t, &mut info as *mut auxinfo_t);
+    //Skylake-X ukernels, used when the CPU supports AVX-512F
+    impl GenericUkernelWrapper<U16, U14, f64> for UkernelWrapper<U16, U14, f64> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, c: *mut f64, rs_c: isize, cs_c: isize) {
+            if cpu_features().avx512f {
+                skx_ukernel::dgemm_16x14(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f64, U16, U14, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+    impl GenericUkernelWrapper<U14, U16, f64> for UkernelWrapper<U14, U16, f64> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, c: *mut f64, rs_c: isize, cs_c: isize) {
+            if cpu_features().avx512f {
+                skx_ukernel::dgemm_14x16(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f64, U14, U16, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+    impl GenericUkernelWrapper<U32, U14, f32> for UkernelWrapper<U32, U14, f32> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f32, a: *mut f32, b: *mut f32, beta: *mut f32, c: *mut f32, rs_c: isize, cs_c: isize) {
+            if cpu_features().avx512f {
+                skx_ukernel::sgemm_32x14(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f32, U32, U14, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+    impl GenericUkernelWrapper<U14, U32, f32> for UkernelWrapper<U14, U32, f32> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f32, a: *mut f32, b: *mut f32, beta: *mut f32, c: *mut f32, rs_c: isize, cs_c: isize) {
+            if cpu_features().avx512f {
+                skx_ukernel::sgemm_14x32(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f32, U14, U32, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
+        }
+    }
+}
+
+#[cfg(target_arch="x86_64")]
+pub mod snb_avx
+{
+    use typenum::{U1,U8};
+    use kern::ukernel_wrapper::{GenericUkernelWrapper,UkernelWrapper};
+    use kern::{snb_ukernel,portable_ukernel};
+    use kern::cpu_features::cpu_features;
+
+    //Sandy Bridge single precision ukernels, used when the CPU supports AVX
+    impl GenericUkernelWrapper<U8, U8, f32> for UkernelWrapper<U8, U8, f32> {
+        #[inline(always)]
+        unsafe fn run( k: isize, alpha: *mut f32, a: *mut f32, b: *mut f32, beta: *mut f32, c: *mut f32, rs_c: isize, cs_c: isize) {
+            if cpu_features().avx {
+                snb_ukernel::sgemm_8x8(k, alpha, a, b, beta, c, rs_c, cs_c);
+            } else {
+                portable_ukernel::run::<f32, U8, U8, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
         }
     }
 }
@@ -113,9 +190,11 @@ pub mod snb
 {
     extern crate libc;
     use self::libc::{ c_double, int64_t };
-    use typenum::{U4,U8};
+    use typenum::{U1,U4,U8};
     use kern::ukernel_wrapper::{GenericUkernelWrapper,UkernelWrapper};
-    use kern::ukernel_wrapper::blis_types::{self,auxinfo_t,inc_t};
+    use kern::blis_types::{self,auxinfo_t,inc_t};
+    use kern::portable_ukernel;
+    use kern::cpu_features::cpu_features;
 
     //Haswell ukernels
     extern{
@@ -128,6 +207,9 @@ pub mod snb
     impl GenericUkernelWrapper<U8, U4, f64> for UkernelWrapper<U8, U4, f64> {
         #[inline(always)]
         unsafe fn run( k: isize, alpha: *mut f64, a: *mut f64, b: *mut f64, beta: *mut f64, c: *mut f64, rs_c: isize, cs_c: isize) {
+            if !cpu_features().avx {
+                return portable_ukernel::run::<f64, U8, U4, U1>(k, alpha, a, b, beta, c, rs_c, cs_c);
+            }
 
             let mut info = auxinfo_t{
 				schema_a: blis_types::pack_t_BLIS_PACKED_ROW_PANELS,
@@ -152,7 +234,7 @@ pub mod knl
     use self::libc::{ c_double, int64_t };
     use typenum::{U24,U8};
     use kern::ukernel_wrapper::{GenericUkernelWrapper,UkernelWrapper};
-    use kern::ukernel_wrapper::blis_types::{auxinfo_t,inc_t};
+    use kern::blis_types::{auxinfo_t,inc_t};
 
 	// KNL ukernels
 	extern{
@@ -1,6 +1,4 @@
 #![feature(specialization)]
-#![feature(allocator_api)]
-#![feature(asm)]
 
 #![cfg_attr(feature="clippy", feature(plugin))]
 #![cfg_attr(feature="clippy", plugin(clippy))]
@@ -18,3 +16,8 @@ pub mod composables;
 pub mod thread_comm;
 pub mod kern;
 pub mod util;
+mod error;
+mod gemm;
+
+pub use error::MommsError;
+pub use gemm::{gemm,Transpose,GemmScalar,StridedMat,StridedMatMut};
@@ -0,0 +1,160 @@
+use thread_comm::ThreadInfo;
+use matrix::{Scalar, Mat, RoCM, RoCMMut, Addressed, Matrix};
+use core::marker::PhantomData;
+
+//General strided views over slices owned by the caller.
+//They behave like Matrix<T> in a control tree, but never allocate or free their buffer.
+
+//Panics unless every element of an h x w matrix with the given strides lies inside a buffer of length len
+fn check_bounds(len: usize, h: usize, w: usize, row_stride: usize, column_stride: usize) {
+    if h == 0 || w == 0 { return; }
+    let last = (h-1).checked_mul(row_stride)
+        .and_then(|y| (w-1).checked_mul(column_stride).and_then(|x| y.checked_add(x)))
+        .expect("Matrix view strides overflow!");
+    assert!(last < len, "Matrix view of {}x{} with strides ({},{}) doesn't fit in a slice of length {}!",
+            h, w, row_stride, column_stride, len);
+}
+
+//Everything but get and set is forwarded to the wrapped Matrix
+macro_rules! forward_mat {
+    ($name: ident) => {
+        #[inline(always)]
+        fn iter_height(&self) -> usize { self.mat.iter_height() }
+        #[inline(always)]
+        fn iter_width(&self) -> usize { self.mat.iter_width() }
+        #[inline(always)]
+        fn logical_h_padding(&self) -> usize { self.mat.logical_h_padding() }
+        #[inline(always)]
+        fn logical_w_padding(&self) -> usize { self.mat.logical_w_padding() }
+
+        #[inline(always)]
+        unsafe fn make_alias(&self) -> Self {
+            $name{ mat: self.mat.make_alias(), _lifetime: PhantomData }
+        }
+        #[inline(always)]
+        unsafe fn send_alias(&mut self, thr: &ThreadInfo<T>) { self.mat.send_alias(thr) }
+
+        #[inline(always)]
+        fn set_scalar(&mut self, alpha: T) { self.mat.set_scalar(alpha) }
+        #[inline(always)]
+        fn get_scalar(&self) -> T { self.mat.get_scalar() }
+        #[inline(always)]
+        fn is_conjugated(&self) -> bool { self.mat.is_conjugated() }
+
+        fn push_y_view(&mut self, blksz: usize) -> usize { self.mat.push_y_view(blksz) }
+        fn push_x_view(&mut self, blksz: usize) -> usize { self.mat.push_x_view(blksz) }
+        #[inline(always)]
+        fn pop_y_view(&mut self) { self.mat.pop_y_view() }
+        #[inline(always)]
+        fn pop_x_view(&mut self) { self.mat.pop_x_view() }
+        fn slide_y_view_to(&mut self, y: usize, blksz: usize) { self.mat.slide_y_view_to(y, blksz) }
+        fn slide_x_view_to(&mut self, x: usize, blksz: usize) { self.mat.slide_x_view_to(x, blksz) }
+
+        fn push_y_split(&mut self, start: usize, end: usize) { self.mat.push_y_split(start, end) }
+        fn push_x_split(&mut self, start: usize, end: usize) { self.mat.push_x_split(start, end) }
+        #[inline(always)]
+        fn pop_y_split(&mut self) { self.mat.pop_y_split() }
+        #[inline(always)]
+        fn pop_x_split(&mut self) { self.mat.pop_x_split() }
+    }
+}
+
+macro_rules! forward_rocm {
+    () => {
+        #[inline(always)]
+        fn partition_is_rocm(&self) -> bool { self.mat.partition_is_rocm() }
+        #[inline(always)]
+        fn get_leaf_rs(&self) -> usize { self.mat.get_leaf_rs() }
+        #[inline(always)]
+        fn get_leaf_cs(&self) -> usize { self.mat.get_leaf_cs() }
+        #[inline(always)]
+        unsafe fn get_buffer(&self) -> *const T { self.mat.get_buffer() }
+        #[inline(always)]
+        fn get_block_rs(&self, lvl: usize, blksz: usize) -> usize { self.mat.get_block_rs(lvl, blksz) }
+        #[inline(always)]
+        fn get_block_cs(&self, lvl: usize, blksz: usize) -> usize { self.mat.get_block_cs(lvl, blksz) }
+        #[inline(always)]
+        fn full_leaves() -> bool { <Matrix<T> as RoCM<T>>::full_leaves() }
+        #[inline(always)]
+        unsafe fn establish_leaf(&mut self, y: usize, x: usize, height: usize, width: usize) {
+            self.mat.establish_leaf(y, x, height, width)
+        }
+    }
+}
+
+macro_rules! forward_inherent {
+    () => {
+        #[inline(always)] pub fn get_row_stride(&self) -> usize { self.mat.get_row_stride() }
+        #[inline(always)] pub fn get_column_stride(&self) -> usize { self.mat.get_column_stride() }
+        pub fn transpose(&mut self) { self.mat.transpose() }
+        pub fn conjugate(&mut self) { self.mat.conjugate() }
+    }
+}
+
+//Read-only view, for A and B
+pub struct MatrixRef<'a, T: 'a + Scalar> {
+    mat: Matrix<T>,
+    _lifetime: PhantomData<&'a [T]>,
+}
+impl<'a, T: Scalar> MatrixRef<'a, T> {
+    //Element (y,x) is data[y*row_stride + x*column_stride]
+    pub fn new(data: &'a [T], h: usize, w: usize, row_stride: usize, column_stride: usize) -> MatrixRef<'a, T> {
+        check_bounds(data.len(), h, w, row_stride, column_stride);
+        let mat = unsafe{ Matrix::from_raw_parts(data.as_ptr() as *mut T, h, w, row_stride, column_stride) };
+        MatrixRef{ mat: mat, _lifetime: PhantomData }
+    }
+    //The view as a Matrix<T>, so the packers specialized on Matrix<T> apply.
+    //Nothing may be written through it, and it must not be moved out of the view.
+    #[inline(always)] pub unsafe fn as_matrix(&mut self) -> &mut Matrix<T> { &mut self.mat }
+    forward_inherent!();
+}
+impl<'a, T: Scalar> Mat<T> for MatrixRef<'a, T> {
+    #[inline(always)]
+    fn get(&self, y: usize, x: usize) -> T { self.mat.get(y, x) }
+    fn set(&mut self, _y: usize, _x: usize, _alpha: T) {
+        panic!("Cannot write to a MatrixRef!");
+    }
+    forward_mat!(MatrixRef);
+}
+//Only RoCM, not RoCMMut, so the kernels and unpackers that write through the buffer reject a MatrixRef as C
+impl<'a, T: Scalar> RoCM<T> for MatrixRef<'a, T> {
+    forward_rocm!();
+}
+impl<'a, T: Scalar> Addressed<T> for MatrixRef<'a, T> {
+    fn address(&self, y: usize, x: usize) -> *const T { self.mat.address(y, x) }
+}
+//Threads sharing a MatrixRef share the &[T] it was made from
+unsafe impl<'a, T: Scalar + Sync> Send for MatrixRef<'a, T> {}
+
+//Mutable view, for C
+pub struct MatrixMut<'a, T: 'a + Scalar> {
+    mat: Matrix<T>,
+    _lifetime: PhantomData<&'a mut [T]>,
+}
+impl<'a, T: Scalar> MatrixMut<'a, T> {
+    //Element (y,x) is data[y*row_stride + x*column_stride]
+    pub fn new(data: &'a mut [T], h: usize, w: usize, row_stride: usize, column_stride: usize) -> MatrixMut<'a, T> {
+        check_bounds(data.len(), h, w, row_stride, column_stride);
+        let mat = unsafe{ Matrix::from_raw_parts(data.as_mut_ptr(), h, w, row_stride, column_stride) };
+        MatrixMut{ mat: mat, _lifetime: PhantomData }
+    }
+    forward_inherent!();
+}
+impl<'a, T: Scalar> Mat<T> for MatrixMut<'a, T> {
+    #[inline(always)]
+    fn get(&self, y: usize, x: usize) -> T { self.mat.get(y, x) }
+    #[inline(always)]
+    fn set(&mut self, y: usize, x: usize, alpha: T) { self.mat.set(y, x, alpha) }
+    forward_mat!(MatrixMut);
+}
+impl<'a, T: Scalar> RoCM<T> for MatrixMut<'a, T> {
+    forward_rocm!();
+}
+impl<'a, T: Scalar> RoCMMut<T> for MatrixMut<'a, T> {
+    #[inline(always)]
+    unsafe fn get_mut_buffer(&mut self) -> *mut T { self.mat.get_mut_buffer() }
+}
+impl<'a, T: Scalar> Addressed<T> for MatrixMut<'a, T> {
+    fn address(&self, y: usize, x: usize) -> *const T { self.mat.address(y, x) }
+}
+unsafe impl<'a, T: Scalar> Send for MatrixMut<'a, T> {}
@@ -1,9 +1,8 @@
 use thread_comm::ThreadInfo;
 use typenum::Unsigned;
-use std::alloc::{Alloc, Global};
-use matrix::{Scalar, Mat, ResizableBuffer, RoCM};
+use matrix::{Scalar, Mat, ResizableBuffer, RoCM, RoCMMut, Addressed};
 use super::view::{MatrixView};
-use util::capacity_to_aligned_layout;
+use util::{alloc_aligned,dealloc_aligned,realloc_aligned};
 
 use core::marker::PhantomData;
 use core::{mem, ptr};
@@ -39,19 +38,17 @@ impl<T: Scalar, PW: Unsigned> ColumnPanelMatrix<T,PW> {
         y_views.push(MatrixView{ offset: 0, padding: 0, iter_size: h }); 
         x_views.push(MatrixView{ offset: 0, padding: 0, iter_size: w }); 
 
-        let layout = capacity_to_aligned_layout::<T>(capacity);
-        let buf = unsafe { Global.alloc(layout).expect("Could not allocate buffer for matrix!") };
+        let buf = unsafe { alloc_aligned::<T>(capacity) };
 
         ColumnPanelMatrix{ alpha: T::one(),
                            y_views: y_views, x_views: x_views,
                            panel_stride: panel_w*h,
-                           buffer: unsafe{buf.cast::<T>().as_mut()},
+                           buffer: buf,
                            capacity: capacity,
                            is_alias: false,
                            _pwt: PhantomData }
     }
 
-
     #[inline(always)]
     pub fn get_panel_stride(&self) -> usize { self.panel_stride }
 
@@ -117,6 +114,8 @@ impl<T: Scalar, PW: Unsigned> Mat<T> for ColumnPanelMatrix<T, PW> {
     fn get_scalar(&self) -> T {
         self.alpha
     }
+    fn block_multiples() -> (usize, usize) { (1, PW::to_usize()) }
+    fn leaf_strides() -> Option<(usize, usize)> { Some((PW::to_usize(), 1)) }
 
     fn push_y_split(&mut self, start: usize, end: usize) {
         let zoomed_view = {
@@ -240,9 +239,8 @@ impl<T: Scalar, PW: Unsigned> Mat<T> for ColumnPanelMatrix<T, PW> {
 impl<T:Scalar, PW: Unsigned> Drop for ColumnPanelMatrix<T, PW> {
     fn drop(&mut self) {
         if !self.is_alias {
-            let layout = capacity_to_aligned_layout::<T>(self.capacity);
             unsafe {
-                Global.dealloc(std::ptr::NonNull::new_unchecked(self.buffer as *mut u8), layout);
+                dealloc_aligned(self.buffer, self.capacity);
             }
         }
     }
@@ -273,13 +271,14 @@ impl<T:Scalar, PW: Unsigned> ResizableBuffer<T> for ColumnPanelMatrix<T, PW> {
         let req_capacity = req_capacity;
         if req_capacity > self.capacity {
             unsafe {
-                let old_layout = capacity_to_aligned_layout::<T>(self.capacity);
-                self.buffer = Global.realloc(std::ptr::NonNull::new_unchecked(self.buffer as *mut u8), old_layout, req_capacity)
-                    .expect("Could not allocate buffer for matrix!").cast::<T>().as_mut();
+                self.buffer = realloc_aligned(self.buffer, self.capacity, req_capacity);
                 self.capacity = req_capacity;
             }
         }
     }
+    fn describe_layout() -> String {
+        format!("ColumnPanelMatrix(panel width {})", PW::to_usize())
+    }
     #[inline(always)]
     fn resize_to(&mut self, other: &Mat<T>, _: AlgorithmStep, _: AlgorithmStep, _: &[AlgorithmStep]) {
         debug_assert_eq!(self.y_views.len(), 1, "Can't resize a submatrix!");
@@ -319,15 +318,6 @@ impl<T: Scalar, PW: Unsigned> RoCM<T> for ColumnPanelMatrix<T, PW> {
         self.buffer.offset((x_view.offset*self.panel_stride + y_view.offset*panel_w) as isize)
     }
 
-    #[inline(always)]
-    unsafe fn get_mut_buffer(&mut self) -> *mut T {
-        let panel_w = PW::to_usize();
-        let y_view = self.y_views.last().unwrap();
-        let x_view = self.x_views.last().unwrap();
-
-        self.buffer.offset((x_view.offset*self.panel_stride + y_view.offset*panel_w) as isize)
-    }
-
 	#[inline(always)]
     fn get_block_rs(&self, _: usize, blksz: usize) -> usize {
 		blksz * PW::to_usize()
@@ -350,3 +340,22 @@ impl<T: Scalar, PW: Unsigned> RoCM<T> for ColumnPanelMatrix<T, PW> {
     #[inline(always)]
     unsafe fn establish_leaf(&mut self, _y: usize, _x: usize, _height: usize, _width: usize) { }
 }
+impl<T: Scalar, PW: Unsigned> RoCMMut<T> for ColumnPanelMatrix<T, PW> {
+    #[inline(always)]
+    unsafe fn get_mut_buffer(&mut self) -> *mut T {
+        let panel_w = PW::to_usize();
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        self.buffer.offset((x_view.offset*self.panel_stride + y_view.offset*panel_w) as isize)
+    }
+}
+impl<T: Scalar, PW: Unsigned> Addressed<T> for ColumnPanelMatrix<T, PW> {
+    fn address(&self, y: usize, x: usize) -> *const T {
+        let panel_w = PW::to_usize();
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+        let elem_index = (x / panel_w + x_view.offset) * self.panel_stride + (y + y_view.offset) * panel_w + x % panel_w;
+        self.buffer.wrapping_offset(elem_index as isize)
+    }
+}
@@ -0,0 +1,109 @@
+extern crate rand;
+
+use core::fmt::{self, Display, LowerExp};
+use core::ops::{Add, Mul, Sub, Div, AddAssign, MulAssign, SubAssign, DivAssign};
+use super::matrix::{Scalar, RealScalar, ScalarConstants};
+
+//Complex number stored as a (re, im) pair, the same layout used by BLAS for ZGEMM and CGEMM.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Complex<R: RealScalar> {
+    pub re: R,
+    pub im: R,
+}
+impl<R: RealScalar> Complex<R> {
+    pub fn new(re: R, im: R) -> Complex<R> {
+        Complex{ re: re, im: im }
+    }
+}
+
+#[allow(non_camel_case_types)]
+pub type c32 = Complex<f32>;
+#[allow(non_camel_case_types)]
+pub type c64 = Complex<f64>;
+
+impl<R: RealScalar> Add for Complex<R> {
+    type Output = Complex<R>;
+    #[inline(always)]
+    fn add(self, other: Complex<R>) -> Complex<R> {
+        Complex{ re: self.re + other.re, im: self.im + other.im }
+    }
+}
+impl<R: RealScalar> Sub for Complex<R> {
+    type Output = Complex<R>;
+    #[inline(always)]
+    fn sub(self, other: Complex<R>) -> Complex<R> {
+        Complex{ re: self.re - other.re, im: self.im - other.im }
+    }
+}
+impl<R: RealScalar> Mul for Complex<R> {
+    type Output = Complex<R>;
+    #[inline(always)]
+    fn mul(self, other: Complex<R>) -> Complex<R> {
+        Complex{ re: self.re * other.re - self.im * other.im,
+                 im: self.re * other.im + self.im * other.re }
+    }
+}
+impl<R: RealScalar> Div for Complex<R> {
+    type Output = Complex<R>;
+    #[inline(always)]
+    fn div(self, other: Complex<R>) -> Complex<R> {
+        let denom = other.norm_sqr();
+        Complex{ re: (self.re * other.re + self.im * other.im) / denom,
+                 im: (self.im * other.re - self.re * other.im) / denom }
+    }
+}
+impl<R: RealScalar> AddAssign for Complex<R> {
+    #[inline(always)]
+    fn add_assign(&mut self, other: Complex<R>) { *self = *self + other; }
+}
+impl<R: RealScalar> SubAssign for Complex<R> {
+    #[inline(always)]
+    fn sub_assign(&mut self, other: Complex<R>) { *self = *self - other; }
+}
+impl<R: RealScalar> MulAssign for Complex<R> {
+    #[inline(always)]
+    fn mul_assign(&mut self, other: Complex<R>) { *self = *self * other; }
+}
+impl<R: RealScalar> DivAssign for Complex<R> {
+    #[inline(always)]
+    fn div_assign(&mut self, other: Complex<R>) { *self = *self / other; }
+}
+
+//Formatting options such as precision apply to both parts
+impl<R: RealScalar> Display for Complex<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.re, f)?;
+        if self.im >= R::zero() { write!(f, "+")?; }
+        Display::fmt(&self.im, f)?;
+        write!(f, "i")
+    }
+}
+impl<R: RealScalar + LowerExp> LowerExp for Complex<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        LowerExp::fmt(&self.re, f)?;
+        if self.im >= R::zero() { write!(f, "+")?; }
+        LowerExp::fmt(&self.im, f)?;
+        write!(f, "i")
+    }
+}
+
+impl<R: RealScalar> rand::Rand for Complex<R> {
+    fn rand<G: rand::Rng>(rng: &mut G) -> Complex<R> {
+        Complex{ re: R::rand(rng), im: R::rand(rng) }
+    }
+}
+
+impl<R: RealScalar> ScalarConstants for Complex<R> {
+    #[inline(always)]
+    fn one() -> Self { Complex{ re: R::one(), im: R::zero() } }
+    #[inline(always)]
+    fn zero() -> Self { Complex{ re: R::zero(), im: R::zero() } }
+}
+impl<R: RealScalar> Scalar for Complex<R> {
+    type Real = R;
+    #[inline(always)]
+    fn conj(self) -> Self { Complex{ re: self.re, im: R::zero() - self.im } }
+    #[inline(always)]
+    fn norm_sqr(self) -> R { self.re * self.re + self.im * self.im }
+}
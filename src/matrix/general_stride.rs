@@ -1,8 +1,8 @@
 use thread_comm::ThreadInfo;
-use std::alloc::{Alloc, Global};
-use matrix::{Scalar, Mat, RoCM};
+use matrix::{Scalar, Mat, RoCM, RoCMMut, Addressed};
 use super::view::{MatrixView};
 use core::{self, ptr};
+use error::MommsError;
 
 pub struct Matrix<T: Scalar> {
     //Matrix scalar
@@ -18,25 +18,55 @@ pub struct Matrix<T: Scalar> {
     buffer: *mut T,
     capacity: usize,
     is_alias: bool,
+
+    //Elements are the conjugates of those in the buffer
+    conj: bool,
 }
 impl<T: Scalar> Matrix<T> {
     pub fn new(h: usize, w: usize) -> Matrix<T> {
+        match Matrix::try_new(h, w) {
+            Ok(mat) => mat,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn try_new(h: usize, w: usize) -> Result<Matrix<T>, MommsError> {
         assert_ne!(core::mem::size_of::<T>(), 0, "Matrix can't handle ZSTs");
-        let layout = ::util::capacity_to_aligned_layout::<T>(h * w);
-        let buf = unsafe { Global.alloc(layout).expect("Could not allocate buffer for matrix!") };
+        let alloc_err = MommsError::AllocationFailed{ capacity: h.saturating_mul(w), elem_size: core::mem::size_of::<T>() };
+        let capacity = h.checked_mul(w).ok_or(alloc_err)?;
+        let buf = unsafe { ::util::try_alloc_aligned::<T>(capacity)? };
 
         let mut y_views : Vec<MatrixView> = Vec::with_capacity(16);
         let mut x_views : Vec<MatrixView> = Vec::with_capacity(16);
         y_views.push(MatrixView{ offset: 0, padding: 0, iter_size: h });
         x_views.push(MatrixView{ offset: 0, padding: 0, iter_size: w });
 
-        Matrix{ alpha: T::one(),
+        Ok(Matrix{ alpha: T::one(),
                 y_views: y_views,
                 x_views: x_views,
                 row_stride: 1, column_stride: h,
-                buffer: unsafe{buf.cast::<T>().as_mut()},
-                capacity: h * w,
-                is_alias: false }
+                buffer: buf,
+                capacity: capacity,
+                is_alias: false,
+                conj: false })
+    }
+
+    //Wraps a buffer the matrix doesn't own, so it is never freed.
+    //The buffer must hold every element (y,x) at y*row_stride + x*column_stride for as long as the matrix lives.
+    pub unsafe fn from_raw_parts(buffer: *mut T, h: usize, w: usize, row_stride: usize, column_stride: usize) -> Matrix<T> {
+        let mut y_views : Vec<MatrixView> = Vec::with_capacity(16);
+        let mut x_views : Vec<MatrixView> = Vec::with_capacity(16);
+        y_views.push(MatrixView{ offset: 0, padding: 0, iter_size: h });
+        x_views.push(MatrixView{ offset: 0, padding: 0, iter_size: w });
+
+        Matrix{ alpha: T::one(),
+                y_views: y_views,
+                x_views: x_views,
+                row_stride: row_stride, column_stride: column_stride,
+                buffer: buffer,
+                capacity: 0,
+                is_alias: true,
+                conj: false }
     }
 
     #[inline(always)] pub fn get_row_stride(&self) -> usize { self.row_stride }
@@ -51,6 +81,11 @@ impl<T: Scalar> Matrix<T> {
         
         core::mem::swap(&mut self.column_stride, &mut self.row_stride);
     }
+
+    //Logically conjugate every element. Combine with transpose for a conjugate transpose.
+    pub fn conjugate(&mut self) {
+        self.conj = !self.conj;
+    }
 }
 impl<T: Scalar> Mat<T> for Matrix<T> {
     #[inline(always)]
@@ -60,9 +95,10 @@ impl<T: Scalar> Mat<T> for Matrix<T> {
 
         let y_coord = (y + y_view.offset) * self.row_stride;
         let x_coord = (x + x_view.offset) * self.column_stride;
-        unsafe{
+        let alpha = unsafe{
             ptr::read(self.buffer.offset((y_coord + x_coord) as isize))
-        }
+        };
+        if self.conj { alpha.conj() } else { alpha }
     }
     #[inline(always)]
     fn set(&mut self, y: usize, x: usize, alpha: T) {
@@ -71,6 +107,7 @@ impl<T: Scalar> Mat<T> for Matrix<T> {
 
         let y_coord = (y + y_view.offset) * self.row_stride;
         let x_coord = (x + x_view.offset) * self.column_stride;
+        let alpha = if self.conj { alpha.conj() } else { alpha };
         unsafe{
             ptr::write(self.buffer.offset((y_coord + x_coord) as isize), alpha);
         }
@@ -100,6 +137,10 @@ impl<T: Scalar> Mat<T> for Matrix<T> {
     fn get_scalar(&self) -> T {
         self.alpha
     }
+    #[inline(always)]
+    fn is_conjugated(&self) -> bool {
+        self.conj
+    }
 
     fn push_y_split(&mut self, start: usize, end: usize) {
         let zoomed_view = {
@@ -207,7 +248,8 @@ impl<T: Scalar> Mat<T> for Matrix<T> {
                 row_stride: self.row_stride, column_stride: self.column_stride,
                 buffer: self.buffer,
                 capacity: self.capacity,
-                is_alias: true }
+                is_alias: true,
+                conj: self.conj }
     }
 
     #[inline(always)]
@@ -221,8 +263,7 @@ impl<T:Scalar> Drop for Matrix<T> {
     fn drop(&mut self) {
         unsafe {
             if !self.is_alias {
-                let layout = ::util::capacity_to_aligned_layout::<T>(self.capacity);
-                Global.dealloc(std::ptr::NonNull::new_unchecked(self.buffer as *mut u8), layout);
+                ::util::dealloc_aligned(self.buffer, self.capacity);
             }
         }
     }
@@ -248,13 +289,6 @@ impl<T: Scalar> RoCM<T> for Matrix<T> {
         self.buffer.offset((y_view.offset*self.row_stride + x_view.offset*self.column_stride) as isize)
     }   
 
-    #[inline(always)]
-    unsafe fn get_mut_buffer(&mut self) -> *mut T {
-        let y_view = self.y_views.last().unwrap();
-        let x_view = self.x_views.last().unwrap();
-
-        self.buffer.offset((y_view.offset*self.row_stride + x_view.offset*self.column_stride) as isize)
-    }
     #[inline(always)]
     fn get_block_rs(&self, _: usize, blksz: usize) -> usize {
         blksz * self.row_stride
@@ -271,3 +305,19 @@ impl<T: Scalar> RoCM<T> for Matrix<T> {
     #[inline(always)]
     unsafe fn establish_leaf(&mut self, _x: usize, _y: usize, _height: usize, _width: usize) { }
 }
+impl<T: Scalar> RoCMMut<T> for Matrix<T> {
+    #[inline(always)]
+    unsafe fn get_mut_buffer(&mut self) -> *mut T {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        self.buffer.offset((y_view.offset*self.row_stride + x_view.offset*self.column_stride) as isize)
+    }
+}
+impl<T: Scalar> Addressed<T> for Matrix<T> {
+    fn address(&self, y: usize, x: usize) -> *const T {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+        self.buffer.wrapping_offset(((y + y_view.offset) * self.row_stride + (x + x_view.offset) * self.column_stride) as isize)
+    }
+}
@@ -0,0 +1,325 @@
+use thread_comm::ThreadInfo;
+use matrix::{Mat};
+use super::view::{MatrixView};
+use util::{alloc_aligned,dealloc_aligned};
+use core::{self, ptr};
+
+//16-bit floating point storage.
+//Arithmetic is done in f32: packing into RowPanelMatrix<f32> and ColumnPanelMatrix<f32> widens,
+//so any f32 kernel can consume half precision A and B.
+pub trait HalfElem where
+    Self: Copy,
+    Self: Send,
+    Self: 'static,
+{
+    fn to_f32(self) -> f32;
+    //Rounds to nearest, ties to even
+    fn from_f32(alpha: f32) -> Self;
+}
+
+//bfloat16: the upper half of an f32
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct bf16(u16);
+impl bf16 {
+    pub fn from_bits(bits: u16) -> bf16 { bf16(bits) }
+    pub fn to_bits(self) -> u16 { self.0 }
+}
+impl HalfElem for bf16 {
+    #[inline(always)]
+    fn to_f32(self) -> f32 {
+        f32::from_bits((self.0 as u32) << 16)
+    }
+    #[inline(always)]
+    fn from_f32(alpha: f32) -> bf16 {
+        let bits = alpha.to_bits();
+        if alpha.is_nan() {
+            //Keep NaNs quiet rather than letting truncation turn them into infinities
+            return bf16((bits >> 16) as u16 | 0x40);
+        }
+        let round = 0x7fff + ((bits >> 16) & 1);
+        bf16((bits.wrapping_add(round) >> 16) as u16)
+    }
+}
+
+//IEEE 754 binary16
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct f16(u16);
+impl f16 {
+    pub fn from_bits(bits: u16) -> f16 { f16(bits) }
+    pub fn to_bits(self) -> u16 { self.0 }
+}
+impl HalfElem for f16 {
+    #[inline(always)]
+    fn to_f32(self) -> f32 {
+        let sign = ((self.0 & 0x8000) as u32) << 16;
+        let exp = ((self.0 >> 10) & 0x1f) as u32;
+        let man = (self.0 & 0x3ff) as u32;
+        if exp == 0 {
+            //Zero or subnormal, man * 2^-24
+            let alpha = man as f32 / 16777216.0;
+            if sign != 0 { -alpha } else { alpha }
+        } else if exp == 0x1f {
+            f32::from_bits(sign | 0x7f80_0000 | (man << 13))
+        } else {
+            f32::from_bits(sign | ((exp + 112) << 23) | (man << 13))
+        }
+    }
+    fn from_f32(alpha: f32) -> f16 {
+        let bits = alpha.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exp = ((bits >> 23) & 0xff) as i32;
+        let man = bits & 0x7f_ffff;
+
+        if exp == 0xff {
+            return f16(sign | 0x7c00 | if man != 0 { 0x200 } else { 0 });
+        }
+        let e = exp - 127 + 15;
+        if e >= 0x1f {
+            return f16(sign | 0x7c00);
+        }
+        if e <= 0 {
+            //Subnormal result. Below half the smallest subnormal everything rounds to zero.
+            if e < -10 {
+                return f16(sign);
+            }
+            let man = man | 0x80_0000;
+            let shift = (14 - e) as u32;
+            let half_man = man >> shift;
+            let rem = man & ((1 << shift) - 1);
+            let halfway = 1 << (shift - 1);
+            let rounded = if rem > halfway || (rem == halfway && half_man & 1 == 1) { half_man + 1 } else { half_man };
+            return f16(sign | rounded as u16);
+        }
+        //Rounding may carry into the exponent, which is still correctly rounded (up to infinity)
+        let mut h = ((e as u32) << 10) | (man >> 13);
+        let rem = man & 0x1fff;
+        if rem > 0x1000 || (rem == 0x1000 && h & 1 == 1) {
+            h += 1;
+        }
+        f16(sign | h as u16)
+    }
+}
+
+//General strided matrix of 16-bit floats, logically a matrix of f32
+pub struct HalfMatrix<H: HalfElem> {
+    alpha: f32,
+
+    y_views: Vec<MatrixView>,
+    x_views: Vec<MatrixView>,
+
+    row_stride: usize,
+    column_stride: usize,
+    buffer: *mut H,
+    capacity: usize,
+    is_alias: bool,
+}
+impl<H: HalfElem> HalfMatrix<H> {
+    pub fn new(h: usize, w: usize) -> HalfMatrix<H> {
+        let buf = unsafe { alloc_aligned::<H>(h * w) };
+
+        let mut y_views : Vec<MatrixView> = Vec::with_capacity(16);
+        let mut x_views : Vec<MatrixView> = Vec::with_capacity(16);
+        y_views.push(MatrixView{ offset: 0, padding: 0, iter_size: h });
+        x_views.push(MatrixView{ offset: 0, padding: 0, iter_size: w });
+
+        HalfMatrix{ alpha: 1.0,
+                 y_views: y_views, x_views: x_views,
+                 row_stride: 1, column_stride: h,
+                 buffer: buf,
+                 capacity: h * w,
+                 is_alias: false }
+    }
+
+    #[inline(always)] pub fn get_row_stride(&self) -> usize { self.row_stride }
+    #[inline(always)] pub fn get_column_stride(&self) -> usize { self.column_stride }
+
+    pub fn transpose(&mut self) {
+        if self.y_views.len() != 1 || self.x_views.len() != 1 { panic!("can't transpose a submatrix!") };
+        let xview = self.x_views.pop().unwrap();
+        let yview = self.y_views.pop().unwrap();
+        self.y_views.push(xview);
+        self.x_views.push(yview);
+
+        core::mem::swap(&mut self.column_stride, &mut self.row_stride);
+    }
+
+    #[inline(always)]
+    pub unsafe fn get_buffer(&self) -> *const H {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        self.buffer.offset((y_view.offset*self.row_stride + x_view.offset*self.column_stride) as isize)
+    }
+}
+impl<H: HalfElem> Mat<f32> for HalfMatrix<H> {
+    #[inline(always)]
+    fn get(&self, y: usize, x: usize) -> f32 {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        let y_coord = (y + y_view.offset) * self.row_stride;
+        let x_coord = (x + x_view.offset) * self.column_stride;
+        unsafe{
+            ptr::read(self.buffer.offset((y_coord + x_coord) as isize)).to_f32()
+        }
+    }
+    #[inline(always)]
+    fn set(&mut self, y: usize, x: usize, alpha: f32) {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        let y_coord = (y + y_view.offset) * self.row_stride;
+        let x_coord = (x + x_view.offset) * self.column_stride;
+        unsafe{
+            ptr::write(self.buffer.offset((y_coord + x_coord) as isize), H::from_f32(alpha));
+        }
+    }
+    #[inline(always)]
+    fn iter_height(&self) -> usize {
+        self.y_views.last().unwrap().iter_size
+    }
+    #[inline(always)]
+    fn iter_width(&self) -> usize {
+        self.x_views.last().unwrap().iter_size
+    }
+    #[inline(always)]
+    fn logical_h_padding(&self) -> usize {
+        self.y_views.last().unwrap().padding
+    }
+    #[inline(always)]
+    fn logical_w_padding(&self) -> usize {
+        self.x_views.last().unwrap().padding
+    }
+
+    #[inline(always)]
+    fn set_scalar(&mut self, alpha: f32) {
+        self.alpha = alpha;
+    }
+    #[inline(always)]
+    fn get_scalar(&self) -> f32 {
+        self.alpha
+    }
+
+
+    fn push_y_split(&mut self, start: usize, end: usize) {
+        let zoomed_view = {
+            let uz_view = self.y_views.last().unwrap();
+            let new_padding = if end <= self.height() { 0 } else { end - self.height() };
+            let new_offset = uz_view.offset + start;
+            MatrixView{ offset: new_offset, padding: new_padding, iter_size: end-start }
+        };
+        self.y_views.push(zoomed_view);
+    }
+    fn push_x_split(&mut self, start: usize, end: usize) {
+        let zoomed_view = {
+            let uz_view = self.x_views.last().unwrap();
+            let new_padding = if end <= self.width() { 0 } else { end - self.width() };
+            let new_offset = uz_view.offset + start;
+            MatrixView{ offset: new_offset, padding: new_padding, iter_size: end-start }
+        };
+        self.x_views.push(zoomed_view);
+    }
+    #[inline(always)]
+    fn pop_y_split(&mut self) {
+        debug_assert!(self.y_views.len() >= 2);
+        self.y_views.pop();
+    }
+    #[inline(always)]
+    fn pop_x_split(&mut self) {
+        debug_assert!(self.x_views.len() >= 2);
+        self.x_views.pop();
+    }
+
+    fn push_y_view(&mut self, blksz: usize) -> usize {
+        let (zoomed_view, uz_iter_size) = {
+            let uz_view = self.y_views.last().unwrap();
+            let (z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(0, blksz);
+            (MatrixView{ offset: uz_view.offset, padding: z_padding, iter_size: z_iter_size }, uz_view.iter_size)
+        };
+        self.y_views.push(zoomed_view);
+        uz_iter_size
+    }
+    fn push_x_view(&mut self, blksz: usize) -> usize {
+        let (zoomed_view, uz_iter_size) = {
+            let uz_view = self.x_views.last().unwrap();
+            let (z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(0, blksz);
+            (MatrixView{ offset: uz_view.offset, padding: z_padding, iter_size: z_iter_size }, uz_view.iter_size)
+        };
+        self.x_views.push(zoomed_view);
+        uz_iter_size
+    }
+    #[inline(always)]
+    fn pop_y_view(&mut self) {
+        debug_assert!(self.y_views.len() >= 2);
+        self.y_views.pop();
+    }
+    #[inline(always)]
+    fn pop_x_view(&mut self) {
+        debug_assert!(self.x_views.len() >= 2);
+        self.x_views.pop();
+    }
+
+    fn slide_y_view_to(&mut self, y: usize, blksz: usize) {
+        let view_len = self.y_views.len();
+        debug_assert!(view_len >= 2);
+
+        let uz_view = self.y_views[view_len-2];
+        let(z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(y, blksz);
+
+        let z_view = self.y_views.last_mut().unwrap();
+        z_view.iter_size = z_iter_size;
+        z_view.padding = z_padding;
+        z_view.offset = uz_view.offset + y;
+    }
+    fn slide_x_view_to(&mut self, x: usize, blksz: usize) {
+        let view_len = self.x_views.len();
+        debug_assert!(view_len >= 2);
+
+        let uz_view = self.x_views[view_len-2];
+        let(z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(x, blksz);
+
+        let z_view = self.x_views.last_mut().unwrap();
+        z_view.iter_size = z_iter_size;
+        z_view.padding = z_padding;
+        z_view.offset = uz_view.offset + x;
+    }
+
+    #[inline(always)]
+    unsafe fn make_alias(&self) -> Self {
+        let x_view = self.x_views.last().unwrap();
+        let y_view = self.y_views.last().unwrap();
+
+        let mut x_views_alias : Vec<MatrixView> = Vec::with_capacity(16);
+        let mut y_views_alias : Vec<MatrixView> = Vec::with_capacity(16);
+        x_views_alias.push(*x_view);
+        y_views_alias.push(*y_view);
+
+        HalfMatrix{ alpha: self.alpha,
+                 x_views: x_views_alias, y_views: y_views_alias,
+                 row_stride: self.row_stride, column_stride: self.column_stride,
+                 buffer: self.buffer,
+                 capacity: self.capacity,
+                 is_alias: true }
+    }
+
+    #[inline(always)]
+    unsafe fn send_alias(&mut self, thr: &ThreadInfo<f32>) {
+        let buf = thr.broadcast(self.buffer as *mut f32);
+        self.is_alias = true;
+        self.buffer = buf as *mut H;
+    }
+}
+impl<H: HalfElem> Drop for HalfMatrix<H> {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.is_alias {
+                dealloc_aligned(self.buffer, self.capacity);
+            }
+        }
+    }
+}
+unsafe impl<H: HalfElem> Send for HalfMatrix<H> {}
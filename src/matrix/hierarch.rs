@@ -1,10 +1,10 @@
 use thread_comm::ThreadInfo;
 use typenum::Unsigned;
-use std::alloc::{Alloc, Global};
-use matrix::{Scalar, Mat, ResizableBuffer, RoCM};
+use matrix::{Scalar, Mat, ResizableBuffer, RoCM, RoCMMut, Addressed};
 use super::view::{MatrixView};
 use composables::AlgorithmStep;
-use util::capacity_to_aligned_layout;
+use util::{try_alloc_aligned,dealloc_aligned,realloc_aligned};
+use error::MommsError;
 use core::{self, ptr, marker::PhantomData};
 
 #[derive(Clone)]
@@ -90,6 +90,14 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> Hierar
     pub fn new(h: usize, w: usize, 
                 hier: &[AlgorithmStep], y_step: AlgorithmStep, x_step: AlgorithmStep) 
         -> Hierarch<T,LH,LW,LRS,LCS> {
+        match Hierarch::try_new(h, w, hier, y_step, x_step) {
+            Ok(mat) => mat,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    pub fn try_new(h: usize, w: usize, 
+                hier: &[AlgorithmStep], y_step: AlgorithmStep, x_step: AlgorithmStep) 
+        -> Result<Hierarch<T,LH,LW,LRS,LCS>, MommsError> {
         assert_ne!(core::mem::size_of::<T>(), 0, "Matrix can't handle ZSTs");
 
         //Setup Views stack
@@ -108,8 +116,10 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> Hierar
             None => LW::to_usize(),
         };
     
-        let n_blocks_y = if h == 0 {1} else {(h-1) / y_tlds + 1};
-        let n_blocks_x = if w == 0 {1} else {(w-1) / x_tlds + 1};
+        //An empty matrix holds no blocks, so its first resize as a packing buffer
+        //always allocates and shares the new buffer among threads
+        let n_blocks_y = if h == 0 || w == 0 {0} else {(h-1) / y_tlds + 1};
+        let n_blocks_x = if h == 0 || w == 0 {0} else {(w-1) / x_tlds + 1};
         let h_padded = n_blocks_y * y_tlds;
         let w_padded = n_blocks_x * x_tlds;
 
@@ -120,23 +130,22 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> Hierar
         //Figure out buffer and capacity
         let (buf, capacity) = {
             //Figure out the number of top-level blocks in each direction
-            let capacity = n_blocks_y * y_tlds * n_blocks_x * x_tlds;
-            let layout = capacity_to_aligned_layout::<T>(capacity);
-            let buf = unsafe { Global.alloc(layout).expect("Could not allocate buffer for matrix!") };
+            let capacity = h_padded.saturating_mul(w_padded);
+            let buf = unsafe { try_alloc_aligned::<T>(capacity)? };
             (buf, capacity)
         };
 
       
         //Return
-        Hierarch{ alpha: T::one(),
-                  y_views: y_views, x_views: x_views,
-                  y_hierarchy: y_hierarchy, x_hierarchy: x_hierarchy,
-                  yh_index: yh_index, xh_index: xh_index,  
-                  buffer: unsafe{buf.cast::<T>().as_mut()},
-                  capacity: capacity,
-                  is_alias: false,
-                  _lht: PhantomData, _lwt: PhantomData,
-                  _lrst: PhantomData, _lcst: PhantomData }
+        Ok(Hierarch{ alpha: T::one(),
+                     y_views: y_views, x_views: x_views,
+                     y_hierarchy: y_hierarchy, x_hierarchy: x_hierarchy,
+                     yh_index: yh_index, xh_index: xh_index,  
+                     buffer: buf,
+                     capacity: capacity,
+                     is_alias: false,
+                     _lht: PhantomData, _lwt: PhantomData,
+                     _lrst: PhantomData, _lcst: PhantomData })
     }
     
     #[inline(always)]
@@ -225,6 +234,8 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> Mat<T>
     fn get_scalar(&self) -> T {
         self.alpha
     }
+    fn block_multiples() -> (usize, usize) { (LH::to_usize(), LW::to_usize()) }
+    fn leaf_strides() -> Option<(usize, usize)> { Some((LRS::to_usize(), LCS::to_usize())) }
 
     fn push_y_split(&mut self, start: usize, end: usize) {
         let iota = self.y_hierarchy[self.yh_index].blksz;
@@ -384,8 +395,7 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> Drop f
     fn drop(&mut self) {
         if !self.is_alias {
             unsafe {
-                let layout = capacity_to_aligned_layout::<T>(self.capacity);
-                Global.dealloc(std::ptr::NonNull::new_unchecked(self.buffer as *mut u8), layout);
+                dealloc_aligned(self.buffer, self.capacity);
             }
         }
     }
@@ -427,14 +437,17 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> Resiza
     fn aquire_buffer_for(&mut self, req_capacity: usize) {
         if req_capacity > self.capacity {
             unsafe {
-                let old_layout = capacity_to_aligned_layout::<T>(self.capacity);
-                self.buffer = Global.realloc(std::ptr::NonNull::new_unchecked(self.buffer as *mut u8), old_layout, req_capacity)
-                    .expect("Could not allocate buffer for matrix!").cast::<T>().as_mut();
+                self.buffer = realloc_aligned(self.buffer, self.capacity, req_capacity);
                 self.capacity = req_capacity;
             }
         }
     }
 
+    fn describe_layout() -> String {
+        format!("Hierarch({}x{} leaves, row stride {}, column stride {})",
+                LH::to_usize(), LW::to_usize(), LRS::to_usize(), LCS::to_usize())
+    }
+
     //(But maybe only need to change y_hierarchy[self.yh_index] and x_hierarchy[self.xh_index])
     #[inline(always)]
     fn resize_to(&mut self, other: &Mat<T>, y_hier_label: AlgorithmStep, x_hier_label: AlgorithmStep, hier: &[AlgorithmStep]) {
@@ -449,8 +462,10 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> Resiza
         y_view.padding = other.logical_h_padding();
         x_view.padding = other.logical_w_padding();
 
-        let y_tlds = self.y_hierarchy[self.yh_index].blksz;
-        let x_tlds = self.x_hierarchy[self.xh_index].blksz;
+        //Pad to the same top level blocks as capacity_for, so a dimension with no partitioning
+        //level still gets leaves of the full LH or LW the packers zero pad to
+        let y_tlds = Self::get_top_level_dim_size(hier, y_hier_label).unwrap_or(LH::to_usize());
+        let x_tlds = Self::get_top_level_dim_size(hier, x_hier_label).unwrap_or(LW::to_usize());
 
         let n_blocks_y = (other.height()-1) / y_tlds + 1;
         let n_blocks_x = (other.width()-1) / x_tlds + 1;
@@ -490,13 +505,6 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> RoCM<T
         self.buffer.offset((y_off + x_off) as isize) 
     }
 
-    #[inline(always)]
-    unsafe fn get_mut_buffer(&mut self) -> *mut T {
-        let y_off = self.y_views.last().unwrap().offset;
-        let x_off = self.x_views.last().unwrap().offset;
-        self.buffer.offset((y_off + x_off) as isize) 
-    }
-
     #[inline(always)]
     fn get_block_rs(&self, lvl: usize, blksz: usize) -> usize {
         if lvl == 0 { 
@@ -525,3 +533,18 @@ impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> RoCM<T
     #[inline(always)]
     unsafe fn establish_leaf(&mut self, _y: usize, _x: usize, _height: usize, _width: usize) { }
 }
+impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> RoCMMut<T> for
+    Hierarch<T, LH, LW, LRS, LCS> {
+    #[inline(always)]
+    unsafe fn get_mut_buffer(&mut self) -> *mut T {
+        let y_off = self.y_views.last().unwrap().offset;
+        let x_off = self.x_views.last().unwrap().offset;
+        self.buffer.offset((y_off + x_off) as isize) 
+    }
+}
+impl<T: Scalar, LH: Unsigned, LW: Unsigned, LRS: Unsigned, LCS: Unsigned> Addressed<T> for
+    Hierarch<T, LH, LW, LRS, LCS> {
+    fn address(&self, y: usize, x: usize) -> *const T {
+        self.buffer.wrapping_offset(self.get_offset(y,x))
+    }
+}
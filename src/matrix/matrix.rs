@@ -4,6 +4,7 @@ use core::fmt::Display;
 use core::ops::{Add, Mul, Sub, Div, AddAssign, MulAssign, SubAssign, DivAssign};
 use thread_comm::ThreadInfo;
 use composables::AlgorithmStep;
+use error::MommsError;
 
 //Trait Definitions
 pub trait ScalarConstants {
@@ -12,6 +13,7 @@ pub trait ScalarConstants {
     #[inline(always)]
     fn zero() -> Self;
 }
+//Numeric core shared by real and complex scalars
 pub trait Scalar where
     Self: Add<Self, Output=Self>,
     Self: Mul<Self, Output=Self>,
@@ -26,11 +28,26 @@ pub trait Scalar where
     Self: Display,
     Self: rand::Rand,
     Self: Send,
+    Self: Sync,
     Self: ScalarConstants,
+    Self: PartialEq,
+{
+    //The type of |x|, which is Self for real scalars
+    type Real: RealScalar;
+    fn conj(self) -> Self;
+    fn norm_sqr(self) -> Self::Real;
+}
+
+//Ordered extension of Scalar for real numbers
+pub trait RealScalar where
+    Self: Scalar<Real=Self>,
     Self: PartialOrd,
 {
     fn max(self, other: Self) -> Self;
     fn min(self, other: Self) -> Self;
+    fn sqrt(self) -> Self;
+    //self * a + b with a single rounding
+    fn mul_add(self, a: Self, b: Self) -> Self;
 }
 
 impl ScalarConstants for f64 {
@@ -40,10 +57,21 @@ impl ScalarConstants for f64 {
     fn zero() -> Self { 0.0 as f64 }
 }
 impl Scalar for f64 {
+    type Real = f64;
+    #[inline(always)]
+    fn conj(self) -> f64 { self }
+    #[inline(always)]
+    fn norm_sqr(self) -> f64 { self * self }
+}
+impl RealScalar for f64 {
     #[inline(always)]
     fn max(self, other: f64) -> f64 { self.max(other) }
     #[inline(always)]
     fn min(self, other: f64) -> f64 { self.min(other) }
+    #[inline(always)]
+    fn sqrt(self) -> f64 { self.sqrt() }
+    #[inline(always)]
+    fn mul_add(self, a: f64, b: f64) -> f64 { self.mul_add(a, b) }
 }
 
 impl ScalarConstants for f32 {
@@ -53,9 +81,33 @@ impl ScalarConstants for f32 {
     fn zero() -> Self { 0.0 as f32 }
 }
 impl Scalar for f32 {
+    type Real = f32;
+    #[inline(always)]
+    fn conj(self) -> f32 { self }
+    #[inline(always)]
+    fn norm_sqr(self) -> f32 { self * self }
+}
+impl RealScalar for f32 {
     #[inline(always)]
     fn max(self, other: f32) -> f32 { self.max(other) }
     fn min(self, other: f32) -> f32 { self.min(other) }
+    fn sqrt(self) -> f32 { self.sqrt() }
+    fn mul_add(self, a: f32, b: f32) -> f32 { self.mul_add(a, b) }
+}
+
+//Accumulator type of integer gemm. Its magnitude is measured in f64 so errors can't overflow.
+impl ScalarConstants for i32 {
+    #[inline(always)]
+    fn one() -> Self { 1 }
+    #[inline(always)]
+    fn zero() -> Self { 0 }
+}
+impl Scalar for i32 {
+    type Real = f64;
+    #[inline(always)]
+    fn conj(self) -> i32 { self }
+    #[inline(always)]
+    fn norm_sqr(self) -> f64 { (self as f64) * (self as f64) }
 }
 
 /* Mat trait and its implementors */
@@ -103,6 +155,16 @@ pub trait Mat<T: Scalar> where Self: Send {
     #[inline(always)]
     fn get_scalar(&self) -> T;
 
+    //Whether the elements are logically the conjugates of what is stored in the buffer.
+    //Only general strided matrices can be conjugated; packing absorbs the conjugation.
+    fn is_conjugated(&self) -> bool { false }
+
+    //Views of packed formats must start on a panel or leaf boundary, so the block sizes of
+    //partitions must be multiples of these (height, width)
+    fn block_multiples() -> (usize, usize) where Self: Sized { (1, 1) }
+    //(row stride, column stride) within a leaf, if the format fixes them
+    fn leaf_strides() -> Option<(usize, usize)> where Self: Sized { None }
+
     //Functions for partitioning matrices.
     fn push_y_view(&mut self, blksz: usize) -> usize;
     fn push_x_view(&mut self, blksz: usize) -> usize;
@@ -176,11 +238,11 @@ pub trait Mat<T: Scalar> where Self: Send {
         }
     }
 
-    fn frosqr(&self) -> T {
-        let mut norm:T = T::zero();
+    fn frosqr(&self) -> T::Real {
+        let mut norm = <T::Real as ScalarConstants>::zero();
         for x in 0..self.width() {
             for y in 0..self.height() {
-                norm += self.get(y,x) * self.get(y,x);
+                norm += self.get(y,x).norm_sqr();
             }
         }
         norm
@@ -219,12 +281,28 @@ pub trait Mat<T: Scalar> where Self: Send {
     }
     
     fn axpby(&mut self, alpha: T, other: &Mat<T>, beta: T) {
+        if let Err(e) = self.try_axpby(alpha, other, beta) {
+            panic!("{}", e);
+        }
+    }
+
+    fn try_axpby(&mut self, alpha: T, other: &Mat<T>, beta: T) -> Result<(), MommsError> {
         if self.width() != other.width() || self.height() != other.height() {
-            panic!("Cannot operate on nonconformal matrices!");
+            return Err(MommsError::NonconformalMatrices{ lhs: (self.height(), self.width()),
+                                                         rhs: (other.height(), other.width()) });
         }
         let h = self.height();
         let w = self.width();
         self.axpby_rec(alpha, other, beta, 0, 0, h, w); 
+        Ok(())
+    }
+
+    fn try_copy_from(&mut self, other: &Mat<T>) -> Result<(), MommsError> {
+        self.try_axpby(T::one(), other, T::zero())
+    }
+
+    fn try_axpy(&mut self, alpha: T, other: &Mat<T>) -> Result<(), MommsError> {
+        self.try_axpby(alpha, other, T::one())
     }
 
     fn axpby_small(&mut self, alpha: T, other: &Mat<T>, beta: T) {
@@ -248,6 +326,8 @@ pub trait ResizableBuffer<T: Scalar> {
     fn capacity_for(other: &Mat<T>, y_hier_label: AlgorithmStep, x_hier_label: AlgorithmStep, hier: &[AlgorithmStep]) -> usize;
     fn aquire_buffer_for(&mut self, capacity: usize);
     fn resize_to(&mut self, other: &Mat<T>, y_hier_label: AlgorithmStep, x_hier_label: AlgorithmStep, hier: &[AlgorithmStep]); 
+    //The packed layout, for describing control trees
+    fn describe_layout() -> String;
 }
 
 //Trait indicating that the matrix can be partitioned down into row or column major form.
@@ -258,9 +338,20 @@ pub trait RoCM<T: Scalar> {
     fn get_leaf_rs(&self) -> usize;
     fn get_leaf_cs(&self) -> usize;
     unsafe fn get_buffer(&self) -> *const T;
-    unsafe fn get_mut_buffer(&mut self) -> *mut T;
     fn get_block_rs(&self, lvl: usize, blksz: usize) -> usize;
     fn get_block_cs(&self, lvl: usize, blksz: usize) -> usize;
     fn full_leaves() -> bool;
     unsafe fn establish_leaf(&mut self, y: usize, x:usize, height: usize, width: usize);
 }
+
+//RoCM matrices that may be written through their buffer, as C is.
+//Views of buffers that are only lent for reading (MatrixRef) implement RoCM alone, so they can only be A or B.
+pub trait RoCMMut<T: Scalar>: RoCM<T> {
+    unsafe fn get_mut_buffer(&mut self) -> *mut T;
+}
+
+//Matrices whose elements have a place in memory, so CacheSim can follow their accesses.
+//address(y, x) is where get(y, x) reads from, relative to the current view.
+pub trait Addressed<T: Scalar>: Mat<T> {
+    fn address(&self, y: usize, x: usize) -> *const T;
+}
@@ -5,9 +5,21 @@ mod row_panel;
 mod column_panel;
 mod hierarch;
 mod pack_pair;
+mod complex;
+mod quantized;
+mod half;
+mod borrowed;
+mod transposed;
+mod traced;
 
-pub use self::matrix::{Scalar,Mat,ResizableBuffer,RoCM};
+pub use self::matrix::{Scalar,RealScalar,Mat,ResizableBuffer,RoCM,RoCMMut,Addressed};
+pub use self::complex::{Complex,c32,c64};
+pub use self::half::{HalfElem,HalfMatrix,bf16,f16};
+pub use self::quantized::{QuantElem,QMatrix,QRowPanelMatrix,QColumnPanelMatrix,K_GROUP};
 pub use self::general_stride::{Matrix};
+pub use self::borrowed::{MatrixRef,MatrixMut};
+pub use self::transposed::{Transposed};
+pub use self::traced::{Traced};
 pub use self::row_panel::{RowPanelMatrix};
 pub use self::column_panel::{ColumnPanelMatrix};
 pub use self::hierarch::{Hierarch,HierarchyNode};
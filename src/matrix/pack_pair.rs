@@ -1,5 +1,5 @@
 use thread_comm::ThreadInfo;
-use matrix::{Scalar,Mat,RoCM};
+use matrix::{Scalar,Mat,RoCM,RoCMMut};
 use core::ptr;
 use core::marker::PhantomData;
 
@@ -16,6 +16,7 @@ impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> PackPair<T, At, Apt> {
     }
 }
 
+use core::arch::asm;
 impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> Mat<T> for PackPair<T, At, Apt> {
     #[inline(always)]
     fn get(&self, y: usize, x: usize) -> T { self.a.get(y,x) }
@@ -48,6 +49,9 @@ impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> Mat<T> for PackPair<T, At, Apt> {
     fn get_scalar(&self) -> T {
         self.a.get_scalar()
     }
+    //Kernels read the packed matrix
+    fn block_multiples() -> (usize, usize) { Apt::block_multiples() }
+    fn leaf_strides() -> Option<(usize, usize)> { Apt::leaf_strides() }
 
     #[inline(always)]
     fn push_y_split(&mut self, start: usize, end: usize) {
@@ -124,8 +128,9 @@ impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> Mat<T> for PackPair<T, At, Apt> {
 unsafe impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> Send for PackPair<T, At, Apt> {}
 
 
+//Establishing a leaf packs it into ap, so ap must be writable
 impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> RoCM<T> for PackPair<T, At, Apt> 
-    where At: RoCM<T>, Apt: RoCM<T>
+    where At: RoCM<T>, Apt: RoCMMut<T>
 {
     #[inline(always)]
     fn partition_is_rocm(&self) -> bool { 
@@ -155,7 +160,8 @@ impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> RoCM<T> for PackPair<T, At, Apt>
         let ap_buf = self.ap.get_mut_buffer().offset((y * self.ap.get_block_rs(1,height) 
             + x * self.ap.get_block_cs(1,width)) as isize);
         
-        if cfg!(feature="asm_snippets") && self.ap.get_leaf_cs() == 1 && self.a.get_leaf_cs() == 1 && width % 4 == 0 {
+        let conj = self.a.is_conjugated();
+        if cfg!(feature="asm_snippets") && !conj && self.ap.get_leaf_cs() == 1 && self.a.get_leaf_cs() == 1 && width % 4 == 0 {
             for ii in 0..height {
                 let a_ii = a_buf.offset((ii * self.a.get_leaf_rs()) as isize);
                 let ap_ii = ap_buf.offset((ii * self.ap.get_leaf_rs()) as isize);
@@ -164,13 +170,10 @@ impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> RoCM<T> for PackPair<T, At, Apt>
                     let a_ii_jj = a_ii.offset(jj as isize);
                     let ap_ii_jj = ap_ii.offset(jj as isize);
 
-                    asm!("vmovupd ymm0, [$0]
-                          vmovapd [$1], ymm0"
-                        : : "r" (a_ii_jj),
-                            "r"(ap_ii_jj)
-                          : "ymm0 memory"
-                          : "intel");
-                    asm!("prefetcht0 ($0)" : : "r"(a_ii_jj.offset((4*self.a.get_leaf_rs()) as isize)));
+                    asm!("vmovupd ymm0, [{0}]",
+                         "vmovapd [{1}], ymm0",
+                         in(reg) a_ii_jj, in(reg) ap_ii_jj, out("ymm0") _, options(nostack, preserves_flags));
+                    asm!("prefetcht0 [{0}]", in(reg) a_ii_jj.offset((4*self.a.get_leaf_rs()) as isize), options(nostack, preserves_flags));
                     //asm!("prefetchw ($0)" : : "r"(ap_ii_jj.offset((4*self.ap.get_leaf_rs()) as isize)));
                     //asm!("prefetcht2 ($0)" : : "r"(a_ii_jj.offset((16*self.a.get_leaf_rs()) as isize)));
                 }
@@ -180,16 +183,12 @@ impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> RoCM<T> for PackPair<T, At, Apt>
             for ii in 0..height {
                 for jj in 0..width {
                     let alpha = ptr::read(a_buf.offset((ii * self.a.get_leaf_rs() + jj * self.a.get_leaf_cs()) as isize));
+                    let alpha = if conj { alpha.conj() } else { alpha };
                     ptr::write(ap_buf.offset((ii * self.ap.get_leaf_rs() + jj * self.ap.get_leaf_cs()) as isize), alpha);
                 }
             }
         }
     }
-
-    #[inline(always)]
-    unsafe fn get_mut_buffer(&mut self) -> *mut T {
-        self.ap.get_mut_buffer()
-    }
     
     #[inline(always)]
     fn get_block_rs(&self, lvl: usize, blksz: usize) -> usize {
@@ -204,3 +203,11 @@ impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> RoCM<T> for PackPair<T, At, Apt>
         Apt::full_leaves()
     }
 }
+impl<T: Scalar, At: Mat<T>, Apt: Mat<T>> RoCMMut<T> for PackPair<T, At, Apt> 
+    where At: RoCM<T>, Apt: RoCMMut<T>
+{
+    #[inline(always)]
+    unsafe fn get_mut_buffer(&mut self) -> *mut T {
+        self.ap.get_mut_buffer()
+    }
+}
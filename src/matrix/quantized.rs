@@ -0,0 +1,825 @@
+extern crate rand;
+
+use thread_comm::ThreadInfo;
+use typenum::Unsigned;
+use matrix::{Mat,ResizableBuffer};
+use super::view::{MatrixView};
+use util::{alloc_aligned,dealloc_aligned,realloc_aligned};
+use core::{self, cmp, ptr, marker::PhantomData};
+
+use composables::{AlgorithmStep};
+
+//8-bit storage for integer gemm.
+//The matrices below store elements of type S but are logically matrices of i32,
+//so they plug into GemmNodes over i32 alongside Matrix<i32> for C.
+pub trait QuantElem where
+    Self: Copy,
+    Self: Send,
+    Self: rand::Rand,
+    Self: 'static,
+{
+    fn widen(self) -> i32;
+    //Saturates values outside the range of Self
+    fn narrow(alpha: i32) -> Self;
+}
+impl QuantElem for i8 {
+    #[inline(always)]
+    fn widen(self) -> i32 { self as i32 }
+    #[inline(always)]
+    fn narrow(alpha: i32) -> i8 { cmp::max(cmp::min(alpha, 127), -128) as i8 }
+}
+impl QuantElem for u8 {
+    #[inline(always)]
+    fn widen(self) -> i32 { self as i32 }
+    #[inline(always)]
+    fn narrow(alpha: i32) -> u8 { cmp::max(cmp::min(alpha, 255), 0) as u8 }
+}
+
+//Packed panels interleave k in groups of K_GROUP, so each 32-bit lane holds
+//the four consecutive k of one row of A or one column of B.
+//This is the operand layout of 8-bit dot product instructions such as vpdpbusd.
+pub const K_GROUP: usize = 4;
+
+#[inline(always)]
+fn round_up_k(k: usize) -> usize {
+    (k + K_GROUP - 1) / K_GROUP * K_GROUP
+}
+
+//Interleaved offset of element (i, p) of a micro-panel that is pd wide in the panel dimension
+#[inline(always)]
+fn interleaved_index(i: usize, p: usize, pd: usize) -> usize {
+    (p / K_GROUP) * K_GROUP * pd + i * K_GROUP + p % K_GROUP
+}
+
+//General strided matrix of 8-bit integers
+pub struct QMatrix<S: QuantElem> {
+    alpha: i32,
+
+    y_views: Vec<MatrixView>,
+    x_views: Vec<MatrixView>,
+
+    row_stride: usize,
+    column_stride: usize,
+    buffer: *mut S,
+    capacity: usize,
+    is_alias: bool,
+}
+impl<S: QuantElem> QMatrix<S> {
+    pub fn new(h: usize, w: usize) -> QMatrix<S> {
+        let buf = unsafe { alloc_aligned::<S>(h * w) };
+
+        let mut y_views : Vec<MatrixView> = Vec::with_capacity(16);
+        let mut x_views : Vec<MatrixView> = Vec::with_capacity(16);
+        y_views.push(MatrixView{ offset: 0, padding: 0, iter_size: h });
+        x_views.push(MatrixView{ offset: 0, padding: 0, iter_size: w });
+
+        QMatrix{ alpha: 1,
+                 y_views: y_views, x_views: x_views,
+                 row_stride: 1, column_stride: h,
+                 buffer: buf,
+                 capacity: h * w,
+                 is_alias: false }
+    }
+
+    #[inline(always)] pub fn get_row_stride(&self) -> usize { self.row_stride }
+    #[inline(always)] pub fn get_column_stride(&self) -> usize { self.column_stride }
+
+    pub fn transpose(&mut self) {
+        if self.y_views.len() != 1 || self.x_views.len() != 1 { panic!("can't transpose a submatrix!") };
+        let xview = self.x_views.pop().unwrap();
+        let yview = self.y_views.pop().unwrap();
+        self.y_views.push(xview);
+        self.x_views.push(yview);
+
+        core::mem::swap(&mut self.column_stride, &mut self.row_stride);
+    }
+
+    #[inline(always)]
+    pub unsafe fn get_buffer(&self) -> *const S {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        self.buffer.offset((y_view.offset*self.row_stride + x_view.offset*self.column_stride) as isize)
+    }
+}
+impl<S: QuantElem> Mat<i32> for QMatrix<S> {
+    #[inline(always)]
+    fn get(&self, y: usize, x: usize) -> i32 {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        let y_coord = (y + y_view.offset) * self.row_stride;
+        let x_coord = (x + x_view.offset) * self.column_stride;
+        unsafe{
+            ptr::read(self.buffer.offset((y_coord + x_coord) as isize)).widen()
+        }
+    }
+    #[inline(always)]
+    fn set(&mut self, y: usize, x: usize, alpha: i32) {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        let y_coord = (y + y_view.offset) * self.row_stride;
+        let x_coord = (x + x_view.offset) * self.column_stride;
+        unsafe{
+            ptr::write(self.buffer.offset((y_coord + x_coord) as isize), S::narrow(alpha));
+        }
+    }
+    #[inline(always)]
+    fn iter_height(&self) -> usize {
+        self.y_views.last().unwrap().iter_size
+    }
+    #[inline(always)]
+    fn iter_width(&self) -> usize {
+        self.x_views.last().unwrap().iter_size
+    }
+    #[inline(always)]
+    fn logical_h_padding(&self) -> usize {
+        self.y_views.last().unwrap().padding
+    }
+    #[inline(always)]
+    fn logical_w_padding(&self) -> usize {
+        self.x_views.last().unwrap().padding
+    }
+
+    #[inline(always)]
+    fn set_scalar(&mut self, alpha: i32) {
+        self.alpha = alpha;
+    }
+    #[inline(always)]
+    fn get_scalar(&self) -> i32 {
+        self.alpha
+    }
+
+    //Uniform over the whole range of S rather than [-1,1)
+    fn fill_rand(&mut self) {
+        let mut rng = rand::thread_rng();
+        for x in 0..self.width() {
+            for y in 0..self.height() {
+                let alpha = S::rand(&mut rng).widen();
+                self.set(y, x, alpha);
+            }
+        }
+    }
+
+    fn push_y_split(&mut self, start: usize, end: usize) {
+        let zoomed_view = {
+            let uz_view = self.y_views.last().unwrap();
+            let new_padding = if end <= self.height() { 0 } else { end - self.height() };
+            let new_offset = uz_view.offset + start;
+            MatrixView{ offset: new_offset, padding: new_padding, iter_size: end-start }
+        };
+        self.y_views.push(zoomed_view);
+    }
+    fn push_x_split(&mut self, start: usize, end: usize) {
+        let zoomed_view = {
+            let uz_view = self.x_views.last().unwrap();
+            let new_padding = if end <= self.width() { 0 } else { end - self.width() };
+            let new_offset = uz_view.offset + start;
+            MatrixView{ offset: new_offset, padding: new_padding, iter_size: end-start }
+        };
+        self.x_views.push(zoomed_view);
+    }
+    #[inline(always)]
+    fn pop_y_split(&mut self) {
+        debug_assert!(self.y_views.len() >= 2);
+        self.y_views.pop();
+    }
+    #[inline(always)]
+    fn pop_x_split(&mut self) {
+        debug_assert!(self.x_views.len() >= 2);
+        self.x_views.pop();
+    }
+
+    fn push_y_view(&mut self, blksz: usize) -> usize {
+        let (zoomed_view, uz_iter_size) = {
+            let uz_view = self.y_views.last().unwrap();
+            let (z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(0, blksz);
+            (MatrixView{ offset: uz_view.offset, padding: z_padding, iter_size: z_iter_size }, uz_view.iter_size)
+        };
+        self.y_views.push(zoomed_view);
+        uz_iter_size
+    }
+    fn push_x_view(&mut self, blksz: usize) -> usize {
+        let (zoomed_view, uz_iter_size) = {
+            let uz_view = self.x_views.last().unwrap();
+            let (z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(0, blksz);
+            (MatrixView{ offset: uz_view.offset, padding: z_padding, iter_size: z_iter_size }, uz_view.iter_size)
+        };
+        self.x_views.push(zoomed_view);
+        uz_iter_size
+    }
+    #[inline(always)]
+    fn pop_y_view(&mut self) {
+        debug_assert!(self.y_views.len() >= 2);
+        self.y_views.pop();
+    }
+    #[inline(always)]
+    fn pop_x_view(&mut self) {
+        debug_assert!(self.x_views.len() >= 2);
+        self.x_views.pop();
+    }
+
+    fn slide_y_view_to(&mut self, y: usize, blksz: usize) {
+        let view_len = self.y_views.len();
+        debug_assert!(view_len >= 2);
+
+        let uz_view = self.y_views[view_len-2];
+        let(z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(y, blksz);
+
+        let z_view = self.y_views.last_mut().unwrap();
+        z_view.iter_size = z_iter_size;
+        z_view.padding = z_padding;
+        z_view.offset = uz_view.offset + y;
+    }
+    fn slide_x_view_to(&mut self, x: usize, blksz: usize) {
+        let view_len = self.x_views.len();
+        debug_assert!(view_len >= 2);
+
+        let uz_view = self.x_views[view_len-2];
+        let(z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(x, blksz);
+
+        let z_view = self.x_views.last_mut().unwrap();
+        z_view.iter_size = z_iter_size;
+        z_view.padding = z_padding;
+        z_view.offset = uz_view.offset + x;
+    }
+
+    #[inline(always)]
+    unsafe fn make_alias(&self) -> Self {
+        let x_view = self.x_views.last().unwrap();
+        let y_view = self.y_views.last().unwrap();
+
+        let mut x_views_alias : Vec<MatrixView> = Vec::with_capacity(16);
+        let mut y_views_alias : Vec<MatrixView> = Vec::with_capacity(16);
+        x_views_alias.push(*x_view);
+        y_views_alias.push(*y_view);
+
+        QMatrix{ alpha: self.alpha,
+                 x_views: x_views_alias, y_views: y_views_alias,
+                 row_stride: self.row_stride, column_stride: self.column_stride,
+                 buffer: self.buffer,
+                 capacity: self.capacity,
+                 is_alias: true }
+    }
+
+    #[inline(always)]
+    unsafe fn send_alias(&mut self, thr: &ThreadInfo<i32>) {
+        let buf = thr.broadcast(self.buffer as *mut i32);
+        self.is_alias = true;
+        self.buffer = buf as *mut S;
+    }
+}
+impl<S: QuantElem> Drop for QMatrix<S> {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.is_alias {
+                dealloc_aligned(self.buffer, self.capacity);
+            }
+        }
+    }
+}
+unsafe impl<S: QuantElem> Send for QMatrix<S> {}
+
+//Packed A: row panels of height PH, each an interleaved PH x k micro-panel.
+//k is padded up to a multiple of K_GROUP with zeros.
+pub struct QRowPanelMatrix<S: QuantElem, PH: Unsigned> {
+    alpha: i32,
+
+    y_views: Vec<MatrixView>, //offset is in # of panels
+    x_views: Vec<MatrixView>,
+
+    panel_stride: usize,
+    buffer: *mut S,
+    capacity: usize,
+    is_alias: bool,
+
+    _pht: PhantomData<PH>,
+}
+impl<S: QuantElem, PH: Unsigned> QRowPanelMatrix<S,PH> {
+    pub fn new(h: usize, w: usize) -> QRowPanelMatrix<S,PH> {
+        let panel_h = PH::to_usize();
+        let n_panels = if h == 0 { 0 } else { (h-1) / panel_h + 1 };
+        let capacity = n_panels * panel_h * round_up_k(w);
+
+        let mut y_views : Vec<MatrixView> = Vec::with_capacity(16);
+        let mut x_views : Vec<MatrixView> = Vec::with_capacity(16);
+        y_views.push(MatrixView{ offset: 0, padding: 0, iter_size: h });
+        x_views.push(MatrixView{ offset: 0, padding: 0, iter_size: w });
+
+        let buf = unsafe { alloc_aligned::<S>(capacity) };
+
+        QRowPanelMatrix{ alpha: 1,
+                         y_views: y_views, x_views: x_views,
+                         panel_stride: panel_h*round_up_k(w),
+                         buffer: buf,
+                         capacity: capacity,
+                         is_alias: false,
+                         _pht: PhantomData }
+    }
+
+    #[inline(always)]
+    pub fn get_panel_stride(&self) -> usize { self.panel_stride }
+
+    #[inline(always)]
+    pub unsafe fn get_panel(&mut self, id: usize) -> *mut S {
+        let y_view = self.y_views.last().unwrap();
+        self.buffer.offset(((y_view.offset + id)*self.panel_stride) as isize)
+    }
+
+    //Start of the first micro-panel of the current view
+    #[inline(always)]
+    pub unsafe fn get_buffer(&self) -> *const S {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+        debug_assert_eq!(x_view.offset % K_GROUP, 0);
+
+        self.buffer.offset((x_view.offset*PH::to_usize() + y_view.offset*self.panel_stride) as isize)
+    }
+
+    #[inline(always)]
+    fn elem_index(&self, y: usize, x: usize) -> usize {
+        let panel_h = PH::to_usize();
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        (y / panel_h + y_view.offset) * self.panel_stride + interleaved_index(y % panel_h, x + x_view.offset, panel_h)
+    }
+}
+impl<S: QuantElem, PH: Unsigned> Mat<i32> for QRowPanelMatrix<S, PH> {
+    #[inline(always)]
+    fn get(&self, y: usize, x: usize) -> i32 {
+        unsafe{
+            ptr::read(self.buffer.offset(self.elem_index(y, x) as isize)).widen()
+        }
+    }
+    #[inline(always)]
+    fn set(&mut self, y: usize, x: usize, alpha: i32) {
+        unsafe{
+            ptr::write(self.buffer.offset(self.elem_index(y, x) as isize), S::narrow(alpha));
+        }
+    }
+    #[inline(always)]
+    fn iter_height(&self) -> usize {
+        self.y_views.last().unwrap().iter_size
+    }
+    #[inline(always)]
+    fn iter_width(&self) -> usize {
+        self.x_views.last().unwrap().iter_size
+    }
+    #[inline(always)]
+    fn logical_h_padding(&self) -> usize {
+        self.y_views.last().unwrap().padding
+    }
+    #[inline(always)]
+    fn logical_w_padding(&self) -> usize {
+        self.x_views.last().unwrap().padding
+    }
+
+    #[inline(always)]
+    fn set_scalar(&mut self, alpha: i32) {
+        self.alpha = alpha;
+    }
+    #[inline(always)]
+    fn get_scalar(&self) -> i32 {
+        self.alpha
+    }
+    //Views in k must start on a group of K_GROUP
+    fn block_multiples() -> (usize, usize) { (PH::to_usize(), K_GROUP) }
+
+    fn push_y_split(&mut self, start: usize, end: usize) {
+        debug_assert!(start % PH::to_usize() == 0 && end % PH::to_usize() == 0);
+
+        let zoomed_view = {
+            let uz_view = self.y_views.last().unwrap();
+            let new_padding = if end <= self.height() { 0 } else { end - self.height() };
+            let new_offset = uz_view.offset + start / PH::to_usize();
+            MatrixView{ offset: new_offset, padding: new_padding, iter_size: end-start }
+        };
+        self.y_views.push(zoomed_view);
+    }
+    fn push_x_split(&mut self, start: usize, end: usize) {
+        let zoomed_view = {
+            let uz_view = self.x_views.last().unwrap();
+            let new_padding = if end <= self.width() { 0 } else { end - self.width() };
+            let new_offset = uz_view.offset + start;
+            MatrixView{ offset: new_offset, padding: new_padding, iter_size: end-start }
+        };
+        self.x_views.push(zoomed_view);
+    }
+    #[inline(always)]
+    fn pop_y_split(&mut self) {
+        debug_assert!(self.y_views.len() >= 2);
+        self.y_views.pop();
+    }
+    #[inline(always)]
+    fn pop_x_split(&mut self) {
+        debug_assert!(self.x_views.len() >= 2);
+        self.x_views.pop();
+    }
+
+    fn push_y_view(&mut self, blksz: usize) -> usize {
+        let (zoomed_view, uz_iter_size) = {
+            let uz_view = self.y_views.last().unwrap();
+            let (z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(0, blksz);
+            (MatrixView{ offset: uz_view.offset, padding: z_padding, iter_size: z_iter_size }, uz_view.iter_size)
+        };
+        self.y_views.push(zoomed_view);
+        uz_iter_size
+    }
+    fn push_x_view(&mut self, blksz: usize) -> usize {
+        let (zoomed_view, uz_iter_size) = {
+            let uz_view = self.x_views.last().unwrap();
+            let (z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(0, blksz);
+            (MatrixView{ offset: uz_view.offset, padding: z_padding, iter_size: z_iter_size }, uz_view.iter_size)
+        };
+        self.x_views.push(zoomed_view);
+        uz_iter_size
+    }
+    #[inline(always)]
+    fn pop_y_view(&mut self) {
+        debug_assert!(self.y_views.len() >= 2);
+        self.y_views.pop();
+    }
+    #[inline(always)]
+    fn pop_x_view(&mut self) {
+        debug_assert!(self.x_views.len() >= 2);
+        self.x_views.pop();
+    }
+
+    fn slide_y_view_to(&mut self, y: usize, blksz: usize) {
+        let view_len = self.y_views.len();
+        debug_assert!(view_len >= 2);
+
+        let uz_view = self.y_views[view_len-2];
+        let(z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(y, blksz);
+
+        let z_view = self.y_views.last_mut().unwrap();
+        z_view.iter_size = z_iter_size;
+        z_view.padding = z_padding;
+        z_view.offset = uz_view.offset + y / PH::to_usize();
+    }
+    fn slide_x_view_to(&mut self, x: usize, blksz: usize) {
+        let view_len = self.x_views.len();
+        debug_assert!(view_len >= 2);
+
+        let uz_view = self.x_views[view_len-2];
+        let(z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(x, blksz);
+
+        let z_view = self.x_views.last_mut().unwrap();
+        z_view.iter_size = z_iter_size;
+        z_view.padding = z_padding;
+        z_view.offset = uz_view.offset + x;
+    }
+
+    #[inline(always)]
+    unsafe fn make_alias(&self) -> Self {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        let mut y_views_alias : Vec<MatrixView> = Vec::with_capacity(16);
+        let mut x_views_alias : Vec<MatrixView> = Vec::with_capacity(16);
+        y_views_alias.push(*y_view);
+        x_views_alias.push(*x_view);
+
+        QRowPanelMatrix{ alpha: self.alpha,
+                         y_views: y_views_alias, x_views: x_views_alias,
+                         panel_stride: self.panel_stride,
+                         buffer: self.buffer,
+                         capacity: self.capacity,
+                         is_alias: true,
+                         _pht: PhantomData }
+    }
+
+    #[inline(always)]
+    unsafe fn send_alias(&mut self, thr: &ThreadInfo<i32>) {
+        let buf = thr.broadcast(self.buffer as *mut i32);
+        self.is_alias = true;
+        self.buffer = buf as *mut S;
+    }
+}
+impl<S: QuantElem, PH: Unsigned> Drop for QRowPanelMatrix<S, PH> {
+    fn drop(&mut self) {
+        if !self.is_alias {
+            unsafe {
+                dealloc_aligned(self.buffer, self.capacity);
+            }
+        }
+    }
+}
+unsafe impl<S: QuantElem, PH: Unsigned> Send for QRowPanelMatrix<S, PH> {}
+
+impl<S: QuantElem, PH: Unsigned> ResizableBuffer<i32> for QRowPanelMatrix<S, PH> {
+    #[inline(always)]
+    fn empty(_: AlgorithmStep, _: AlgorithmStep, _: &[AlgorithmStep]) -> Self {
+        QRowPanelMatrix::new(0,0)
+    }
+    #[inline(always)]
+    fn capacity(&self) -> usize { self.capacity }
+    #[inline(always)]
+    fn set_capacity(&mut self, capacity: usize) { self.capacity = capacity; }
+    #[inline(always)]
+    fn capacity_for(other: &Mat<i32>, _: AlgorithmStep, _: AlgorithmStep, _: &[AlgorithmStep]) -> usize {
+        if other.height() == 0 || other.width() == 0 {
+            0
+        } else {
+            let new_n_panels = (other.height()-1) / PH::to_usize() + 1;
+            new_n_panels * PH::to_usize() * round_up_k(other.width())
+        }
+    }
+    #[inline(always)]
+    fn aquire_buffer_for(&mut self, req_capacity: usize) {
+        if req_capacity > self.capacity {
+            unsafe {
+                self.buffer = realloc_aligned(self.buffer, self.capacity, req_capacity);
+                self.capacity = req_capacity;
+            }
+        }
+    }
+    fn describe_layout() -> String {
+        format!("QRowPanelMatrix(panel height {})", PH::to_usize())
+    }
+    #[inline(always)]
+    fn resize_to(&mut self, other: &Mat<i32>, _: AlgorithmStep, _: AlgorithmStep, _: &[AlgorithmStep]) {
+        debug_assert_eq!(self.y_views.len(), 1, "Can't resize a submatrix!");
+        let y_view = self.y_views.last_mut().unwrap();
+        let x_view = self.x_views.last_mut().unwrap();
+
+        y_view.iter_size = other.iter_height();
+        x_view.iter_size = other.iter_width();
+        y_view.padding = other.logical_h_padding();
+        x_view.padding = other.logical_w_padding();
+        self.panel_stride = PH::to_usize()*round_up_k(other.width());
+    }
+}
+
+//Packed B: column panels of width PW, each an interleaved k x PW micro-panel.
+//k is padded up to a multiple of K_GROUP with zeros.
+pub struct QColumnPanelMatrix<S: QuantElem, PW: Unsigned> {
+    alpha: i32,
+
+    y_views: Vec<MatrixView>,
+    x_views: Vec<MatrixView>, //offset is in # of panels
+
+    panel_stride: usize,
+    buffer: *mut S,
+    capacity: usize,
+    is_alias: bool,
+
+    _pwt: PhantomData<PW>,
+}
+impl<S: QuantElem, PW: Unsigned> QColumnPanelMatrix<S,PW> {
+    pub fn new(h: usize, w: usize) -> QColumnPanelMatrix<S,PW> {
+        let panel_w = PW::to_usize();
+        let n_panels = if w == 0 { 0 } else { (w-1) / panel_w + 1 };
+        let capacity = n_panels * panel_w * round_up_k(h);
+
+        let mut y_views : Vec<MatrixView> = Vec::with_capacity(16);
+        let mut x_views : Vec<MatrixView> = Vec::with_capacity(16);
+        y_views.push(MatrixView{ offset: 0, padding: 0, iter_size: h });
+        x_views.push(MatrixView{ offset: 0, padding: 0, iter_size: w });
+
+        let buf = unsafe { alloc_aligned::<S>(capacity) };
+
+        QColumnPanelMatrix{ alpha: 1,
+                            y_views: y_views, x_views: x_views,
+                            panel_stride: panel_w*round_up_k(h),
+                            buffer: buf,
+                            capacity: capacity,
+                            is_alias: false,
+                            _pwt: PhantomData }
+    }
+
+    #[inline(always)]
+    pub fn get_panel_stride(&self) -> usize { self.panel_stride }
+
+    #[inline(always)]
+    pub unsafe fn get_panel(&mut self, id: usize) -> *mut S {
+        let x_view = self.x_views.last().unwrap();
+        self.buffer.offset(((x_view.offset + id)*self.panel_stride) as isize)
+    }
+
+    //Start of the first micro-panel of the current view
+    #[inline(always)]
+    pub unsafe fn get_buffer(&self) -> *const S {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+        debug_assert_eq!(y_view.offset % K_GROUP, 0);
+
+        self.buffer.offset((y_view.offset*PW::to_usize() + x_view.offset*self.panel_stride) as isize)
+    }
+
+    #[inline(always)]
+    fn elem_index(&self, y: usize, x: usize) -> usize {
+        let panel_w = PW::to_usize();
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        (x / panel_w + x_view.offset) * self.panel_stride + interleaved_index(x % panel_w, y + y_view.offset, panel_w)
+    }
+}
+impl<S: QuantElem, PW: Unsigned> Mat<i32> for QColumnPanelMatrix<S, PW> {
+    #[inline(always)]
+    fn get(&self, y: usize, x: usize) -> i32 {
+        unsafe{
+            ptr::read(self.buffer.offset(self.elem_index(y, x) as isize)).widen()
+        }
+    }
+    #[inline(always)]
+    fn set(&mut self, y: usize, x: usize, alpha: i32) {
+        unsafe{
+            ptr::write(self.buffer.offset(self.elem_index(y, x) as isize), S::narrow(alpha));
+        }
+    }
+    #[inline(always)]
+    fn iter_height(&self) -> usize {
+        self.y_views.last().unwrap().iter_size
+    }
+    #[inline(always)]
+    fn iter_width(&self) -> usize {
+        self.x_views.last().unwrap().iter_size
+    }
+    #[inline(always)]
+    fn logical_h_padding(&self) -> usize {
+        self.y_views.last().unwrap().padding
+    }
+    #[inline(always)]
+    fn logical_w_padding(&self) -> usize {
+        self.x_views.last().unwrap().padding
+    }
+
+    #[inline(always)]
+    fn set_scalar(&mut self, alpha: i32) {
+        self.alpha = alpha;
+    }
+    #[inline(always)]
+    fn get_scalar(&self) -> i32 {
+        self.alpha
+    }
+    fn block_multiples() -> (usize, usize) { (K_GROUP, PW::to_usize()) }
+
+    fn push_y_split(&mut self, start: usize, end: usize) {
+        let zoomed_view = {
+            let uz_view = self.y_views.last().unwrap();
+            let new_padding = if end <= self.height() { 0 } else { end - self.height() };
+            let new_offset = uz_view.offset + start;
+            MatrixView{ offset: new_offset, padding: new_padding, iter_size: end-start }
+        };
+        self.y_views.push(zoomed_view);
+    }
+    fn push_x_split(&mut self, start: usize, end: usize) {
+        debug_assert!(start % PW::to_usize() == 0 && end % PW::to_usize() == 0);
+
+        let zoomed_view = {
+            let uz_view = self.x_views.last().unwrap();
+            let new_padding = if end <= self.width() { 0 } else { end - self.width() };
+            let new_offset = uz_view.offset + start / PW::to_usize();
+            MatrixView{ offset: new_offset, padding: new_padding, iter_size: end-start }
+        };
+        self.x_views.push(zoomed_view);
+    }
+    #[inline(always)]
+    fn pop_y_split(&mut self) {
+        debug_assert!(self.y_views.len() >= 2);
+        self.y_views.pop();
+    }
+    #[inline(always)]
+    fn pop_x_split(&mut self) {
+        debug_assert!(self.x_views.len() >= 2);
+        self.x_views.pop();
+    }
+
+    fn push_y_view(&mut self, blksz: usize) -> usize {
+        let (zoomed_view, uz_iter_size) = {
+            let uz_view = self.y_views.last().unwrap();
+            let (z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(0, blksz);
+            (MatrixView{ offset: uz_view.offset, padding: z_padding, iter_size: z_iter_size }, uz_view.iter_size)
+        };
+        self.y_views.push(zoomed_view);
+        uz_iter_size
+    }
+    fn push_x_view(&mut self, blksz: usize) -> usize {
+        let (zoomed_view, uz_iter_size) = {
+            let uz_view = self.x_views.last().unwrap();
+            let (z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(0, blksz);
+            (MatrixView{ offset: uz_view.offset, padding: z_padding, iter_size: z_iter_size }, uz_view.iter_size)
+        };
+        self.x_views.push(zoomed_view);
+        uz_iter_size
+    }
+    #[inline(always)]
+    fn pop_y_view(&mut self) {
+        debug_assert!(self.y_views.len() >= 2);
+        self.y_views.pop();
+    }
+    #[inline(always)]
+    fn pop_x_view(&mut self) {
+        debug_assert!(self.x_views.len() >= 2);
+        self.x_views.pop();
+    }
+
+    fn slide_y_view_to(&mut self, y: usize, blksz: usize) {
+        let view_len = self.y_views.len();
+        debug_assert!(view_len >= 2);
+
+        let uz_view = self.y_views[view_len-2];
+        let(z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(y, blksz);
+
+        let z_view = self.y_views.last_mut().unwrap();
+        z_view.iter_size = z_iter_size;
+        z_view.padding = z_padding;
+        z_view.offset = uz_view.offset + y;
+    }
+    fn slide_x_view_to(&mut self, x: usize, blksz: usize) {
+        let view_len = self.x_views.len();
+        debug_assert!(view_len >= 2);
+
+        let uz_view = self.x_views[view_len-2];
+        let(z_iter_size, z_padding) = uz_view.zoomed_size_and_padding(x, blksz);
+
+        let z_view = self.x_views.last_mut().unwrap();
+        z_view.iter_size = z_iter_size;
+        z_view.padding = z_padding;
+        z_view.offset = uz_view.offset + x / PW::to_usize();
+    }
+
+    #[inline(always)]
+    unsafe fn make_alias(&self) -> Self {
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        let mut y_views_alias : Vec<MatrixView> = Vec::with_capacity(16);
+        let mut x_views_alias : Vec<MatrixView> = Vec::with_capacity(16);
+        y_views_alias.push(*y_view);
+        x_views_alias.push(*x_view);
+
+        QColumnPanelMatrix{ alpha: self.alpha,
+                            y_views: y_views_alias, x_views: x_views_alias,
+                            panel_stride: self.panel_stride,
+                            buffer: self.buffer,
+                            capacity: self.capacity,
+                            is_alias: true,
+                            _pwt: PhantomData }
+    }
+
+    #[inline(always)]
+    unsafe fn send_alias(&mut self, thr: &ThreadInfo<i32>) {
+        let buf = thr.broadcast(self.buffer as *mut i32);
+        self.is_alias = true;
+        self.buffer = buf as *mut S;
+    }
+}
+impl<S: QuantElem, PW: Unsigned> Drop for QColumnPanelMatrix<S, PW> {
+    fn drop(&mut self) {
+        if !self.is_alias {
+            unsafe {
+                dealloc_aligned(self.buffer, self.capacity);
+            }
+        }
+    }
+}
+unsafe impl<S: QuantElem, PW: Unsigned> Send for QColumnPanelMatrix<S, PW> {}
+
+impl<S: QuantElem, PW: Unsigned> ResizableBuffer<i32> for QColumnPanelMatrix<S, PW> {
+    #[inline(always)]
+    fn empty(_: AlgorithmStep, _: AlgorithmStep, _: &[AlgorithmStep]) -> Self {
+        QColumnPanelMatrix::new(0,0)
+    }
+    #[inline(always)]
+    fn capacity(&self) -> usize { self.capacity }
+    #[inline(always)]
+    fn set_capacity(&mut self, capacity: usize) { self.capacity = capacity; }
+    #[inline(always)]
+    fn capacity_for(other: &Mat<i32>, _: AlgorithmStep, _: AlgorithmStep, _: &[AlgorithmStep]) -> usize {
+        if other.height() == 0 || other.width() == 0 {
+            0
+        } else {
+            let new_n_panels = (other.width()-1) / PW::to_usize() + 1;
+            new_n_panels * PW::to_usize() * round_up_k(other.height())
+        }
+    }
+    #[inline(always)]
+    fn aquire_buffer_for(&mut self, req_capacity: usize) {
+        if req_capacity > self.capacity {
+            unsafe {
+                self.buffer = realloc_aligned(self.buffer, self.capacity, req_capacity);
+                self.capacity = req_capacity;
+            }
+        }
+    }
+    fn describe_layout() -> String {
+        format!("QColumnPanelMatrix(panel width {})", PW::to_usize())
+    }
+    #[inline(always)]
+    fn resize_to(&mut self, other: &Mat<i32>, _: AlgorithmStep, _: AlgorithmStep, _: &[AlgorithmStep]) {
+        debug_assert_eq!(self.x_views.len(), 1, "Can't resize a submatrix!");
+        let y_view = self.y_views.last_mut().unwrap();
+        let x_view = self.x_views.last_mut().unwrap();
+
+        y_view.iter_size = other.iter_height();
+        x_view.iter_size = other.iter_width();
+        y_view.padding = other.logical_h_padding();
+        x_view.padding = other.logical_w_padding();
+        self.panel_stride = PW::to_usize()*round_up_k(other.height());
+    }
+}
@@ -1,9 +1,8 @@
 use thread_comm::ThreadInfo;
 use typenum::Unsigned;
-use std::alloc::{Alloc, Global};
-use matrix::{Scalar,Mat,ResizableBuffer,RoCM};
+use matrix::{Scalar,Mat,ResizableBuffer,RoCM,RoCMMut,Addressed};
 use super::view::{MatrixView};
-use util::capacity_to_aligned_layout;
+use util::{alloc_aligned,dealloc_aligned,realloc_aligned};
 use core::{self, ptr,marker::PhantomData};
 
 use composables::{AlgorithmStep};
@@ -38,13 +37,12 @@ impl<T: Scalar, PH: Unsigned> RowPanelMatrix<T,PH> {
         y_views.push(MatrixView{ offset: 0, padding: 0, iter_size: h }); 
         x_views.push(MatrixView{ offset: 0, padding: 0, iter_size: w }); 
 
-        let layout = capacity_to_aligned_layout::<T>(capacity);
-        let buf = unsafe { Global.alloc(layout).expect("Could not allocate buffer for matrix!") };
+        let buf = unsafe { alloc_aligned::<T>(capacity) };
 
         RowPanelMatrix{ alpha: T::one(),
                         y_views: y_views, x_views: x_views,
                         panel_stride: panel_h*w, 
-                        buffer: unsafe{buf.cast::<T>().as_mut()},
+                        buffer: buf,
                         capacity: capacity,
                         is_alias: false,
                         _pht: PhantomData }
@@ -117,6 +115,8 @@ impl<T: Scalar, PH: Unsigned> Mat<T> for RowPanelMatrix<T, PH> {
     fn get_scalar(&self) -> T {
         self.alpha
     }
+    fn block_multiples() -> (usize, usize) { (PH::to_usize(), 1) }
+    fn leaf_strides() -> Option<(usize, usize)> { Some((1, PH::to_usize())) }
 
     fn push_y_split(&mut self, start: usize, end: usize) {
         debug_assert!(start % PH::to_usize() == 0 && end % PH::to_usize() == 0);
@@ -239,8 +239,7 @@ impl<T:Scalar, PH: Unsigned> Drop for RowPanelMatrix<T, PH> {
     fn drop(&mut self) {
         if !self.is_alias {
             unsafe {
-                let layout = capacity_to_aligned_layout::<T>(self.capacity);
-                Global.dealloc(std::ptr::NonNull::new_unchecked(self.buffer as *mut u8), layout);
+                dealloc_aligned(self.buffer, self.capacity);
             }
         }
     }
@@ -270,13 +269,14 @@ impl<T:Scalar, PH: Unsigned> ResizableBuffer<T> for RowPanelMatrix<T, PH> {
     fn aquire_buffer_for(&mut self, req_capacity: usize) {
         if req_capacity > self.capacity {
             unsafe {
-                let old_layout = capacity_to_aligned_layout::<T>(self.capacity);
-                self.buffer = Global.realloc(std::ptr::NonNull::new_unchecked(self.buffer as *mut u8), old_layout, req_capacity)
-                    .expect("Could not allocate buffer for matrix!").cast::<T>().as_mut();
+                self.buffer = realloc_aligned(self.buffer, self.capacity, req_capacity);
                 self.capacity = req_capacity;
             }
         }
     }
+    fn describe_layout() -> String {
+        format!("RowPanelMatrix(panel height {})", PH::to_usize())
+    }
     #[inline(always)]
     fn resize_to(&mut self, other: &Mat<T>, _: AlgorithmStep, _: AlgorithmStep, _: &[AlgorithmStep]) {
         debug_assert_eq!(self.y_views.len(), 1, "Can't resize a submatrix!");
@@ -316,15 +316,6 @@ impl<T: Scalar, PH: Unsigned> RoCM<T> for RowPanelMatrix<T,PH> {
         self.buffer.offset((x_view.offset*panel_h + y_view.offset*self.panel_stride) as isize)
     }
 
-    #[inline(always)]
-    unsafe fn get_mut_buffer(&mut self) -> *mut T {
-        let panel_h = PH::to_usize();
-        let y_view = self.y_views.last().unwrap();
-        let x_view = self.x_views.last().unwrap();
-
-        self.buffer.offset((x_view.offset*panel_h + y_view.offset*self.panel_stride) as isize)
-    }
-
 	#[inline(always)]
     fn get_block_rs(&self, lvl: usize, blksz: usize) -> usize {
         if lvl == 0 {
@@ -347,3 +338,22 @@ impl<T: Scalar, PH: Unsigned> RoCM<T> for RowPanelMatrix<T,PH> {
     #[inline(always)]
     unsafe fn establish_leaf(&mut self, _y: usize, _x: usize, _height: usize, _width: usize) { }
 }
+impl<T: Scalar, PH: Unsigned> RoCMMut<T> for RowPanelMatrix<T,PH> {
+    #[inline(always)]
+    unsafe fn get_mut_buffer(&mut self) -> *mut T {
+        let panel_h = PH::to_usize();
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+
+        self.buffer.offset((x_view.offset*panel_h + y_view.offset*self.panel_stride) as isize)
+    }
+}
+impl<T: Scalar, PH: Unsigned> Addressed<T> for RowPanelMatrix<T,PH> {
+    fn address(&self, y: usize, x: usize) -> *const T {
+        let panel_h = PH::to_usize();
+        let y_view = self.y_views.last().unwrap();
+        let x_view = self.x_views.last().unwrap();
+        let elem_index = (y / panel_h + y_view.offset) * self.panel_stride + (x + x_view.offset) * panel_h + y % panel_h;
+        self.buffer.wrapping_offset(elem_index as isize)
+    }
+}
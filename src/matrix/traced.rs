@@ -0,0 +1,132 @@
+use core::mem;
+use std::sync::{Arc, Mutex};
+use thread_comm::ThreadInfo;
+use matrix::{Scalar, Mat, ResizableBuffer, RoCM, RoCMMut, Addressed};
+use composables::{AlgorithmStep, CacheSim};
+
+//A matrix whose accesses are run through a CacheSim as a control tree runs on it.
+//get and set report the element they touch. The kernels and packers read and write the buffers
+//directly, so they report the micro-panels, micro-tiles and blocks they touch themselves,
+//then run on the wrapped matrices. A packing buffer starts out untraced and is traced by
+//the simulator of the matrix packed into it.
+pub struct Traced<M> {
+    mat: M,
+    sim: Option<Arc<Mutex<CacheSim>>>,
+}
+impl<M> Traced<M> {
+    pub fn new(mat: M, sim: &Arc<Mutex<CacheSim>>) -> Traced<M> {
+        Traced{ mat: mat, sim: Some(sim.clone()) }
+    }
+    #[inline(always)] pub fn inner(&self) -> &M { &self.mat }
+    #[inline(always)] pub fn inner_mut(&mut self) -> &mut M { &mut self.mat }
+    pub fn into_inner(self) -> M { self.mat }
+
+    #[inline(always)] pub fn sim(&self) -> Option<&Arc<Mutex<CacheSim>>> { self.sim.as_ref() }
+    pub fn set_sim(&mut self, sim: Option<Arc<Mutex<CacheSim>>>) { self.sim = sim }
+
+    //Reports an access of len elements from start
+    #[inline(always)]
+    pub fn trace<T>(&self, start: *const T, len: usize, write: bool) {
+        if let Some(ref sim) = self.sim {
+            sim.lock().unwrap().access(start as usize, len * mem::size_of::<T>(), write);
+        }
+    }
+}
+
+impl<T: Scalar, M: Addressed<T>> Mat<T> for Traced<M> {
+    #[inline(always)]
+    fn get(&self, y: usize, x: usize) -> T {
+        self.trace(self.mat.address(y, x), 1, false);
+        self.mat.get(y, x)
+    }
+    #[inline(always)]
+    fn set(&mut self, y: usize, x: usize, alpha: T) {
+        self.trace(self.mat.address(y, x), 1, true);
+        self.mat.set(y, x, alpha)
+    }
+
+    #[inline(always)]
+    fn iter_height(&self) -> usize { self.mat.iter_height() }
+    #[inline(always)]
+    fn iter_width(&self) -> usize { self.mat.iter_width() }
+    #[inline(always)]
+    fn logical_h_padding(&self) -> usize { self.mat.logical_h_padding() }
+    #[inline(always)]
+    fn logical_w_padding(&self) -> usize { self.mat.logical_w_padding() }
+
+    #[inline(always)]
+    unsafe fn make_alias(&self) -> Self {
+        Traced{ mat: self.mat.make_alias(), sim: self.sim.clone() }
+    }
+    #[inline(always)]
+    unsafe fn send_alias(&mut self, thr: &ThreadInfo<T>) { self.mat.send_alias(thr) }
+
+    #[inline(always)]
+    fn set_scalar(&mut self, alpha: T) { self.mat.set_scalar(alpha) }
+    #[inline(always)]
+    fn get_scalar(&self) -> T { self.mat.get_scalar() }
+    #[inline(always)]
+    fn is_conjugated(&self) -> bool { self.mat.is_conjugated() }
+    fn block_multiples() -> (usize, usize) { M::block_multiples() }
+    fn leaf_strides() -> Option<(usize, usize)> { M::leaf_strides() }
+
+    fn push_y_view(&mut self, blksz: usize) -> usize { self.mat.push_y_view(blksz) }
+    fn push_x_view(&mut self, blksz: usize) -> usize { self.mat.push_x_view(blksz) }
+    #[inline(always)]
+    fn pop_y_view(&mut self) { self.mat.pop_y_view() }
+    #[inline(always)]
+    fn pop_x_view(&mut self) { self.mat.pop_x_view() }
+    fn slide_y_view_to(&mut self, y: usize, blksz: usize) { self.mat.slide_y_view_to(y, blksz) }
+    fn slide_x_view_to(&mut self, x: usize, blksz: usize) { self.mat.slide_x_view_to(x, blksz) }
+
+    fn push_y_split(&mut self, start: usize, end: usize) { self.mat.push_y_split(start, end) }
+    fn push_x_split(&mut self, start: usize, end: usize) { self.mat.push_x_split(start, end) }
+    #[inline(always)]
+    fn pop_y_split(&mut self) { self.mat.pop_y_split() }
+    #[inline(always)]
+    fn pop_x_split(&mut self) { self.mat.pop_x_split() }
+}
+
+impl<T: Scalar, M: Addressed<T> + RoCM<T>> RoCM<T> for Traced<M> {
+    #[inline(always)]
+    fn partition_is_rocm(&self) -> bool { self.mat.partition_is_rocm() }
+    #[inline(always)]
+    fn get_leaf_rs(&self) -> usize { self.mat.get_leaf_rs() }
+    #[inline(always)]
+    fn get_leaf_cs(&self) -> usize { self.mat.get_leaf_cs() }
+    #[inline(always)]
+    unsafe fn get_buffer(&self) -> *const T { self.mat.get_buffer() }
+    #[inline(always)]
+    fn get_block_rs(&self, lvl: usize, blksz: usize) -> usize { self.mat.get_block_rs(lvl, blksz) }
+    #[inline(always)]
+    fn get_block_cs(&self, lvl: usize, blksz: usize) -> usize { self.mat.get_block_cs(lvl, blksz) }
+    #[inline(always)]
+    fn full_leaves() -> bool { M::full_leaves() }
+    #[inline(always)]
+    unsafe fn establish_leaf(&mut self, y: usize, x: usize, height: usize, width: usize) {
+        self.mat.establish_leaf(y, x, height, width)
+    }
+}
+impl<T: Scalar, M: Addressed<T> + RoCMMut<T>> RoCMMut<T> for Traced<M> {
+    #[inline(always)]
+    unsafe fn get_mut_buffer(&mut self) -> *mut T { self.mat.get_mut_buffer() }
+}
+impl<T: Scalar, M: Addressed<T>> Addressed<T> for Traced<M> {
+    fn address(&self, y: usize, x: usize) -> *const T { self.mat.address(y, x) }
+}
+
+impl<T: Scalar, M: Addressed<T> + ResizableBuffer<T>> ResizableBuffer<T> for Traced<M> {
+    fn empty(y_hier_label: AlgorithmStep, x_hier_label: AlgorithmStep, hier: &[AlgorithmStep]) -> Self {
+        Traced{ mat: M::empty(y_hier_label, x_hier_label, hier), sim: None }
+    }
+    fn capacity(&self) -> usize { self.mat.capacity() }
+    fn set_capacity(&mut self, capacity: usize) { self.mat.set_capacity(capacity) }
+    fn capacity_for(other: &Mat<T>, y_hier_label: AlgorithmStep, x_hier_label: AlgorithmStep, hier: &[AlgorithmStep]) -> usize {
+        M::capacity_for(other, y_hier_label, x_hier_label, hier)
+    }
+    fn aquire_buffer_for(&mut self, capacity: usize) { self.mat.aquire_buffer_for(capacity) }
+    fn resize_to(&mut self, other: &Mat<T>, y_hier_label: AlgorithmStep, x_hier_label: AlgorithmStep, hier: &[AlgorithmStep]) {
+        self.mat.resize_to(other, y_hier_label, x_hier_label, hier)
+    }
+    fn describe_layout() -> String { M::describe_layout() }
+}
@@ -0,0 +1,99 @@
+use thread_comm::ThreadInfo;
+use matrix::{Scalar, Mat, RoCM, RoCMMut, Addressed};
+
+//Logical transpose of another matrix. Nothing is copied or reordered:
+//rows and columns, strides and partitions are swapped on every access,
+//so it can be partitioned at any depth and packing absorbs the transpose.
+//Wrap a conjugated Matrix for a conjugate transpose.
+pub struct Transposed<M> {
+    mat: M,
+}
+impl<M> Transposed<M> {
+    pub fn new(mat: M) -> Transposed<M> {
+        Transposed{ mat: mat }
+    }
+    #[inline(always)] pub fn inner(&self) -> &M { &self.mat }
+    #[inline(always)] pub fn inner_mut(&mut self) -> &mut M { &mut self.mat }
+    pub fn into_inner(self) -> M { self.mat }
+}
+
+impl<T: Scalar, M: Mat<T>> Mat<T> for Transposed<M> {
+    #[inline(always)]
+    fn get(&self, y: usize, x: usize) -> T { self.mat.get(x, y) }
+    #[inline(always)]
+    fn set(&mut self, y: usize, x: usize, alpha: T) { self.mat.set(x, y, alpha) }
+
+    #[inline(always)]
+    fn iter_height(&self) -> usize { self.mat.iter_width() }
+    #[inline(always)]
+    fn iter_width(&self) -> usize { self.mat.iter_height() }
+    #[inline(always)]
+    fn logical_h_padding(&self) -> usize { self.mat.logical_w_padding() }
+    #[inline(always)]
+    fn logical_w_padding(&self) -> usize { self.mat.logical_h_padding() }
+
+    #[inline(always)]
+    unsafe fn make_alias(&self) -> Self {
+        Transposed{ mat: self.mat.make_alias() }
+    }
+    #[inline(always)]
+    unsafe fn send_alias(&mut self, thr: &ThreadInfo<T>) { self.mat.send_alias(thr) }
+
+    #[inline(always)]
+    fn set_scalar(&mut self, alpha: T) { self.mat.set_scalar(alpha) }
+    #[inline(always)]
+    fn get_scalar(&self) -> T { self.mat.get_scalar() }
+    #[inline(always)]
+    fn is_conjugated(&self) -> bool { self.mat.is_conjugated() }
+    fn block_multiples() -> (usize, usize) {
+        let (h, w) = M::block_multiples();
+        (w, h)
+    }
+    fn leaf_strides() -> Option<(usize, usize)> {
+        M::leaf_strides().map(|(rs, cs)| (cs, rs))
+    }
+
+    fn push_y_view(&mut self, blksz: usize) -> usize { self.mat.push_x_view(blksz) }
+    fn push_x_view(&mut self, blksz: usize) -> usize { self.mat.push_y_view(blksz) }
+    #[inline(always)]
+    fn pop_y_view(&mut self) { self.mat.pop_x_view() }
+    #[inline(always)]
+    fn pop_x_view(&mut self) { self.mat.pop_y_view() }
+    fn slide_y_view_to(&mut self, y: usize, blksz: usize) { self.mat.slide_x_view_to(y, blksz) }
+    fn slide_x_view_to(&mut self, x: usize, blksz: usize) { self.mat.slide_y_view_to(x, blksz) }
+
+    fn push_y_split(&mut self, start: usize, end: usize) { self.mat.push_x_split(start, end) }
+    fn push_x_split(&mut self, start: usize, end: usize) { self.mat.push_y_split(start, end) }
+    #[inline(always)]
+    fn pop_y_split(&mut self) { self.mat.pop_x_split() }
+    #[inline(always)]
+    fn pop_x_split(&mut self) { self.mat.pop_y_split() }
+}
+
+impl<T: Scalar, M: Mat<T> + RoCM<T>> RoCM<T> for Transposed<M> {
+    #[inline(always)]
+    fn partition_is_rocm(&self) -> bool { self.mat.partition_is_rocm() }
+    #[inline(always)]
+    fn get_leaf_rs(&self) -> usize { self.mat.get_leaf_cs() }
+    #[inline(always)]
+    fn get_leaf_cs(&self) -> usize { self.mat.get_leaf_rs() }
+    #[inline(always)]
+    unsafe fn get_buffer(&self) -> *const T { self.mat.get_buffer() }
+    #[inline(always)]
+    fn get_block_rs(&self, lvl: usize, blksz: usize) -> usize { self.mat.get_block_cs(lvl, blksz) }
+    #[inline(always)]
+    fn get_block_cs(&self, lvl: usize, blksz: usize) -> usize { self.mat.get_block_rs(lvl, blksz) }
+    #[inline(always)]
+    fn full_leaves() -> bool { M::full_leaves() }
+    #[inline(always)]
+    unsafe fn establish_leaf(&mut self, y: usize, x: usize, height: usize, width: usize) {
+        self.mat.establish_leaf(x, y, width, height)
+    }
+}
+impl<T: Scalar, M: Mat<T> + RoCMMut<T>> RoCMMut<T> for Transposed<M> {
+    #[inline(always)]
+    unsafe fn get_mut_buffer(&mut self) -> *mut T { self.mat.get_mut_buffer() }
+}
+impl<T: Scalar, M: Addressed<T>> Addressed<T> for Transposed<M> {
+    fn address(&self, y: usize, x: usize) -> *const T { self.mat.address(x, y) }
+}
@@ -3,6 +3,7 @@ use core::ptr::{self};
 use std::sync::{Arc,RwLock};
 //use std::sync::{Barrier};
 use std::sync::atomic::{AtomicPtr,AtomicUsize,AtomicBool,Ordering};
+use error::MommsError;
 
 pub struct ThreadComm<T> {
     n_threads: usize,
@@ -88,8 +89,11 @@ impl<T> ThreadComm<T> {
         self.slot.load(Ordering::Relaxed)*/
     }
     //Pretty sure with this implementation, split can only be called one time.
-    fn split(&self, thread_id: usize, n_way: usize) -> Arc<ThreadComm<T>> {
-        assert_eq!(self.n_threads % n_way, 0);
+    fn split(&self, thread_id: usize, n_way: usize) -> Result<Arc<ThreadComm<T>>, MommsError> {
+        //Every thread of the communicator sees the same n_way, so they all fail together
+        if n_way == 0 || self.n_threads % n_way != 0 {
+            return Err(MommsError::UnevenSplit{ n_threads: self.n_threads, n_way: n_way });
+        }
 
         let subcomm_n_threads = self.n_threads / n_way;
         let sub_comm_number = thread_id / subcomm_n_threads; // Which subcomm are we going to use?
@@ -103,7 +107,7 @@ impl<T> ThreadComm<T> {
         self.barrier(thread_id);
 
         let comm = self.sub_comms[sub_comm_number].read().unwrap().clone();
-        comm.unwrap()
+        Ok(comm.unwrap())
     }
 }
 //unsafe impl Sync for ThreadComm {}
@@ -111,14 +115,16 @@ impl<T> ThreadComm<T> {
 
 pub struct ThreadInfo<T> {
     thread_id: usize,
+    //Id among all the threads spawned together, kept through splits
+    global_id: usize,
     comm: Arc<ThreadComm<T>>,
 }
 impl<T> ThreadInfo<T> {
     pub fn new( id: usize, comm: Arc<ThreadComm<T>> ) -> ThreadInfo<T> {
-        ThreadInfo{ thread_id: id, comm: comm }
+        ThreadInfo{ thread_id: id, global_id: id, comm: comm }
     }
     pub fn single_thread() -> ThreadInfo<T>{
-        ThreadInfo{ thread_id : 0, comm : Arc::new(ThreadComm::new(1)) }
+        ThreadInfo{ thread_id : 0, global_id: 0, comm : Arc::new(ThreadComm::new(1)) }
     }
     pub fn barrier(&self) {
         self.comm.barrier(self.thread_id);
@@ -128,9 +134,16 @@ impl<T> ThreadInfo<T> {
     }
     pub fn num_threads(&self) -> usize { self.comm.n_threads }
     pub fn thread_id(&self) -> usize { self.thread_id }
+    pub fn global_thread_id(&self) -> usize { self.global_id }
     pub fn split(&self, n_way: usize) -> ThreadInfo<T> {
-        let subcomm = self.comm.split(self.thread_id, n_way);
+        match self.try_split(n_way) {
+            Ok(info) => info,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    pub fn try_split(&self, n_way: usize) -> Result<ThreadInfo<T>, MommsError> {
+        let subcomm = self.comm.split(self.thread_id, n_way)?;
         let subcomm_id = self.thread_id % (self.comm.n_threads / n_way);
-        ThreadInfo{ thread_id: subcomm_id, comm: subcomm }
+        Ok(ThreadInfo{ thread_id: subcomm_id, global_id: self.global_id, comm: subcomm })
     }
 }
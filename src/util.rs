@@ -1,14 +1,20 @@
+#[cfg(feature="hwloc")]
 extern crate hwloc;
 extern crate libc;
 
 use std::time::Instant;
-use libc::{c_double, int64_t, c_char};
+#[cfg(feature="blis")]
+use libc::{c_double, c_float, int64_t, c_char};
 
+#[cfg(feature="blis")]
 use std::ffi::{CString};
 use thread_comm::ThreadInfo;
-use matrix::{Scalar, Mat, Matrix, RoCM};
+use matrix::{Scalar, Mat, Matrix};
+#[cfg(feature="blis")]
+use matrix::{RoCM, RoCMMut};
 use composables::{GemmNode, TripleLoop};
-use std::alloc::Layout;
+use std::alloc::{self, Layout};
+use error::MommsError;
 
 #[cfg(feature="blis")]
 extern{
@@ -19,6 +25,13 @@ extern{
                b: *const c_double, ldb: *const int64_t,
                beta: *const c_double,
                c: *mut c_double, ldc: *const int64_t );
+    fn sgemm_( transa: *const c_char, transb: *const c_char,
+               m: *const int64_t, n: *const int64_t, k: *const int64_t,
+               alpha: *const c_float, 
+               a: *const c_float, lda: *const int64_t,
+               b: *const c_float, ldb: *const int64_t,
+               beta: *const c_float,
+               c: *mut c_float, ldc: *const int64_t );
 }
 
 #[cfg(feature="blis")]
@@ -27,9 +40,9 @@ pub fn blas_dgemm( a: &mut Matrix<f64>, b: &mut Matrix<f64>, c: &mut Matrix<f64>
     unsafe {
         let transa = CString::new("N").unwrap();
         let transb = CString::new("N").unwrap();
-        let ap = a.get_mut_buffer();
+        let ap = a.get_buffer();
         let bp = b.get_buffer();
-        let cp = c.get_buffer();
+        let cp = c.get_mut_buffer();
 
         let lda = a.get_column_stride() as int64_t;
         let ldb = b.get_column_stride() as int64_t;
@@ -48,11 +61,42 @@ pub fn blas_dgemm( a: &mut Matrix<f64>, b: &mut Matrix<f64>, c: &mut Matrix<f64>
                 ap as *const c_double, &lda,
                 bp as *const c_double, &ldb,
                 &beta as *const c_double,
-                cp as *mut c_double, &ldc );
+                cp, &ldc );
+    }
+}
+
+#[cfg(feature="blis")]
+pub fn blas_sgemm( a: &mut Matrix<f32>, b: &mut Matrix<f32>, c: &mut Matrix<f32> ) 
+{
+    unsafe {
+        let transa = CString::new("N").unwrap();
+        let transb = CString::new("N").unwrap();
+        let ap = a.get_buffer();
+        let bp = b.get_buffer();
+        let cp = c.get_mut_buffer();
+
+        let lda = a.get_column_stride() as int64_t;
+        let ldb = b.get_column_stride() as int64_t;
+        let ldc = c.get_column_stride() as int64_t;
+
+        let m = c.height() as int64_t;
+        let n = b.width() as int64_t;
+        let k = a.width() as int64_t;
+
+        let alpha: f32 = 1.0;
+        let beta: f32 = 1.0;
+
+        sgemm_( transa.as_ptr() as *const c_char, transb.as_ptr() as *const c_char,
+                &m, &n, &k,
+                &alpha as *const c_float,
+                ap as *const c_float, &lda,
+                bp as *const c_float, &ldb,
+                &beta as *const c_float,
+                cp, &ldc );
     }
 }
 
-pub fn test_c_eq_a_b<T:Scalar, At:Mat<T>, Bt:Mat<T>, Ct:Mat<T>>( a: &mut At, b: &mut Bt, c: &mut Ct ) -> T {
+pub fn test_c_eq_a_b<T:Scalar, At:Mat<T>, Bt:Mat<T>, Ct:Mat<T>>( a: &mut At, b: &mut Bt, c: &mut Ct ) -> T::Real {
     let mut ref_gemm: TripleLoop = TripleLoop{};
 
     let m = c.height();
@@ -96,23 +140,94 @@ pub fn gflops(m: usize, n: usize, k: usize, seconds: f64) -> f64 {
     2.0 * nflops / seconds / 1E9
 }
 
+#[cfg(feature="hwloc")]
 pub fn pin_to_core(core: usize) {
+    if let Err(e) = try_pin_to_core(core) {
+        panic!("{}", e);
+    }
+}
+
+#[cfg(feature="hwloc")]
+pub fn try_pin_to_core(core: usize) -> Result<(), MommsError> {
     use self::hwloc::{Topology, CPUBIND_THREAD, ObjectType};
     let mut topo = Topology::new();
     let tid = unsafe { libc::pthread_self() };
 
     let bind_to = {
-        let cores = topo.objects_with_type(&ObjectType::Core).unwrap();
-        match cores.get(core) {
-            Some(val) => val.cpuset().unwrap(),
-            None => panic!("No Core found with id {}", core)
+        let cores = topo.objects_with_type(&ObjectType::Core).unwrap_or(Vec::new());
+        match cores.get(core).and_then(|val| val.cpuset()) {
+            Some(val) => val,
+            None => return Err(MommsError::NoSuchCore{ core: core, n_cores: cores.len() }),
         }
     };
     let _ = topo.set_cpubind_for_thread(tid, bind_to, CPUBIND_THREAD);
+    Ok(())
 }
 
 pub fn capacity_to_aligned_layout<T>(capacity: usize) -> Layout {
+    match try_capacity_to_aligned_layout::<T>(capacity) {
+        Ok(layout) => layout,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+//Fails if the buffer would be larger than the address space
+pub fn try_capacity_to_aligned_layout<T>(capacity: usize) -> Result<Layout, MommsError> {
 //    Layout::new::<T>().repeat_packed(capacity).unwrap().align_to(4096)
     //Layout::array::<T>(capacity).unwrap().align_to(4096)
-    Layout::from_size_align(core::mem::size_of::<T>() * capacity, 4096).unwrap()
+    let elem_size = core::mem::size_of::<T>();
+    elem_size.checked_mul(capacity)
+        .and_then(|size| Layout::from_size_align(size, 4096).ok())
+        .ok_or(MommsError::AllocationFailed{ capacity: capacity, elem_size: elem_size })
+}
+
+//Page-aligned buffers for capacity elements, through the global allocator.
+//The allocator can't hand out zero sized blocks, so an empty buffer is a dangling
+//page-aligned pointer that is never allocated or freed.
+fn dangling_aligned<T>(layout: Layout) -> *mut T {
+    layout.align() as *mut T
+}
+
+pub unsafe fn try_alloc_aligned<T>(capacity: usize) -> Result<*mut T, MommsError> {
+    let layout = try_capacity_to_aligned_layout::<T>(capacity)?;
+    if layout.size() == 0 {
+        return Ok(dangling_aligned(layout));
+    }
+    let buf = alloc::alloc(layout);
+    if buf.is_null() {
+        Err(MommsError::AllocationFailed{ capacity: capacity, elem_size: core::mem::size_of::<T>() })
+    } else {
+        Ok(buf as *mut T)
+    }
+}
+
+pub unsafe fn alloc_aligned<T>(capacity: usize) -> *mut T {
+    match try_alloc_aligned::<T>(capacity) {
+        Ok(buf) => buf,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+pub unsafe fn dealloc_aligned<T>(buf: *mut T, capacity: usize) {
+    let layout = capacity_to_aligned_layout::<T>(capacity);
+    if layout.size() != 0 {
+        alloc::dealloc(buf as *mut u8, layout);
+    }
+}
+
+pub unsafe fn realloc_aligned<T>(buf: *mut T, old_capacity: usize, new_capacity: usize) -> *mut T {
+    let old_layout = capacity_to_aligned_layout::<T>(old_capacity);
+    let new_layout = capacity_to_aligned_layout::<T>(new_capacity);
+    if old_layout.size() == 0 {
+        return alloc_aligned::<T>(new_capacity);
+    }
+    if new_layout.size() == 0 {
+        alloc::dealloc(buf as *mut u8, old_layout);
+        return dangling_aligned(new_layout);
+    }
+    let new_buf = alloc::realloc(buf as *mut u8, old_layout, new_layout.size());
+    if new_buf.is_null() {
+        panic!("{}", MommsError::AllocationFailed{ capacity: new_capacity, elem_size: core::mem::size_of::<T>() });
+    }
+    new_buf as *mut T
 }